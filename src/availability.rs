@@ -0,0 +1,139 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::calendar::ShiftCalendar;
+use crate::shift_gen::{
+    DecidedSchedule, Incomplete, Slot, Staff, StaffGroupList, WeekRuleTable
+};
+use crate::skill::SkillRequirement;
+use crate::solver::Absence;
+
+/// Weekdays and slots a staff can or cannot work every week
+///
+/// a pattern keeps the staff off its days and slots, or with `only` keeps
+/// them off every other one. `from` and `to` limit the dates it is in force
+/// and need a calendar.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AvailabilityPattern {
+    /// 0 is the first day of a rule week
+    pub days: Vec<usize>,
+    pub slots: Vec<Slot>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<NaiveDate>,
+    /// inclusive
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<NaiveDate>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub only: bool,
+}
+
+impl AvailabilityPattern {
+    pub fn matches(&self, day: usize, slot: Slot) -> bool {
+        self.days.contains(&day) && self.slots.contains(&slot)
+    }
+
+    /// `date` is `None` without a calendar, every pattern is then in force
+    pub fn in_force(&self, date: Option<NaiveDate>) -> bool {
+        date.is_none_or(|d| self.from.is_none_or(|from| from <= d) && self.to.is_none_or(|to| d <= to))
+    }
+}
+
+/// Whether the patterns of a staff in force on the date let them work the slot
+///
+/// none of the patterns keeping them off may match, and one of the `only`
+/// ones must when there are any.
+pub fn is_available(staff: &Staff, day: usize, slot: Slot, date: Option<NaiveDate>) -> bool {
+    blocking_patterns(staff, day, slot, date).is_empty()
+}
+
+/// Indices of the patterns of a staff keeping them off the slot: the ones
+/// matching it, and every `only` one in force when none of those matches
+pub fn blocking_patterns(staff: &Staff, day: usize, slot: Slot, date: Option<NaiveDate>) -> Vec<usize> {
+    let in_force = staff.profile.availability.iter().enumerate().filter(|(_, p)| p.in_force(date));
+    let (only, off): (Vec<_>, Vec<_>) = in_force.partition(|(_, p)| p.only);
+    let mut blocking: Vec<usize> = off.iter().filter(|(_, p)| p.matches(day, slot)).map(|(i, _)| *i).collect();
+    if !only.iter().any(|(_, p)| p.matches(day, slot)) {
+        blocking.extend(only.iter().map(|(i, _)| *i));
+    }
+    blocking
+}
+
+/// Hand the holes of unavailable or absent staff to available ones
+///
+/// rotation picks staff regardless of their availability and absences. Every
+/// hole held by a staff who cannot work it, or is away on an absence covering
+/// it, goes to the next staff of the same group in
+/// rotation order who can and is not in the slot yet, then to the staff of
+/// the fallback groups of its rule in order, each from the one rotation would
+/// pick; a hole with fallbacks whose staff is in the slot already moves the
+/// same way. It is left as it is when nobody fits. Returns the number of
+/// holes given to someone else.
+///
+/// a staff is only given a hole when every requirement the slot met stays
+/// met, so that the holes `assign_qualified` gave keep their skills.
+pub fn assign_available<'a>(
+    schedule: &mut DecidedSchedule<'a>,
+    week_rule_table: &WeekRuleTable<'_, Incomplete>,
+    staff_group_list: &'a StaffGroupList,
+    calendar: Option<&ShiftCalendar>,
+    absences: &[Absence],
+    requirements: &[SkillRequirement],
+) -> usize {
+    let mut changed = 0;
+    let cycle = week_rule_table.0.len().max(1);
+    let week_delta = schedule.week_delta;
+    for (index, week_shift) in schedule.weeks.iter_mut().enumerate() {
+        let week = week_delta + index;
+        let week_rule = week_rule_table.0.get(week % cycle);
+        for (day, day_shift) in week_shift.0.iter_mut().enumerate() {
+            let date = calendar.map(|c| c.date_of(week, day));
+            for slot in Slot::ALL {
+                let can_work = |staff: &Staff| {
+                    is_available(staff, day, slot, date)
+                        && !absences.iter().any(|a| {
+                            (a.group_id, a.staff_id) == (staff.get_group_id(), staff.get_id()) && a.covers(week, day, slot)
+                        })
+                };
+                let rules = week_rule.map_or(&[][..], |r| match slot {
+                    Slot::Morning => &r.0[day].shift_morning[..],
+                    Slot::Afternoon => &r.0[day].shift_afternoon[..],
+                });
+                let applying: Vec<&SkillRequirement> = requirements.iter().filter(|r| r.applies(day, slot)).collect();
+                let staff_list = day_shift.slot_mut(slot);
+                for hole in 0..staff_list.len() {
+                    let holder = staff_list[hole];
+                    let fallback = rules.get(hole).map_or(&[][..], |r| &r.fallback[..]);
+                    let doubled = !fallback.is_empty() && staff_list[..hole].iter().any(|s| std::ptr::eq(*s, holder));
+                    if !doubled && can_work(holder) {
+                        continue;
+                    }
+                    let Some(group) = staff_group_list.0.get(holder.get_group_id()) else {
+                        continue;
+                    };
+                    let same_group = (1..group.len()).map(|step| group.pickup_staff((holder.get_id() + step) % group.len()));
+                    let fallback_groups = fallback
+                        .iter()
+                        .filter_map(|&(group_id, id)| Some((staff_group_list.0.get(group_id)?, id)))
+                        .flat_map(|(group, id)| {
+                            (0..group.len()).map(move |step| group.pickup_staff((week / cycle + id + step) % group.len()))
+                        });
+                    let met_before: Vec<bool> = applying.iter().map(|r| r.is_met(staff_list)).collect();
+                    let replacement = same_group
+                        .chain(fallback_groups)
+                        .filter(|s| can_work(s))
+                        .filter(|s| !staff_list.iter().any(|t| std::ptr::eq(*t, *s)))
+                        .find(|s| {
+                            let mut trial = staff_list.clone();
+                            trial[hole] = s;
+                            applying.iter().zip(&met_before).all(|(r, met)| !met || r.is_met(&trial))
+                        });
+                    if let Some(staff) = replacement {
+                        staff_list[hole] = staff;
+                        changed += 1;
+                    }
+                }
+            }
+        }
+    }
+    changed
+}
@@ -0,0 +1,83 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use chrono::NaiveDate;
+use clap::Args;
+
+use shift_calendar::compensatory::{
+    compensation_balances, holiday_work, plan_compensatory_days, CompensationKind
+};
+use shift_calendar::render::WEEKDAY_NAMES;
+use shift_calendar::solver::Generator;
+
+use crate::{load_config, RangeArgs};
+
+#[derive(Args)]
+pub struct CompensateArgs {
+    /// Roster and rule config
+    #[arg(short, long)]
+    config: PathBuf,
+    #[command(flatten)]
+    range: RangeArgs,
+    /// Date the owed days are counted on, today by default
+    #[arg(long)]
+    on: Option<NaiveDate>,
+    /// Write the planned days off to the config with the overrides covering them
+    #[arg(long)]
+    apply: bool,
+}
+
+/// exit code 1 when holiday work is past its window without a day off
+pub fn run(args: CompensateArgs) -> Result<ExitCode, String> {
+    let config = load_config(&args.config)?;
+    let range = args.range.resolve(&config)?;
+    let calendar = range.calendar.ok_or("compensate needs `anchor` in the config")?;
+    let holidays = config.holidays.as_ref().ok_or("compensate needs `[holidays]` in the config")?;
+    let on = args.on.unwrap_or_else(|| chrono::Local::now().date_naive());
+    let schedule = config
+        .generate(&Generator::Rotation, range.week_delta, range.week_gen_range)
+        .map_err(|e| e.to_string())?;
+
+    let works = holiday_work(&schedule, &calendar, holidays, &config.compensatory_days);
+    for work in &works {
+        let given = match work.compensation {
+            Some(c) if c.kind() == CompensationKind::Substitute => format!("振替休日 {}", c.off),
+            Some(c) => format!("代休 {}", c.off),
+            None if work.is_overdue(on) => format!("overdue since {}", work.due),
+            None => format!("owed by {}", work.due),
+        };
+        println!("{} {} {:<16} {}", work.date, WEEKDAY_NAMES[work.day], work.staff.name, given);
+    }
+
+    let balances = compensation_balances(&works, &config.staff_group_list, on);
+    let mut overdue_any = false;
+    for balance in balances.iter().filter(|b| !b.owed.is_empty()) {
+        overdue_any |= !balance.overdue.is_empty();
+        println!("{:<16} owed {}, overdue {}", balance.staff.name, balance.owed.len(), balance.overdue.len());
+    }
+
+    let plans = plan_compensatory_days(&schedule, &config.staff_group_list, &calendar, holidays, &works, &config.absences);
+    for plan in &plans {
+        let c = plan.compensatory_day;
+        let staff = config.staff_group_list.get_staff(c.group_id, c.staff_id).map_or("", |s| s.name.as_str());
+        let takers: Vec<String> = plan
+            .overrides
+            .iter()
+            .map(|o| {
+                let taker = config.staff_group_list.get_staff(o.group_id, o.staff_id).map_or("", |s| s.name.as_str());
+                format!("{} -> {}", o.slot.label(), taker)
+            })
+            .collect();
+        println!("代休 {} {} for {}: {}", c.off, staff, c.worked, takers.join(", "));
+    }
+    let unplanned = works.iter().filter(|w| w.compensation.is_none()).count() - plans.len();
+    if unplanned > 0 {
+        println!("{} holiday work with no day off found in its window", unplanned);
+    }
+    if args.apply && !plans.is_empty() {
+        std::fs::write(&args.config, config.source_with_compensation(&plans))
+            .map_err(|e| format!("{}: {}", args.config.display(), e))?;
+        println!("written to {}", args.config.display());
+    }
+    Ok(if overdue_any { ExitCode::FAILURE } else { ExitCode::SUCCESS })
+}
@@ -0,0 +1,79 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::Args;
+
+use shift_calendar::contract::{format_hours, utilisation, Contract, Measure};
+use shift_calendar::solver::Generator;
+
+use crate::{load_config, RangeArgs};
+
+#[derive(Args)]
+pub struct ContractArgs {
+    /// Roster and rule config
+    #[arg(short, long)]
+    config: PathBuf,
+    #[command(flatten)]
+    range: RangeArgs,
+    /// Report on the schedule of the solver instead of rotation
+    #[arg(long)]
+    solver: bool,
+    /// Also list staff without a contract
+    #[arg(long)]
+    all: bool,
+}
+
+/// exit code 1 when a contract is breached
+pub fn run(args: ContractArgs) -> Result<ExitCode, String> {
+    let config = load_config(&args.config)?;
+    let range = args.range.resolve(&config)?;
+    let generator = if args.solver { Generator::Solver(config.solver_config()) } else { Generator::Rotation };
+    let schedule = config
+        .generate(&generator, range.week_delta, range.week_gen_range)
+        .map_err(|e| e.to_string())?;
+
+    let bounds = |min: Option<u32>, max: Option<u32>, format: &dyn Fn(u32) -> String| {
+        let bound = |b: Option<u32>| b.map_or(String::new(), format);
+        match (min, max) {
+            (None, None) => String::new(),
+            _ => format!("{}..{}", bound(min), bound(max)),
+        }
+    };
+    let no_contract = Contract::default();
+    let mut breached = false;
+    println!("{:<16} {:<10} {:>5} {:>8} {:>7} {:>12}  ", "staff", "period", "days", "limit", "hours", "limit");
+    for u in utilisation(&schedule, &config.staff_group_list, &config.slot_durations, range.calendar.as_ref()) {
+        let contract = match (&u.staff.profile.contract, args.all) {
+            (Some(contract), _) => contract,
+            (None, true) => &no_contract,
+            (None, false) => continue,
+        };
+        for (period, usage) in &u.periods {
+            let (min_days, max_days) = contract.bounds(*period, Measure::Days);
+            let (min_minutes, max_minutes) = contract.bounds(*period, Measure::Hours);
+            let flags: Vec<&str> = u
+                .breaches
+                .iter()
+                .filter(|b| b.period == *period)
+                .map(|b| match (b.measure, b.over) {
+                    (Measure::Days, true) => "too many days",
+                    (Measure::Days, false) => "too few days",
+                    (Measure::Hours, true) => "too many hours",
+                    (Measure::Hours, false) => "too few hours",
+                })
+                .collect();
+            breached |= !flags.is_empty();
+            println!(
+                "{:<16} {:<10} {:>5} {:>8} {:>7} {:>12}  {}",
+                u.staff.name,
+                period.to_string(),
+                usage.days,
+                bounds(min_days, max_days, &|d| d.to_string()),
+                format_hours(usage.minutes),
+                bounds(min_minutes, max_minutes, &format_hours),
+                flags.join(", ")
+            );
+        }
+    }
+    Ok(if breached { ExitCode::FAILURE } else { ExitCode::SUCCESS })
+}
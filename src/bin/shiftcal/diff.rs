@@ -0,0 +1,63 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use chrono::NaiveDate;
+use clap::Args;
+
+use shift_calendar::calendar::ShiftCalendar;
+use shift_calendar::config::ShiftConfig;
+use shift_calendar::diff::{diff_schedules, DiffSide};
+
+use crate::load_config;
+
+#[derive(Args)]
+pub struct DiffArgs {
+    /// Config before the change
+    old: PathBuf,
+    /// Config after the change
+    new: PathBuf,
+    /// First date to compare
+    #[arg(long)]
+    from: NaiveDate,
+    /// Last date to compare
+    #[arg(long)]
+    to: NaiveDate,
+}
+
+/// calendar of a config and the weeks covering `from..=to`
+pub fn horizon(config: &ShiftConfig, path: &std::path::Path, from: NaiveDate, to: NaiveDate) -> Result<(ShiftCalendar, usize, usize), String> {
+    let calendar = config
+        .anchor
+        .map(ShiftCalendar::new)
+        .ok_or_else(|| format!("{}: diff needs `anchor` in the config", path.display()))?;
+    let last_week = calendar
+        .week_of(to)
+        .ok_or_else(|| format!("{}: {} is before the anchor", path.display(), to))?;
+    let first_week = calendar.week_of(from).unwrap_or(0);
+    Ok((calendar, first_week, last_week + 1 - first_week))
+}
+
+/// Exits 1 when the schedules differ, like diff(1)
+pub fn run(args: DiffArgs) -> Result<ExitCode, String> {
+    if args.to < args.from {
+        return Err(format!("--to {} is before --from {}", args.to, args.from));
+    }
+    let old = load_config(&args.old)?;
+    let new = load_config(&args.new)?;
+    let (old_calendar, old_delta, old_range) = horizon(&old, &args.old, args.from, args.to)?;
+    let (new_calendar, new_delta, new_range) = horizon(&new, &args.new, args.from, args.to)?;
+    let old_schedule = old.schedule(old_delta, old_range).map_err(|e| format!("{}: {}", args.old.display(), e))?;
+    let new_schedule = new.schedule(new_delta, new_range).map_err(|e| format!("{}: {}", args.new.display(), e))?;
+
+    let diff = diff_schedules(
+        &DiffSide { schedule: &old_schedule, staff_group_list: &old.staff_group_list, calendar: old_calendar },
+        &DiffSide { schedule: &new_schedule, staff_group_list: &new.staff_group_list, calendar: new_calendar },
+        args.from,
+        args.to,
+    );
+    if diff.is_empty() {
+        return Ok(ExitCode::SUCCESS);
+    }
+    print!("{}", diff.render());
+    Ok(ExitCode::from(1))
+}
@@ -0,0 +1,193 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::Args;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Cell, Paragraph, Row, Table, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+
+use shift_calendar::config::{HolePosition, Severity};
+use shift_calendar::editor::{Direction, RuleEditor};
+use shift_calendar::render::WEEKDAY_NAMES;
+use shift_calendar::shift_gen::Slot;
+
+#[derive(Args)]
+pub struct EditArgs {
+    /// Roster and rule config, saved in place
+    #[arg(short, long)]
+    config: PathBuf,
+    /// Number of weeks previewed and checked
+    #[arg(long, default_value_t = 2)]
+    weeks: usize,
+}
+
+const HELP: &str = "arrows move  tab next hole  a add  d delete  shift+arrows move hole  g/G group  +/- index  \
+                    w/W add/delete week  [/] preview weeks  s save  q quit";
+
+struct App {
+    editor: RuleEditor,
+    message: String,
+    quit_requested: bool,
+}
+
+fn severity_color(severity: Severity) -> Color {
+    match severity {
+        Severity::Error => Color::Red,
+        Severity::Warning => Color::Yellow,
+    }
+}
+
+impl App {
+    fn hole_cell(&self, week_rule_index: usize, day: usize, slot: Slot) -> Cell<'static> {
+        let editor = &self.editor;
+        let cursor = editor.cursor;
+        let is_cursor_cell = (cursor.week_rule_index, cursor.day, cursor.slot) == (week_rule_index, day, slot);
+        let staff_group_list = &editor.config().staff_group_list;
+        let spans: Vec<Span> = editor
+            .holes(week_rule_index, day, slot)
+            .iter()
+            .enumerate()
+            .flat_map(|(index, hole)| {
+                let label = std::iter::once(hole)
+                    .chain(&hole.fallback)
+                    .map(|h| format!("{}:{}", staff_group_list.0.get(h.group).map_or("?", |g| g.get_name()), h.id))
+                    .collect::<Vec<_>>()
+                    .join("|");
+                let mut style = Style::default();
+                let position = HolePosition { week_rule_index, day, slot, index };
+                if let Some(d) = editor.diagnostics_at(position).first() {
+                    style = style.fg(severity_color(d.severity));
+                }
+                if is_cursor_cell && index == cursor.index {
+                    style = style.add_modifier(Modifier::REVERSED);
+                }
+                [Span::styled(label, style), Span::raw(" ")]
+            })
+            .collect();
+        let cell = Cell::from(Line::from(spans));
+        if is_cursor_cell {
+            cell.style(Style::default().bg(Color::DarkGray))
+        } else {
+            cell
+        }
+    }
+
+    fn rule_table(&self) -> Table<'static> {
+        let header = Row::new(std::iter::once("").chain(WEEKDAY_NAMES)).style(Style::default().add_modifier(Modifier::BOLD));
+        let rows: Vec<Row> = (0..self.editor.rule_table().len())
+            .flat_map(|week_rule_index| {
+                let week_style = match self.editor.week_diagnostics(week_rule_index).first() {
+                    Some(d) => Style::default().fg(severity_color(d.severity)),
+                    None => Style::default(),
+                };
+                Slot::ALL.into_iter().map(move |slot| {
+                    let label = Cell::from(format!("W{} {}", week_rule_index, slot.label())).style(week_style);
+                    Row::new(std::iter::once(label).chain((0..7).map(|day| self.hole_cell(week_rule_index, day, slot))))
+                })
+            })
+            .collect();
+        let widths = std::iter::once(Constraint::Length(14)).chain([Constraint::Fill(1); 7]);
+        Table::new(rows, widths).header(header).block(Block::bordered().title(" week rules "))
+    }
+
+    fn diagnostics(&self) -> Paragraph<'static> {
+        let lines: Vec<Line> = self
+            .editor
+            .diagnostics()
+            .iter()
+            .map(|d| Line::styled(d.to_string(), Style::default().fg(severity_color(d.severity))))
+            .collect();
+        let title = format!(" checks ({}) ", lines.len());
+        Paragraph::new(lines).wrap(Wrap { trim: false }).block(Block::bordered().title(title))
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let table_height = self.editor.rule_table().len() as u16 * 2 + 3;
+        let [table_area, bottom_area, status_area] =
+            Layout::vertical([Constraint::Length(table_height), Constraint::Fill(1), Constraint::Length(2)]).areas(frame.area());
+        let [preview_area, diagnostics_area] =
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(bottom_area);
+
+        frame.render_widget(self.rule_table(), table_area);
+        let title = format!(" weeks {}..{} ", self.editor.week_delta, self.editor.week_delta + self.editor.preview_weeks);
+        frame.render_widget(Paragraph::new(self.editor.preview()).block(Block::bordered().title(title)), preview_area);
+        frame.render_widget(self.diagnostics(), diagnostics_area);
+
+        let dirty = if self.editor.is_dirty() { "[modified] " } else { "" };
+        let status = vec![Line::from(format!("{}{}", dirty, self.message)), Line::from(HELP).style(Style::default().fg(Color::DarkGray))];
+        frame.render_widget(Paragraph::new(status), status_area);
+    }
+
+    /// `false` to quit
+    fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> bool {
+        let direction = match code {
+            KeyCode::Up | KeyCode::Char('k') | KeyCode::Char('K') => Some(Direction::Up),
+            KeyCode::Down | KeyCode::Char('j') | KeyCode::Char('J') => Some(Direction::Down),
+            KeyCode::Left | KeyCode::Char('h') | KeyCode::Char('H') => Some(Direction::Left),
+            KeyCode::Right | KeyCode::Char('l') | KeyCode::Char('L') => Some(Direction::Right),
+            _ => None,
+        };
+        let quit_requested = std::mem::take(&mut self.quit_requested);
+        self.message.clear();
+        let editor = &mut self.editor;
+        match (code, direction) {
+            (KeyCode::Char(c), Some(direction)) if c.is_ascii_uppercase() => editor.move_hole(direction),
+            (_, Some(direction)) if modifiers.contains(KeyModifiers::SHIFT) => editor.move_hole(direction),
+            (_, Some(direction)) => editor.move_cursor(direction),
+            (KeyCode::Tab, _) => editor.next_hole(),
+            (KeyCode::Char('a'), _) => editor.add_hole(),
+            (KeyCode::Char('d') | KeyCode::Delete, _) => editor.delete_hole(),
+            (KeyCode::Char('g'), _) => editor.cycle_group(1),
+            (KeyCode::Char('G'), _) => editor.cycle_group(-1),
+            (KeyCode::Char('+') | KeyCode::Char('='), _) => editor.change_id(1),
+            (KeyCode::Char('-'), _) => editor.change_id(-1),
+            (KeyCode::Char('w'), _) => editor.add_week_rule(),
+            (KeyCode::Char('W'), _) => editor.delete_week_rule(),
+            (KeyCode::Char('['), _) => editor.shift_preview(-1),
+            (KeyCode::Char(']'), _) => editor.shift_preview(1),
+            (KeyCode::Char('s'), _) => {
+                self.message = match editor.save() {
+                    Ok(()) => "saved".to_string(),
+                    Err(e) => format!("save failed: {}", e),
+                }
+            }
+            (KeyCode::Char('q') | KeyCode::Esc, _) => {
+                if !editor.is_dirty() || quit_requested {
+                    return false;
+                }
+                self.quit_requested = true;
+                self.message = "unsaved changes, press q again to quit".to_string();
+            }
+            _ => {}
+        }
+        true
+    }
+}
+
+fn run_app(terminal: &mut DefaultTerminal, app: &mut App) -> std::io::Result<()> {
+    loop {
+        terminal.draw(|frame| app.draw(frame))?;
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+            && !app.handle_key(key.code, key.modifiers)
+        {
+            return Ok(());
+        }
+    }
+}
+
+pub fn run(args: EditArgs) -> Result<ExitCode, String> {
+    let mut editor = RuleEditor::open(&args.config).map_err(|e| format!("{}: {}", args.config.display(), e))?;
+    editor.preview_weeks = args.weeks;
+    editor.shift_preview(0);
+    let mut app = App { editor, message: String::new(), quit_requested: false };
+
+    let mut terminal = ratatui::init();
+    let result = run_app(&mut terminal, &mut app);
+    ratatui::restore();
+    result.map_err(|e| e.to_string())?;
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,93 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use chrono::NaiveDate;
+use clap::Args;
+
+use shift_calendar::calendar::ShiftCalendar;
+use shift_calendar::config::{HolePosition, ShiftConfig};
+use shift_calendar::explain::{Derivation, Explainer, Replacement};
+use shift_calendar::history::find_staff;
+use shift_calendar::shift_gen::Slot;
+
+use crate::load_config;
+
+#[derive(Args)]
+pub struct ExplainArgs {
+    /// Roster and rule config
+    #[arg(short, long)]
+    config: PathBuf,
+    #[arg(long)]
+    date: NaiveDate,
+    /// Explain the holes of a staff on the date, employee id or name
+    #[arg(long, conflicts_with_all = ["slot", "hole"])]
+    staff: Option<String>,
+    /// morning or afternoon
+    #[arg(long, required_unless_present = "staff")]
+    slot: Option<Slot>,
+    /// Index of the hole in the slot
+    #[arg(long, default_value_t = 0)]
+    hole: usize,
+}
+
+fn print_derivation(derivation: &Derivation<'_>, config: &ShiftConfig) {
+    println!("{}", derivation.heading());
+    for step in derivation.steps(&config.staff_group_list) {
+        println!("  {}", step);
+    }
+    let file = config.path.as_ref().map_or(String::new(), |p| p.display().to_string());
+    let position = HolePosition {
+        week_rule_index: derivation.week_rule_index,
+        day: derivation.day,
+        slot: derivation.slot,
+        index: derivation.hole,
+    };
+    if let Some(location) = config.hole_location(position) {
+        println!("  hole defined at {}:{}:{}", file, location.line, location.column);
+    }
+    if let Some(location) = derivation.replacement.and_then(|r| match r {
+        Replacement::Override { index, .. } => config.override_location(index),
+        Replacement::Reassigned { .. } => None,
+    }) {
+        println!("  override defined at {}:{}:{}", file, location.line, location.column);
+    }
+}
+
+pub fn run(args: ExplainArgs) -> Result<ExitCode, String> {
+    let config = load_config(&args.config)?;
+    let calendar = config
+        .anchor
+        .map(ShiftCalendar::new)
+        .ok_or("explain needs `anchor` in the config")?;
+    if calendar.position_of(args.date).is_none() {
+        return Err(format!("{} is before the anchor", args.date));
+    }
+    let week_rule_table = config.week_rule_table();
+    let explainer = Explainer::new(&week_rule_table, &config.staff_group_list, &config.overrides, Some(calendar))
+        .with_absences(&config.absences);
+
+    match (&args.staff, args.slot) {
+        (Some(key), _) => {
+            let staff = find_staff(&config.staff_group_list, key)
+                .map_err(|_| format!("no single staff matches `{}`", key))?;
+            let derivations = explainer.explain_staff(staff, args.date);
+            if derivations.is_empty() {
+                println!("{} has no hole on {}", staff.name, args.date);
+            }
+            for (i, derivation) in derivations.iter().enumerate() {
+                if i > 0 {
+                    println!();
+                }
+                print_derivation(derivation, &config);
+            }
+        }
+        (None, Some(slot)) => {
+            let derivation = explainer
+                .explain_date(args.date, slot, args.hole)
+                .ok_or_else(|| format!("no {} hole {} on {}", slot.label(), args.hole, args.date))?;
+            print_derivation(&derivation, &config);
+        }
+        (None, None) => unreachable!("clap requires --slot without --staff"),
+    }
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,108 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use chrono::Utc;
+use clap::{Args, ValueEnum};
+
+use shift_calendar::document::{GenerationParams, Metadata, ScheduleDocument};
+use shift_calendar::export::{schedule_to_csv, schedule_to_ics};
+use shift_calendar::render::{render_day_slot_grid, render_staff_day_matrix, RenderOptions};
+use shift_calendar::solver::Generator;
+
+use crate::{load_config, write_output, RangeArgs};
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    /// day × slot grid
+    Table,
+    /// staff × day matrix
+    Matrix,
+    Csv,
+    Json,
+    Ics,
+}
+
+#[derive(Args)]
+pub struct GenerateArgs {
+    /// Roster and rule config
+    #[arg(short, long)]
+    config: PathBuf,
+    #[command(flatten)]
+    range: RangeArgs,
+    #[arg(short, long, value_enum, default_value_t = Format::Table)]
+    format: Format,
+    /// Colour the table formats
+    #[arg(long)]
+    color: bool,
+    /// Write to a file instead of stdout
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    /// Fill the holes by constraint search instead of rotation
+    ///
+    /// The search keeps quotas, labour limits, `[checks.overtime]`, the
+    /// contract maximums, absences and availability. Contract minimums and
+    /// `[[checks.skill]]` are not kept, `validate --solver` reports their
+    /// breaches.
+    #[arg(long, conflicts_with = "qualified")]
+    solver: bool,
+    /// Skip staff without the skills `[[checks.skill]]` requires
+    #[arg(long)]
+    qualified: bool,
+    /// Seed of the solver local search
+    #[arg(long, requires = "solver")]
+    seed: Option<u64>,
+    /// Moves tried by the solver local search
+    #[arg(long, requires = "solver")]
+    iterations: Option<usize>,
+}
+
+pub fn run(args: GenerateArgs) -> Result<ExitCode, String> {
+    let config = load_config(&args.config)?;
+    let range = args.range.resolve(&config)?;
+    let staff_group_list = &config.staff_group_list;
+    let week_rule_table = config.week_rule_table();
+    let generator = if args.solver {
+        let mut solver_config = config.solver_config();
+        solver_config.seed = args.seed.unwrap_or(solver_config.seed);
+        solver_config.iterations = args.iterations.unwrap_or(solver_config.iterations);
+        Generator::Solver(solver_config)
+    } else if args.qualified {
+        config.qualified_rotation()
+    } else {
+        Generator::Rotation
+    };
+    let schedule = config
+        .generate(&generator, range.week_delta, range.week_gen_range)
+        .map_err(|e| e.to_string())?;
+
+    let options = RenderOptions::new()
+        .with_color(args.color)
+        .with_start_date(range.calendar.map(|c| c.date_of(range.week_delta, 0)));
+    let content = match args.format {
+        Format::Table => render_day_slot_grid(&schedule.weeks, &options),
+        Format::Matrix => render_staff_day_matrix(&schedule.weeks, staff_group_list, &options),
+        Format::Csv => schedule_to_csv(&schedule, staff_group_list, range.calendar.as_ref()),
+        Format::Json => {
+            let params = GenerationParams {
+                week_delta: range.week_delta,
+                week_gen_range: range.week_gen_range,
+                start_date: range.calendar.map(|c| c.date_of(range.week_delta, 0)),
+                generator: generator.clone(),
+                absences: config.absences.clone(),
+            };
+            let metadata = Metadata {
+                title: args.config.display().to_string(),
+                created_at: Some(Utc::now().to_rfc3339()),
+                note: None,
+            };
+            ScheduleDocument::new(metadata, staff_group_list, &week_rule_table, params, &schedule.weeks, &config.overrides)
+                .to_json()
+        }
+        Format::Ics => {
+            let calendar = range.calendar.ok_or("ics output needs `anchor` in the config")?;
+            schedule_to_ics(&schedule, &calendar)
+        }
+    };
+    write_output(args.output.as_ref(), content.as_bytes())?;
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,63 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::Args;
+
+use shift_calendar::contract::format_hours;
+use shift_calendar::hours::working_hours;
+use shift_calendar::render::WEEKDAY_NAMES;
+use shift_calendar::solver::Generator;
+
+use crate::{load_config, RangeArgs};
+
+#[derive(Args)]
+pub struct HoursArgs {
+    /// Roster and rule config
+    #[arg(short, long)]
+    config: PathBuf,
+    #[command(flatten)]
+    range: RangeArgs,
+    /// Report on the schedule of the solver instead of rotation
+    #[arg(long)]
+    solver: bool,
+}
+
+/// limits of `[checks.overtime]`, the statutory ones without it
+pub fn run(args: HoursArgs) -> Result<ExitCode, String> {
+    let config = load_config(&args.config)?;
+    let range = args.range.resolve(&config)?;
+    let generator = if args.solver { Generator::Solver(config.solver_config()) } else { Generator::Rotation };
+    let schedule = config
+        .generate(&generator, range.week_delta, range.week_gen_range)
+        .map_err(|e| e.to_string())?;
+    let limits = config.checks.overtime.as_ref().map(|o| o.limits).unwrap_or_default();
+
+    println!("{:<16} {:<10} {:>7} {:>8}  ", "staff", "period", "hours", "overtime");
+    let reports = working_hours(&schedule, &config.staff_group_list, &config.slot_durations, &limits, range.calendar.as_ref());
+    for report in &reports {
+        let name = &report.staff.name;
+        for week in &report.weeks {
+            let long_days: Vec<String> = report
+                .days
+                .iter()
+                .filter(|d| d.week == week.week && d.overtime > 0)
+                .map(|d| format!("{} {}", WEEKDAY_NAMES[d.day], format_hours(d.minutes)))
+                .collect();
+            let overtime = if week.overtime() > 0 { format_hours(week.overtime()) } else { String::new() };
+            let period = format!("week {}", week.week);
+            println!("{:<16} {:<10} {:>7} {:>8}  {}", name, period, format_hours(week.minutes), overtime, long_days.join(", "));
+        }
+        for (month, minutes) in &report.months {
+            println!("{:<16} {:<10} {:>7}", name, month.to_string(), format_hours(*minutes));
+        }
+    }
+    let overtime: u32 = reports.iter().map(|r| r.overtime()).sum();
+    println!(
+        "total {}, overtime {} (limits {} a day, {} a week)",
+        format_hours(reports.iter().map(|r| r.minutes()).sum()),
+        format_hours(overtime),
+        format_hours(limits.daily_minutes),
+        format_hours(limits.weekly_minutes)
+    );
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,60 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use chrono::NaiveDate;
+use clap::Args;
+
+use shift_calendar::leave::MANDATORY_DAYS;
+
+use crate::load_config;
+
+#[derive(Args)]
+pub struct LeaveArgs {
+    /// Roster and rule config
+    #[arg(short, long)]
+    config: PathBuf,
+    /// Date of the balances, today by default
+    #[arg(long)]
+    on: Option<NaiveDate>,
+}
+
+/// exit code 1 when a year ended short of the mandatory days
+pub fn run(args: LeaveArgs) -> Result<ExitCode, String> {
+    let config = load_config(&args.config)?;
+    let on = args.on.unwrap_or_else(|| chrono::Local::now().date_naive());
+
+    let mut missed_any = false;
+    println!("{:<16} {:<10} {:>7} {:>5} {:>7} {:>5}  mandatory", "staff", "joined", "granted", "used", "balance", "lost");
+    for staff in config.staff_group_list.0.iter().flat_map(|g| g.iter()) {
+        let (Some(joined), Some(ledger)) = (staff.profile.join_date, config.leave_ledger(staff, on)) else {
+            continue;
+        };
+        let valid = ledger.grants.iter().filter(|g| g.expires > on);
+        let granted: u32 = valid.clone().map(|g| g.days).sum();
+        let used: u32 = valid.map(|g| g.used).sum();
+        let missed = ledger.missed();
+        missed_any |= !missed.is_empty();
+        let mut notes = vec![];
+        if let Some(year) = ledger.mandatory.iter().rfind(|m| m.grant_date <= on) {
+            let state = if year.is_met() { "" } else if year.deadline < on { " missed" } else { " at risk" };
+            notes.push(format!("{}/{} by {}{}", year.taken, MANDATORY_DAYS, year.deadline, state));
+        }
+        for year in missed.iter().filter(|m| ledger.mandatory.last() != Some(*m)) {
+            notes.push(format!("missed {}/{} by {}", year.taken, MANDATORY_DAYS, year.deadline));
+        }
+        if !ledger.unpaid.is_empty() {
+            notes.push(format!("{} days over the balance", ledger.unpaid.len()));
+        }
+        println!(
+            "{:<16} {:<10} {:>7} {:>5} {:>7} {:>5}  {}",
+            staff.name,
+            joined.to_string(),
+            granted,
+            used,
+            ledger.balance(),
+            ledger.lost(),
+            notes.join(", ")
+        );
+    }
+    Ok(if missed_any { ExitCode::FAILURE } else { ExitCode::SUCCESS })
+}
@@ -0,0 +1,167 @@
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use chrono::NaiveDate;
+use clap::{Args, Parser, Subcommand};
+
+use shift_calendar::calendar::ShiftCalendar;
+use shift_calendar::config::ShiftConfig;
+
+mod compensate;
+mod contract;
+mod diff;
+#[cfg(feature = "tui")]
+mod edit;
+mod explain;
+mod generate;
+mod hours;
+mod leave;
+mod offset;
+mod period;
+mod query;
+mod repair;
+mod satisfaction;
+mod swap;
+mod synth;
+mod validate;
+
+#[derive(Parser)]
+#[command(name = "shiftcal", version, about = "Generate shifts according to week rules")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Generate the schedule of a range of weeks
+    Generate(generate::GenerateArgs),
+    /// Run the configured checkers over a range of weeks
+    Validate(validate::ValidateArgs),
+    /// Who works on a date
+    Who(query::WhoArgs),
+    /// When a staff works next
+    When(query::WhenArgs),
+    /// Compare the schedules of two configs between two dates
+    Diff(diff::DiffArgs),
+    /// Show how the staff of a hole is derived
+    Explain(explain::ExplainArgs),
+    /// Holiday work of each staff, the days off given for it and 代休 to plan
+    Compensate(compensate::CompensateArgs),
+    /// Days and hours of each staff per week and month against their contract
+    Contract(contract::ContractArgs),
+    /// Hours worked by each staff per week and month, and statutory overtime
+    Hours(hours::HoursArgs),
+    /// Paid leave granted, used and left of each staff, and the mandatory 5 days
+    Leave(leave::LeaveArgs),
+    /// Find the start of the rule table balancing a past schedule best
+    ///
+    /// Offsets are scored on the weeks `generate` makes, with availability,
+    /// fallbacks, absences and overrides.
+    Offset(offset::OffsetArgs),
+    /// Find the repeat period of each group and the weekdays each staff gets
+    ///
+    /// The period is the one of the schedule `generate` makes, with
+    /// availability, fallbacks, absences and overrides.
+    Period(period::PeriodArgs),
+    /// Adapt the schedule of a config to a changed config, moving as few assignments as possible
+    Repair(repair::RepairArgs),
+    /// Report how well a schedule meets the preferences of each staff
+    Satisfaction(satisfaction::SatisfactionArgs),
+    /// Check a shift swap or giveaway and apply it as overrides
+    Swap(swap::SwapArgs),
+    /// Build the week rules from the needs of each group
+    Synth(synth::SynthArgs),
+    /// Edit the week rules in a terminal UI
+    #[cfg(feature = "tui")]
+    Edit(edit::EditArgs),
+}
+
+/// Weeks to work on
+#[derive(Args)]
+struct RangeArgs {
+    /// Date in the first week, needs `anchor` in the config
+    #[arg(long, conflicts_with = "week_delta")]
+    start: Option<NaiveDate>,
+    /// Absolute index of the first week
+    #[arg(long)]
+    week_delta: Option<usize>,
+    /// Number of weeks
+    #[arg(long, default_value_t = 4)]
+    weeks: usize,
+}
+
+/// Resolved range of weeks
+struct WeekRange {
+    week_delta: usize,
+    week_gen_range: usize,
+    calendar: Option<ShiftCalendar>,
+}
+
+impl RangeArgs {
+    fn resolve(&self, config: &ShiftConfig) -> Result<WeekRange, String> {
+        let calendar = config.anchor.map(ShiftCalendar::new);
+        let week_delta = match (self.start, self.week_delta) {
+            (Some(start), _) => calendar
+                .ok_or("--start needs `anchor` in the config")?
+                .week_of(start)
+                .ok_or_else(|| format!("{} is before the anchor", start))?,
+            (None, Some(week_delta)) => week_delta,
+            (None, None) => 0,
+        };
+        Ok(WeekRange { week_delta, week_gen_range: self.weeks, calendar })
+    }
+}
+
+fn load_config(path: &Path) -> Result<ShiftConfig, String> {
+    let config = ShiftConfig::load(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    // gen_shift divides by these lengths
+    if config.rule_table.is_empty() {
+        return Err(format!("{}: no `[[week]]` rule", path.display()));
+    }
+    if let Some(group) = config.staff_group_list.0.iter().find(|g| g.len() == 0) {
+        return Err(format!("{}: group `{}` has no staff", path.display(), group.get_name()));
+    }
+    Ok(config)
+}
+
+fn write_output(output: Option<&PathBuf>, content: &[u8]) -> Result<(), String> {
+    match output {
+        Some(path) => std::fs::write(path, content).map_err(|e| format!("{}: {}", path.display(), e)),
+        None => {
+            use std::io::Write;
+            std::io::stdout().write_all(content).map_err(|e| e.to_string())
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Command::Generate(args) => generate::run(args),
+        Command::Validate(args) => validate::run(args),
+        Command::Who(args) => query::run_who(args),
+        Command::When(args) => query::run_when(args),
+        Command::Diff(args) => diff::run(args),
+        Command::Explain(args) => explain::run(args),
+        Command::Compensate(args) => compensate::run(args),
+        Command::Contract(args) => contract::run(args),
+        Command::Hours(args) => hours::run(args),
+        Command::Leave(args) => leave::run(args),
+        Command::Offset(args) => offset::run(args),
+        Command::Period(args) => period::run(args),
+        Command::Repair(args) => repair::run(args),
+        Command::Satisfaction(args) => satisfaction::run(args),
+        Command::Swap(args) => swap::run(args),
+        Command::Synth(args) => synth::run(args),
+        #[cfg(feature = "tui")]
+        Command::Edit(args) => edit::run(args),
+    };
+    match result {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
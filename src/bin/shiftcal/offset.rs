@@ -0,0 +1,76 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::Args;
+
+use shift_calendar::document::rule_table_to_doc;
+use shift_calendar::history::import_history_csv;
+use shift_calendar::offset::{imbalance, StartOffset};
+use shift_calendar::shift_gen::StaffGroupList;
+use shift_calendar::solver::Generator;
+use shift_calendar::stats::AssignmentCounts;
+
+use crate::{load_config, RangeArgs};
+
+#[derive(Args)]
+pub struct OffsetArgs {
+    /// Roster and rule config
+    #[arg(short, long)]
+    config: PathBuf,
+    /// Past schedule, csv with date, slot and staff columns
+    #[arg(long)]
+    history: PathBuf,
+    /// Weeks to balance, the coming year for instance
+    #[command(flatten)]
+    range: RangeArgs,
+    /// Number of offsets listed
+    #[arg(long, default_value_t = 3)]
+    top: usize,
+    /// Write the rule table of the best offset to the config
+    #[arg(long)]
+    write: bool,
+}
+
+fn describe(offset: &StartOffset, staff_group_list: &StaffGroupList) -> String {
+    let shifts: Vec<String> = staff_group_list
+        .0
+        .iter()
+        .zip(&offset.group_shifts)
+        .map(|(group, shift)| format!("{}+{}", group.get_name(), shift))
+        .collect();
+    format!("rule {} first, {}", offset.rule_offset, shifts.join(" "))
+}
+
+pub fn run(args: OffsetArgs) -> Result<ExitCode, String> {
+    let config = load_config(&args.config)?;
+    let range = args.range.resolve(&config)?;
+    let calendar = range.calendar.ok_or("offset needs `anchor` in the config to place the history")?;
+    let staff_group_list = &config.staff_group_list;
+    let file = std::fs::File::open(&args.history).map_err(|e| format!("{}: {}", args.history.display(), e))?;
+    let history = import_history_csv(file, staff_group_list, &calendar).map_err(|errors| {
+        let lines: Vec<String> = errors.iter().map(|e| format!("{}: {}", args.history.display(), e)).collect();
+        lines.join("\n")
+    })?;
+    let history = AssignmentCounts::from_schedule(&history);
+
+    let week_rule_table = config.week_rule_table();
+    // the generated weeks, as `rank_offsets` scores the offsets
+    let mut current = history.clone();
+    current.add_schedule(
+        &config.generate(&Generator::Rotation, range.week_delta, range.week_gen_range).map_err(|e| e.to_string())?,
+    );
+    println!("current imbalance {}", imbalance(&current, staff_group_list).iter().sum::<u64>());
+
+    let scores = config.rank_offsets(&history, range.week_delta, range.week_gen_range);
+    for score in scores.iter().take(args.top) {
+        println!("{:>16}  {}", score.cost(), describe(&score.offset, staff_group_list));
+    }
+    if args.write {
+        let best = scores.first().ok_or("no offset to write")?;
+        let rule_table = rule_table_to_doc(&best.offset.apply(&week_rule_table, staff_group_list));
+        std::fs::write(&args.config, config.source_with_rule_table(&rule_table))
+            .map_err(|e| format!("{}: {}", args.config.display(), e))?;
+        println!("written to {}", args.config.display());
+    }
+    Ok(ExitCode::SUCCESS)
+}
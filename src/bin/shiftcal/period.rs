@@ -0,0 +1,46 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::Args;
+
+use shift_calendar::render::WEEKDAY_NAMES;
+use shift_calendar::stats::WeekdaySlotCounts;
+
+use crate::load_config;
+
+#[derive(Args)]
+pub struct PeriodArgs {
+    /// Roster and rule config
+    #[arg(short, long)]
+    config: PathBuf,
+}
+
+/// `morning/afternoon` counts of each weekday
+fn count_cells(counts: &WeekdaySlotCounts) -> String {
+    counts.iter().map(|[m, a]| format!(" {:>7}", format!("{}/{}", m, a))).collect()
+}
+
+pub fn run(args: PeriodArgs) -> Result<ExitCode, String> {
+    let config = load_config(&args.config)?;
+    let analysis = config.analyze_period().ok_or("the rule table is empty or a group has no staff")?;
+
+    println!("the schedule repeats every {} weeks of the {} analysed", analysis.period, analysis.weeks);
+    for group in &analysis.groups {
+        let name = config.staff_group_list.0[group.group_id].get_name();
+        println!();
+        println!("group {}: {} staff, period {} weeks", name, group.staff.len(), group.period);
+        let header: String = WEEKDAY_NAMES.iter().map(|d| format!(" {:>7}", d)).collect();
+        println!("  {:<12}{}", "am/pm", header);
+        println!("  {:<12}{}", "holes", count_cells(&group.holes));
+        for (staff_id, coverage) in group.staff.iter().enumerate() {
+            let never: Vec<String> = group
+                .never(staff_id)
+                .iter()
+                .map(|(day, slot)| format!("{} {}", WEEKDAY_NAMES[*day], slot.label()))
+                .collect();
+            let never = if never.is_empty() { String::new() } else { format!("  never: {}", never.join(", ")) };
+            println!("  {:<12}{}{}", coverage.staff.name, count_cells(&coverage.counts), never);
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
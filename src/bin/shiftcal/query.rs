@@ -0,0 +1,84 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use chrono::NaiveDate;
+use clap::Args;
+
+use shift_calendar::calendar::ShiftCalendar;
+use shift_calendar::config::ShiftConfig;
+use shift_calendar::history::find_staff;
+use shift_calendar::query::{Assignment, ShiftQuery};
+use shift_calendar::render::WEEKDAY_NAMES;
+use shift_calendar::shift_gen::{Slot, StaffGroupList};
+
+use crate::load_config;
+
+#[derive(Args)]
+pub struct WhoArgs {
+    /// Roster and rule config
+    #[arg(short, long)]
+    config: PathBuf,
+    #[arg(long)]
+    date: NaiveDate,
+    /// morning or afternoon, every slot when omitted
+    #[arg(long)]
+    slot: Option<Slot>,
+}
+
+#[derive(Args)]
+pub struct WhenArgs {
+    /// Roster and rule config
+    #[arg(short, long)]
+    config: PathBuf,
+    /// Employee id or name
+    #[arg(long)]
+    staff: String,
+    /// First date to look at, today when omitted
+    #[arg(long)]
+    from: Option<NaiveDate>,
+    /// Last date to look at
+    #[arg(long, conflicts_with = "next")]
+    to: Option<NaiveDate>,
+    /// Number of assignments to list
+    #[arg(long, default_value_t = 5)]
+    next: usize,
+}
+
+fn calendar(config: &ShiftConfig) -> Result<ShiftCalendar, String> {
+    config
+        .anchor
+        .map(ShiftCalendar::new)
+        .ok_or_else(|| "queries need `anchor` in the config".to_string())
+}
+
+fn print_assignments(assignments: &[Assignment<'_>], staff_group_list: &StaffGroupList) {
+    for a in assignments {
+        let group = staff_group_list.0.get(a.staff.get_group_id()).map_or("", |g| g.get_name());
+        println!("{} {} {:<9} {} ({})", a.date, WEEKDAY_NAMES[a.day], a.slot.label(), a.staff.name, group);
+    }
+}
+
+pub fn run_who(args: WhoArgs) -> Result<ExitCode, String> {
+    let config = load_config(&args.config)?;
+    let week_rule_table = config.week_rule_table();
+    let query = ShiftQuery::new(&week_rule_table, &config.staff_group_list, calendar(&config)?).with_overrides(&config.overrides)
+        .with_absences(&config.absences);
+    print_assignments(&query.who(args.date, args.slot), &config.staff_group_list);
+    Ok(ExitCode::SUCCESS)
+}
+
+pub fn run_when(args: WhenArgs) -> Result<ExitCode, String> {
+    let config = load_config(&args.config)?;
+    let staff = find_staff(&config.staff_group_list, &args.staff)
+        .map_err(|_| format!("no single staff matches `{}`", args.staff))?;
+    let week_rule_table = config.week_rule_table();
+    let query = ShiftQuery::new(&week_rule_table, &config.staff_group_list, calendar(&config)?).with_overrides(&config.overrides)
+        .with_absences(&config.absences);
+    let from = args.from.unwrap_or_else(|| chrono::Local::now().date_naive());
+    let assignments = match args.to {
+        Some(to) => query.assignments_between(staff, from, to),
+        None => query.next_assignments(staff, from, args.next),
+    };
+    print_assignments(&assignments, &config.staff_group_list);
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,73 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use chrono::NaiveDate;
+use clap::Args;
+
+use shift_calendar::diff::{diff_schedules, DiffSide};
+use shift_calendar::render::{render_day_slot_grid, RenderOptions};
+use shift_calendar::repair::Published;
+
+use crate::diff::horizon;
+use crate::load_config;
+
+#[derive(Args)]
+pub struct RepairArgs {
+    /// Config the published schedule was generated from
+    published: PathBuf,
+    /// Config with the change: absences, roster or week rules
+    config: PathBuf,
+    /// First date to repair
+    #[arg(long)]
+    from: NaiveDate,
+    /// Last date to repair
+    #[arg(long)]
+    to: NaiveDate,
+    /// Seed of the solver local search
+    #[arg(long)]
+    seed: Option<u64>,
+    /// Moves tried by the solver local search
+    #[arg(long)]
+    iterations: Option<usize>,
+}
+
+/// Prints the repaired schedule and the changes to the published one
+pub fn run(args: RepairArgs) -> Result<ExitCode, String> {
+    if args.to < args.from {
+        return Err(format!("--to {} is before --from {}", args.to, args.from));
+    }
+    let old = load_config(&args.published)?;
+    let new = load_config(&args.config)?;
+    if old.anchor != new.anchor {
+        return Err("the configs must have the same `anchor`".to_string());
+    }
+    let (calendar, week_delta, week_gen_range) = horizon(&new, &args.config, args.from, args.to)?;
+    let published = old.schedule(week_delta, week_gen_range).map_err(|e| format!("{}: {}", args.published.display(), e))?;
+
+    let mut solver_config = new.solver_config();
+    solver_config.seed = args.seed.unwrap_or(solver_config.seed);
+    solver_config.iterations = args.iterations.unwrap_or(solver_config.iterations);
+    let repaired = new
+        .repair(
+            &Published { schedule: &published, staff_group_list: &old.staff_group_list },
+            &solver_config,
+            week_delta,
+            week_gen_range,
+        )
+        .map_err(|e| e.to_string())?;
+
+    let options = RenderOptions::new().with_start_date(Some(calendar.date_of(week_delta, 0)));
+    print!("{}", render_day_slot_grid(&repaired.weeks, &options));
+    let diff = diff_schedules(
+        &DiffSide { schedule: &published, staff_group_list: &old.staff_group_list, calendar },
+        &DiffSide { schedule: &repaired, staff_group_list: &new.staff_group_list, calendar },
+        args.from,
+        args.to,
+    );
+    let changed: usize = diff.by_date.iter().map(|slot| slot.added.len().max(slot.removed.len())).sum();
+    println!("\n{} assignments changed", changed);
+    if !diff.is_empty() {
+        print!("\n{}", diff.render());
+    }
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,41 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::Args;
+
+use shift_calendar::preference::{satisfaction_report, score_schedule};
+use shift_calendar::solver::Generator;
+
+use crate::{load_config, RangeArgs};
+
+#[derive(Args)]
+pub struct SatisfactionArgs {
+    /// Roster and rule config
+    #[arg(short, long)]
+    config: PathBuf,
+    #[command(flatten)]
+    range: RangeArgs,
+    /// Report on the schedule of the solver instead of rotation
+    #[arg(long)]
+    solver: bool,
+}
+
+pub fn run(args: SatisfactionArgs) -> Result<ExitCode, String> {
+    let config = load_config(&args.config)?;
+    let range = args.range.resolve(&config)?;
+    let generator = if args.solver { Generator::Solver(config.solver_config()) } else { Generator::Rotation };
+    let schedule = config
+        .generate(&generator, range.week_delta, range.week_gen_range)
+        .map_err(|e| e.to_string())?;
+
+    println!("{:<16} {:<12} {:>6} {:>6} {:>8} {:>6}", "staff", "group", "shifts", "liked", "disliked", "score");
+    for s in satisfaction_report(&schedule, &config.staff_group_list, range.calendar.as_ref()) {
+        let group = config.staff_group_list.0.get(s.staff.get_group_id()).map_or("", |g| g.get_name());
+        println!(
+            "{:<16} {:<12} {:>6} {:>6} {:>8} {:>6}",
+            s.staff.name, group, s.assignments, s.liked, s.disliked, s.score
+        );
+    }
+    println!("total score {}", score_schedule(&schedule, range.calendar.as_ref()));
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,104 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use chrono::NaiveDate;
+use clap::Args;
+
+use shift_calendar::calendar::ShiftCalendar;
+use shift_calendar::history::find_staff;
+use shift_calendar::render::WEEKDAY_NAMES;
+use shift_calendar::shift_gen::Slot;
+use shift_calendar::swap::{CauseOfSwapErr, HoleRef, SwapEngine, SwapProposal};
+
+use crate::load_config;
+
+#[derive(Args)]
+pub struct SwapArgs {
+    /// Roster and rule config
+    #[arg(short, long)]
+    config: PathBuf,
+    /// Date of the hole to give away or trade
+    #[arg(long)]
+    date: NaiveDate,
+    /// morning or afternoon
+    #[arg(long)]
+    slot: Slot,
+    /// Index of the hole in the slot
+    #[arg(long, default_value_t = 0)]
+    hole: usize,
+    /// Give the hole to this staff, employee id or name
+    #[arg(long, conflicts_with_all = ["with_date", "candidates"])]
+    to: Option<String>,
+    /// Trade the hole for the one on this date
+    #[arg(long, requires = "with_slot", conflicts_with = "candidates")]
+    with_date: Option<NaiveDate>,
+    #[arg(long)]
+    with_slot: Option<Slot>,
+    #[arg(long, default_value_t = 0)]
+    with_hole: usize,
+    /// List the colleagues who could take or trade the hole
+    #[arg(long)]
+    candidates: bool,
+    /// Write the change to the config as overrides when it passes the checks
+    #[arg(long, conflicts_with = "candidates")]
+    apply: bool,
+}
+
+fn hole_ref(calendar: &ShiftCalendar, date: NaiveDate, slot: Slot, hole: usize) -> Result<HoleRef, String> {
+    let (week, day) = calendar.position_of(date).ok_or_else(|| format!("{} is before the anchor", date))?;
+    Ok(HoleRef { week, day, slot, hole })
+}
+
+/// Exits 1 when the change breaks a check
+pub fn run(args: SwapArgs) -> Result<ExitCode, String> {
+    let config = load_config(&args.config)?;
+    let calendar = config.anchor.map(ShiftCalendar::new).ok_or("swap needs `anchor` in the config")?;
+    let hole = hole_ref(&calendar, args.date, args.slot, args.hole)?;
+    let engine = SwapEngine::new(&config);
+
+    if args.candidates {
+        let candidates = engine.candidates(hole).map_err(|e| e.to_string())?;
+        for candidate in candidates {
+            let swaps: Vec<String> = candidate
+                .swaps
+                .iter()
+                .map(|h| format!("{} {}", calendar.date_of(h.week, h.day), h.slot.label()))
+                .collect();
+            let take = if candidate.can_take { "take" } else { "    " };
+            println!("{:<12} {} swap: {}", candidate.staff.name, take, swaps.join(", "));
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let proposal = match (&args.to, args.with_date, args.with_slot) {
+        (Some(key), _, _) => {
+            let staff = find_staff(&config.staff_group_list, key)
+                .map_err(|_| format!("no single staff matches `{}`", key))?;
+            SwapProposal::Giveaway { hole, group_id: staff.get_group_id(), staff_id: staff.get_id() }
+        }
+        (None, Some(date), Some(slot)) => {
+            SwapProposal::Swap { a: hole, b: hole_ref(&calendar, date, slot, args.with_hole)? }
+        }
+        _ => return Err("give --to, --with-date or --candidates".to_string()),
+    };
+    let overrides = match engine.check(&proposal) {
+        Ok(overrides) => overrides,
+        Err(e) if matches!(e.reason, CauseOfSwapErr::Infeasible(_)) => {
+            println!("{}", e);
+            return Ok(ExitCode::from(1));
+        }
+        Err(e) => return Err(e.to_string()),
+    };
+    for o in &overrides {
+        let staff = config.staff_group_list.get_staff(o.group_id, o.staff_id).map_or("", |s| s.name.as_str());
+        println!("{} {} {} hole {} -> {}", calendar.date_of(o.week, o.day), WEEKDAY_NAMES[o.day], o.slot.label(), o.hole, staff);
+    }
+    if args.apply {
+        std::fs::write(&args.config, config.source_with_overrides(&overrides))
+            .map_err(|e| format!("{}: {}", args.config.display(), e))?;
+        println!("written to {}", args.config.display());
+    } else {
+        println!("passes the checks, --apply to write it");
+    }
+    Ok(ExitCode::SUCCESS)
+}
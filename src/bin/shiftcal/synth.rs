@@ -0,0 +1,53 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::Args;
+
+use shift_calendar::config::ShiftConfig;
+use shift_calendar::document::rule_table_to_doc;
+use shift_calendar::synth::synthesize_rule_table;
+
+use crate::write_output;
+
+#[derive(Args)]
+pub struct SynthArgs {
+    /// Roster, `[[need]]` and `[[checks.quota]]` config
+    #[arg(short, long)]
+    config: PathBuf,
+    /// Number of week rules, the shortest one using every staff by default
+    #[arg(long)]
+    cycle: Option<usize>,
+    /// Write the config with the new `[[week]]` tables to a file instead of stdout
+    #[arg(short, long, conflicts_with = "in_place")]
+    output: Option<PathBuf>,
+    /// Replace the `[[week]]` tables of the config file
+    #[arg(long)]
+    in_place: bool,
+}
+
+/// exit code 1 when the needs cannot be met
+pub fn run(args: SynthArgs) -> Result<ExitCode, String> {
+    let config = ShiftConfig::load(&args.config).map_err(|e| format!("{}: {}", args.config.display(), e))?;
+    if config.needs.is_empty() {
+        return Err(format!("{}: no `[[need]]` entry", args.config.display()));
+    }
+    let week_rule_table = match synthesize_rule_table(&config.staff_group_list, &config.synth_request(args.cycle)) {
+        Ok(week_rule_table) => week_rule_table,
+        Err(errors) => {
+            for e in errors {
+                match e.group_id.and_then(|g| config.staff_group_list.0.get(g)) {
+                    Some(group) => eprintln!("error: group `{}`: {}", group.get_name(), e.reason),
+                    None => eprintln!("error: {}", e),
+                }
+            }
+            return Ok(ExitCode::FAILURE);
+        }
+    };
+    let source = config.source_with_rule_table(&rule_table_to_doc(&week_rule_table));
+    // never write a config that no longer loads
+    ShiftConfig::parse(&source, args.config.parent())
+        .map_err(|e| format!("{}: the rewritten config is invalid, {}", args.config.display(), e))?;
+    let output = if args.in_place { Some(&args.config) } else { args.output.as_ref() };
+    write_output(output, source.as_bytes())?;
+    Ok(ExitCode::SUCCESS)
+}
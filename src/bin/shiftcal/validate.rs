@@ -0,0 +1,66 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Args, ValueEnum};
+
+use shift_calendar::config::{Severity, ShiftConfig};
+use shift_calendar::solver::Generator;
+use shift_calendar::validate::validate_with;
+
+use crate::RangeArgs;
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+}
+
+#[derive(Args)]
+pub struct ValidateArgs {
+    /// Roster and rule config
+    #[arg(short, long)]
+    config: PathBuf,
+    #[command(flatten)]
+    range: RangeArgs,
+    #[arg(short, long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+    /// Check the schedule of the constraint solver instead of rotation
+    #[arg(long, conflicts_with = "qualified")]
+    solver: bool,
+    /// Check the rotation skipping unqualified staff
+    #[arg(long)]
+    qualified: bool,
+}
+
+/// exit code 1 when any error is found, warnings alone exit with 0
+pub fn run(args: ValidateArgs) -> Result<ExitCode, String> {
+    let config = ShiftConfig::load(&args.config).map_err(|e| format!("{}: {}", args.config.display(), e))?;
+    let range = args.range.resolve(&config)?;
+    let generator = if args.solver {
+        Generator::Solver(config.solver_config())
+    } else if args.qualified {
+        config.qualified_rotation()
+    } else {
+        Generator::Rotation
+    };
+    let diagnostics = validate_with(&config, &generator, range.week_delta, range.week_gen_range);
+
+    match args.format {
+        Format::Text => {
+            for diagnostic in &diagnostics {
+                println!("{}", diagnostic);
+            }
+            let count = |severity| diagnostics.iter().filter(|d| d.severity == severity).count();
+            eprintln!("{} errors, {} warnings", count(Severity::Error), count(Severity::Warning));
+        }
+        Format::Json => {
+            println!("{}", serde_json::to_string_pretty(&diagnostics).map_err(|e| e.to_string())?);
+        }
+    }
+
+    if diagnostics.iter().any(|d| d.severity == Severity::Error) {
+        Ok(ExitCode::FAILURE)
+    } else {
+        Ok(ExitCode::SUCCESS)
+    }
+}
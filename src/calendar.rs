@@ -0,0 +1,54 @@
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// Maps the absolute week indexes used by `gen_shift` to dates
+///
+/// day 0 of every rule week is `anchor + 7 * week` days.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShiftCalendar {
+    anchor: NaiveDate,
+}
+
+impl ShiftCalendar {
+    /// `anchor` is the first day of week 0
+    pub fn new(anchor: NaiveDate) -> Self {
+        Self { anchor }
+    }
+
+    /// calendar on which week `week_delta` starts at `start_date`
+    pub fn from_start(start_date: NaiveDate, week_delta: usize) -> Self {
+        Self { anchor: start_date - Days::new(7 * week_delta as u64) }
+    }
+
+    pub fn anchor(&self) -> NaiveDate {
+        self.anchor
+    }
+
+    pub fn date_of(&self, week: usize, day: usize) -> NaiveDate {
+        self.anchor + Days::new((7 * week + day) as u64)
+    }
+
+    /// (week, day) of a date, `None` before the anchor
+    pub fn position_of(&self, date: NaiveDate) -> Option<(usize, usize)> {
+        let days = usize::try_from((date - self.anchor).num_days()).ok()?;
+        Some((days / 7, days % 7))
+    }
+
+    pub fn week_of(&self, date: NaiveDate) -> Option<usize> {
+        self.position_of(date).map(|(week, _)| week)
+    }
+}
+
+/// day index of a weekday name, 0 is Monday
+pub fn parse_weekday(name: &str) -> Option<usize> {
+    match name.trim().to_lowercase().as_str() {
+        "mon" | "monday" | "月" => Some(0),
+        "tue" | "tuesday" | "火" => Some(1),
+        "wed" | "wednesday" | "水" => Some(2),
+        "thu" | "thursday" | "木" => Some(3),
+        "fri" | "friday" | "金" => Some(4),
+        "sat" | "saturday" | "土" => Some(5),
+        "sun" | "sunday" | "日" => Some(6),
+        _ => None,
+    }
+}
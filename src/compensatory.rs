@@ -0,0 +1,207 @@
+use chrono::{Days, NaiveDate};
+
+use crate::availability::is_available;
+use crate::calendar::ShiftCalendar;
+use crate::shift_gen::{
+    DecidedSchedule, ShiftOverride, Slot, Staff, StaffGroupList
+};
+use crate::solver::Absence;
+
+/// Days staff are not meant to work, read from `[holidays]`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Holidays {
+    /// 法定休日, weekdays with 0 the first day of a rule week
+    pub rest_days: Vec<usize>,
+    /// dates the store is closed
+    pub closed: Vec<NaiveDate>,
+    /// days after the holiday work a 代休 is due within
+    pub window_days: u64,
+}
+
+impl Holidays {
+    pub fn is_holiday(&self, day: usize, date: NaiveDate) -> bool {
+        self.rest_days.contains(&day) || self.closed.contains(&date)
+    }
+}
+
+/// Day off given for work on a holiday, read from `[[compensatory]]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompensatoryDay {
+    pub group_id: usize,
+    pub staff_id: usize,
+    /// the holiday worked
+    pub worked: NaiveDate,
+    pub off: NaiveDate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompensationKind {
+    /// 振替休日, the day off is set before the holiday is worked
+    Substitute,
+    /// 代休, the day off is given after the holiday work
+    Compensatory,
+}
+
+impl CompensatoryDay {
+    pub fn kind(&self) -> CompensationKind {
+        if self.off < self.worked { CompensationKind::Substitute } else { CompensationKind::Compensatory }
+    }
+}
+
+/// An assignment on a holiday
+#[derive(Debug, Clone)]
+pub struct HolidayWork<'a> {
+    pub staff: &'a Staff,
+    /// absolute week index
+    pub week: usize,
+    pub day: usize,
+    pub date: NaiveDate,
+    /// last day a 代休 can be given on
+    pub due: NaiveDate,
+    pub compensation: Option<CompensatoryDay>,
+}
+
+impl HolidayWork<'_> {
+    /// still owed after `on`
+    pub fn is_overdue(&self, on: NaiveDate) -> bool {
+        self.compensation.is_none() && self.due < on
+    }
+}
+
+fn is_staff(staff: &Staff, group_id: usize, staff_id: usize) -> bool {
+    (staff.get_group_id(), staff.get_id()) == (group_id, staff_id)
+}
+
+/// Every staff working on a holiday of the schedule, once per day, with the
+/// day off given for it if any
+pub fn holiday_work<'a>(
+    schedule: &DecidedSchedule<'a>,
+    calendar: &ShiftCalendar,
+    holidays: &Holidays,
+    compensations: &[CompensatoryDay],
+) -> Vec<HolidayWork<'a>> {
+    let mut works = vec![];
+    for (week, day, day_shift) in schedule.days() {
+        let date = calendar.date_of(week, day);
+        if !holidays.is_holiday(day, date) {
+            continue;
+        }
+        let mut staff: Vec<&'a Staff> = Slot::ALL.iter().flat_map(|slot| day_shift.slot(*slot).iter().copied()).collect();
+        staff.sort_by_key(|s| (s.get_group_id(), s.get_id()));
+        staff.dedup_by_key(|s| (s.get_group_id(), s.get_id()));
+        for staff in staff {
+            let compensation = compensations
+                .iter()
+                .find(|c| is_staff(staff, c.group_id, c.staff_id) && c.worked == date)
+                .copied();
+            let due = date + Days::new(holidays.window_days);
+            works.push(HolidayWork { staff, week, day, date, due, compensation });
+        }
+    }
+    works
+}
+
+/// Holiday work still owed by each staff, in roster order
+#[derive(Debug, Clone)]
+pub struct CompensationBalance<'a> {
+    pub staff: &'a Staff,
+    /// holidays worked without a day off
+    pub owed: Vec<NaiveDate>,
+    /// the owed ones past their window on the date of the balance
+    pub overdue: Vec<NaiveDate>,
+}
+
+pub fn compensation_balances<'a>(
+    works: &[HolidayWork<'a>],
+    staff_group_list: &'a StaffGroupList,
+    on: NaiveDate,
+) -> Vec<CompensationBalance<'a>> {
+    staff_group_list
+        .0
+        .iter()
+        .flat_map(|group| group.iter())
+        .map(|staff| {
+            let owed: Vec<&HolidayWork<'a>> = works
+                .iter()
+                .filter(|w| std::ptr::eq(w.staff, staff) && w.compensation.is_none())
+                .collect();
+            CompensationBalance {
+                staff,
+                owed: owed.iter().map(|w| w.date).collect(),
+                overdue: owed.iter().filter(|w| w.is_overdue(on)).map(|w| w.date).collect(),
+            }
+        })
+        .collect()
+}
+
+/// A 代休 found for an owed holiday, with the overrides covering the holes
+/// the staff leaves that day
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompensationPlan {
+    pub compensatory_day: CompensatoryDay,
+    pub overrides: Vec<ShiftOverride>,
+}
+
+/// Find a 代休 for every owed holiday work
+///
+/// the day off is the first working day of the staff after the holiday and
+/// within the window that is not a holiday itself, and on which every hole
+/// of theirs can go to another staff of the group, taken in rotation order,
+/// who is available and neither in the slot already nor absent. Days
+/// planned are taken out of the schedule before the next search. Works with
+/// no such day in the schedule are left out.
+pub fn plan_compensatory_days<'a>(
+    schedule: &DecidedSchedule<'a>,
+    staff_group_list: &'a StaffGroupList,
+    calendar: &ShiftCalendar,
+    holidays: &Holidays,
+    works: &[HolidayWork<'_>],
+    absences: &[Absence],
+) -> Vec<CompensationPlan> {
+    let mut schedule = schedule.clone();
+    let mut plans = vec![];
+    for work in works.iter().filter(|w| w.compensation.is_none()) {
+        let (group_id, staff_id) = (work.staff.get_group_id(), work.staff.get_id());
+        let Some(group) = staff_group_list.0.get(group_id) else {
+            continue;
+        };
+        let absent = |s: &Staff, week: usize, day: usize, slot: Slot| {
+            absences.iter().any(|a| is_staff(s, a.group_id, a.staff_id) && a.covers(week, day, slot))
+        };
+        let first = work.week * 7 + work.day + 1;
+        let last = work.week * 7 + work.day + work.due.signed_duration_since(work.date).num_days() as usize;
+        let plan = (first..=last).find_map(|d| {
+            let (week, day) = (d / 7, d % 7);
+            let date = calendar.date_of(week, day);
+            let day_shift = schedule.week(week)?.0.get(day)?;
+            if holidays.is_holiday(day, date) {
+                return None;
+            }
+            let mut overrides = vec![];
+            for slot in Slot::ALL {
+                let staff_list = day_shift.slot(slot);
+                for (hole, s) in staff_list.iter().enumerate().filter(|(_, s)| is_staff(s, group_id, staff_id)) {
+                    let taker = (1..group.len())
+                        .map(|step| group.pickup_staff((s.get_id() + step) % group.len()))
+                        .filter(|t| is_available(t, day, slot, Some(date)))
+                        .find(|t| !staff_list.iter().any(|u| std::ptr::eq(*u, *t)) && !absent(t, week, day, slot))?;
+                    overrides.push(ShiftOverride { week, day, slot, hole, group_id, staff_id: taker.get_id() });
+                }
+            }
+            if overrides.is_empty() {
+                return None;
+            }
+            Some(CompensationPlan { compensatory_day: CompensatoryDay { group_id, staff_id, worked: work.date, off: date }, overrides })
+        });
+        if let Some(plan) = plan {
+            for o in &plan.overrides {
+                let taker = group.pickup_staff(o.staff_id);
+                if let Some(week_shift) = schedule.weeks.get_mut(o.week - schedule.week_delta) {
+                    week_shift.0[o.day].slot_mut(o.slot)[o.hole] = taker;
+                }
+            }
+            plans.push(plan);
+        }
+    }
+    plans
+}
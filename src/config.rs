@@ -0,0 +1,1770 @@
+//! TOML configuration of a roster and its rule table
+//!
+//! ```toml
+//! # first day of rule week 0, a monday
+//! anchor = 2025-01-06
+//! # roster csv, relative to this file. `[[group]]` tables can be used instead.
+//! roster = "roster.csv"
+//!
+//! [[group]]
+//! name = "A"
+//! staff = ["nameA0", "nameA1"]
+//!
+//! [[week]]
+//! mon = { morning = ["A:0", "B:0"], afternoon = ["B:1"] }
+//! tue = { afternoon = ["A:1"] }
+//! ```
+//!
+//! A hole is written `<group name>:<staff index>`. Days left out have no hole.
+//! Holes separated by `|`, such as `"B:0|A:0"`, draw from the next group when
+//! the ones before it are short: rotation picks from B and moves to A when
+//! no staff of B is available or every one is in the slot already, and the
+//! solver takes staff of A only when B cannot fill it.
+//!
+//! Checkers run by `shiftcal validate` are configured under `[checks]`:
+//!
+//! ```toml
+//! [checks]
+//! basic = { severity = "warning" }
+//!
+//! [[checks.quota]]
+//! group = "A"
+//! min_per_week = 1
+//! max_per_week = 3
+//!
+//! [[checks.coverage]]
+//! days = ["sat", "sun"]
+//! slots = ["morning"]
+//! min = 2
+//!
+//! [checks.labour]
+//! max_consecutive_days = 6
+//! min_days_off_per_week = 1
+//!
+//! [[checks.skill]]
+//! skill = "keyholder"
+//! slots = ["morning"]
+//! min = 1
+//!
+//! # 法定労働時間, 8 and 40 hours by default
+//! [checks.overtime]
+//! daily_hours = 8
+//! weekly_hours = 40
+//! severity = "warning"
+//! ```
+//!
+//! Every check takes an optional `severity`, `"error"` by default. Skills are
+//! given to staff by the `skills` column of the roster or by `[[skill]]`
+//! tables; `shiftcal generate --qualified` skips unqualified staff to meet
+//! the `[[checks.skill]]` requirements.
+//!
+//! ```toml
+//! [[skill]]
+//! name = "keyholder"
+//! staff = ["A:0", "B:1"]
+//! ```
+//!
+//! Employment contracts limit the days and hours worked per week and per
+//! calendar month, the months needing `anchor`. A contract applies to every
+//! staff of an `employment_type` from the roster, or to the `staff` listed,
+//! which wins. Hours come from the length of the slots, 4 hours each unless
+//! given under `[slots]` as `hours` or as `start` and `end`, less
+//! `break_minutes`. `severity` applies to the breaches of the contract.
+//!
+//! ```toml
+//! [slots]
+//! morning = { start = "09:00", end = "13:30", break_minutes = 30 }
+//! afternoon = { hours = 5.5 }
+//!
+//! [[contract]]
+//! employment_type = "part-time"
+//! max_days_per_week = 3
+//! max_hours_per_week = 20
+//!
+//! [[contract]]
+//! staff = ["A:0"]
+//! min_days_per_month = 18
+//! ```
+//!
+//! Approved paid leave is used from the statutory grants counted from the
+//! roster `join_date`, part-timers getting 比例付与 from the days of their
+//! contract. Leave after the anchor is an absence, keeping the staff out of
+//! the schedule. Its dates falling on the rest days and closed dates of
+//! `[holidays]` use no leave. A grant is withheld when `[[attendance]]` gives
+//! an 出勤率 under 80% for the year before it.
+//!
+//! ```toml
+//! [[leave]]
+//! staff = "A:0"
+//! from = 2025-03-03
+//! to = 2025-03-04
+//!
+//! [[attendance]]
+//! staff = "A:1"
+//! grant = 2025-04-01
+//! rate = 0.75
+//! ```
+//!
+//! Work on a `rest_days` weekday (法定休日) or a `closed` date is owed a day
+//! off within `window_days` (30 by default). `[[compensatory]]` records the
+//! day off given, a 振替休日 when it comes before the holiday and a 代休
+//! after it; after the anchor it keeps the staff out of the schedule like an
+//! absence.
+//!
+//! ```toml
+//! [holidays]
+//! rest_days = ["sun"]
+//! closed = [2025-01-01]
+//! window_days = 14
+//!
+//! [[compensatory]]
+//! staff = "A:0"
+//! worked = 2025-01-12
+//! off = 2025-01-15
+//! ```
+//!
+//! `shiftcal synth` builds the `[[week]]` tables from the holes each group
+//! needs, within the `[[checks.quota]]` bounds:
+//!
+//! ```toml
+//! [[need]]
+//! group = "A"
+//! days = ["mon", "tue", "wed", "thu", "fri"]
+//! slots = ["morning"]
+//! count = 1
+//! ```
+//!
+//! Generated assignments can be replaced by hand. The date needs `anchor`;
+//! `week` (absolute week index) and `day` can be given instead.
+//!
+//! ```toml
+//! [[override]]
+//! date = 2025-01-13
+//! slot = "morning"
+//! hole = 0
+//! staff = "A:1"
+//! ```
+//!
+//! Likes (positive `weight`) and dislikes (negative) of a staff are added to
+//! their profile; they apply to the assignments matching every one of `day`,
+//! `slot` and `date` given. The solver maximises them.
+//!
+//! ```toml
+//! [[preference]]
+//! staff = "A:1"
+//! slot = "morning"
+//! weight = 2
+//! ```
+//!
+//! Availability patterns repeat every week: the staff never works the `days`
+//! and `slots` given, every day and slot when one is left out, or with
+//! `only = true` works nothing else. `from` and `to` limit the dates they
+//! are in force and need `anchor`. Rotation hands the holes they cannot work
+//! to the next staff of the group who can, the solver keeps them off, and
+//! `shiftcal validate` reports the rest with the `severity` of the pattern.
+//!
+//! ```toml
+//! [[availability]]
+//! staff = "A:1"
+//! days = ["wed"]
+//! severity = "warning"
+//!
+//! [[availability]]
+//! staff = "A:2"
+//! days = ["sat", "sun"]
+//! only = true
+//! from = 2025-04-01
+//! ```
+//!
+//! Absences keep a staff out of the schedule: rotation hands their holes on
+//! like those of unavailable staff, and the solver and `shiftcal repair`
+//! never give them any. `shiftcal validate` reports the ones still in it,
+//! through an override or when nobody could take the hole, with the
+//! `severity` of the table. `to`, `slot` and `severity` are optional, and
+//! `[[leave]]` and `[[compensatory]]` take a `severity` too.
+//!
+//! ```toml
+//! [[absence]]
+//! staff = "A:0"
+//! from = 2025-01-13
+//! to = 2025-01-24
+//! ```
+
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use chrono::{Datelike, NaiveDate, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+use toml::Spanned;
+
+use crate::calendar::{parse_weekday, ShiftCalendar};
+use crate::availability::AvailabilityPattern;
+use crate::compensatory::{CompensationPlan, CompensatoryDay, Holidays};
+use crate::contract::{Contract, SlotDurations};
+use crate::document::{rule_table_from_doc, DayRuleDoc, HoleDoc, WeekRuleDoc};
+use crate::hours::StatutoryLimits;
+use crate::leave::{build_ledger, grant_days_per_week, Attendance, Leave, LeaveLedger};
+use crate::offset::{rank_offsets, OffsetScore};
+use crate::period::{analyze_period, PeriodAnalysis};
+use crate::preference::StaffPreference;
+use crate::repair::{repair_schedule, Published};
+use crate::roster::{import_roster_csv, RosterErr};
+use crate::skill::SkillRequirement;
+use crate::solver::{Absence, Generator, SolveErr, SolverConfig};
+use crate::stats::AssignmentCounts;
+use crate::synth::{CoverageNeed, StaffQuota, SynthRequest};
+use crate::shift_gen::{
+    apply_overrides, DecidedSchedule, EmploymentType, Incomplete, ShiftOverride, Slot, Staff, StaffGroup, StaffGroupList, WeekRuleTable
+};
+
+/// line and column in a config file, both 1-based
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Location {
+    pub fn from_offset(source: &str, offset: usize) -> Self {
+        let before = &source[..offset.min(source.len())];
+        let line = before.matches('\n').count() + 1;
+        let column = before[before.rfind('\n').map_or(0, |i| i + 1)..].chars().count() + 1;
+        Self { line, column }
+    }
+}
+
+#[derive(Debug)]
+pub struct ConfigErr {
+    pub location: Option<Location>,
+    pub reason: CauseOfConfigErr,
+}
+
+#[derive(Debug)]
+pub enum CauseOfConfigErr {
+    Io(PathBuf, std::io::Error),
+    Toml(toml::de::Error),
+    InvalidAnchor(String),
+    /// rule weeks run from monday to sunday
+    AnchorNotMonday(NaiveDate),
+    Roster(Vec<RosterErr>),
+    /// neither `roster` nor `[[group]]` is given, or both are
+    RosterSourceErr,
+    MalformedHole(String),
+    UnknownGroup(String),
+    UnknownWeekday(String),
+    MalformedOverride(&'static str),
+    MalformedAbsence(&'static str),
+    MalformedPreference(&'static str),
+    MalformedAvailability(&'static str),
+    MalformedSkill(&'static str),
+    MalformedSlot(&'static str),
+    MalformedContract(&'static str),
+    MalformedLeave(&'static str),
+    MalformedHolidays(&'static str),
+    MalformedCompensatory(&'static str),
+    MalformedAttendance(&'static str),
+}
+
+impl std::fmt::Display for ConfigErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(location) = self.location {
+            write!(f, "{}:{}: ", location.line, location.column)?;
+        }
+        match &self.reason {
+            CauseOfConfigErr::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            CauseOfConfigErr::Toml(e) => write!(f, "{}", e.message()),
+            CauseOfConfigErr::InvalidAnchor(a) => write!(f, "invalid anchor `{}`, expected a date", a),
+            CauseOfConfigErr::AnchorNotMonday(a) => write!(f, "anchor `{}` is a {}, expected a Monday", a, a.format("%A")),
+            CauseOfConfigErr::Roster(errors) => {
+                write!(f, "invalid roster")?;
+                errors.iter().try_for_each(|e| write!(f, "\n  {}", e))
+            }
+            CauseOfConfigErr::RosterSourceErr => write!(f, "give either `roster` or `[[group]]`"),
+            CauseOfConfigErr::MalformedHole(h) => write!(f, "malformed hole `{}`, expected `<group>:<index>`", h),
+            CauseOfConfigErr::UnknownGroup(g) => write!(f, "unknown group `{}`", g),
+            CauseOfConfigErr::UnknownWeekday(d) => write!(f, "unknown weekday `{}`", d),
+            CauseOfConfigErr::MalformedOverride(reason) => write!(f, "malformed override, {}", reason),
+            CauseOfConfigErr::MalformedAbsence(reason) => write!(f, "malformed absence, {}", reason),
+            CauseOfConfigErr::MalformedPreference(reason) => write!(f, "malformed preference, {}", reason),
+            CauseOfConfigErr::MalformedAvailability(reason) => write!(f, "malformed availability, {}", reason),
+            CauseOfConfigErr::MalformedSkill(reason) => write!(f, "malformed skill, {}", reason),
+            CauseOfConfigErr::MalformedSlot(reason) => write!(f, "malformed slot, {}", reason),
+            CauseOfConfigErr::MalformedContract(reason) => write!(f, "malformed contract, {}", reason),
+            CauseOfConfigErr::MalformedLeave(reason) => write!(f, "malformed leave, {}", reason),
+            CauseOfConfigErr::MalformedHolidays(reason) => write!(f, "malformed holidays, {}", reason),
+            CauseOfConfigErr::MalformedCompensatory(reason) => write!(f, "malformed compensatory day, {}", reason),
+            CauseOfConfigErr::MalformedAttendance(reason) => write!(f, "malformed attendance, {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ConfigErr {}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawConfig {
+    anchor: Option<Spanned<toml::value::Datetime>>,
+    roster: Option<String>,
+    #[serde(default)]
+    group: Vec<RawGroup>,
+    #[serde(default)]
+    week: Vec<Spanned<RawWeek>>,
+    #[serde(default)]
+    checks: RawChecks,
+    #[serde(default, rename = "override")]
+    overrides: Vec<Spanned<RawOverride>>,
+    #[serde(default)]
+    need: Vec<Spanned<RawNeed>>,
+    #[serde(default)]
+    absence: Vec<Spanned<RawAbsence>>,
+    #[serde(default)]
+    preference: Vec<Spanned<RawPreference>>,
+    #[serde(default)]
+    availability: Vec<Spanned<RawAvailability>>,
+    #[serde(default)]
+    skill: Vec<RawSkill>,
+    #[serde(default)]
+    slots: RawSlots,
+    #[serde(default)]
+    contract: Vec<Spanned<RawContract>>,
+    #[serde(default)]
+    leave: Vec<Spanned<RawLeave>>,
+    #[serde(default)]
+    attendance: Vec<Spanned<RawAttendance>>,
+    holidays: Option<Spanned<RawHolidays>>,
+    #[serde(default)]
+    compensatory: Vec<Spanned<RawCompensatory>>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawHolidays {
+    #[serde(default)]
+    rest_days: Vec<String>,
+    #[serde(default)]
+    closed: Vec<toml::value::Datetime>,
+    #[serde(default = "default_window_days")]
+    window_days: u64,
+}
+
+fn default_window_days() -> u64 {
+    30
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawCompensatory {
+    staff: Spanned<String>,
+    worked: toml::value::Datetime,
+    off: toml::value::Datetime,
+    #[serde(default)]
+    severity: Severity,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct RawSlots {
+    morning: Option<Spanned<RawSlot>>,
+    afternoon: Option<Spanned<RawSlot>>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawSlot {
+    hours: Option<f64>,
+    /// `"09:00"`, with `end` instead of `hours`
+    start: Option<String>,
+    end: Option<String>,
+    /// unpaid break taken out of the slot
+    #[serde(default)]
+    break_minutes: u32,
+}
+
+/// limits of the staff listed, or of every staff of an employment type
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawContract {
+    employment_type: Option<String>,
+    staff: Option<Vec<Spanned<String>>>,
+    min_days_per_week: Option<u32>,
+    max_days_per_week: Option<u32>,
+    min_hours_per_week: Option<u32>,
+    max_hours_per_week: Option<u32>,
+    min_days_per_month: Option<u32>,
+    max_days_per_month: Option<u32>,
+    min_hours_per_month: Option<u32>,
+    max_hours_per_month: Option<u32>,
+    #[serde(default)]
+    severity: Severity,
+}
+
+impl RawContract {
+    fn contract(&self) -> Contract {
+        Contract {
+            min_days_per_week: self.min_days_per_week,
+            max_days_per_week: self.max_days_per_week,
+            min_hours_per_week: self.min_hours_per_week,
+            max_hours_per_week: self.max_hours_per_week,
+            min_days_per_month: self.min_days_per_month,
+            max_days_per_month: self.max_days_per_month,
+            min_hours_per_month: self.min_hours_per_month,
+            max_hours_per_month: self.max_hours_per_month,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawSkill {
+    name: String,
+    staff: Vec<Spanned<String>>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawPreference {
+    staff: Spanned<String>,
+    day: Option<String>,
+    slot: Option<Slot>,
+    date: Option<toml::value::Datetime>,
+    weight: i64,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawAvailability {
+    staff: Spanned<String>,
+    days: Option<Vec<String>>,
+    slots: Option<Vec<Slot>>,
+    from: Option<toml::value::Datetime>,
+    to: Option<toml::value::Datetime>,
+    #[serde(default)]
+    only: bool,
+    #[serde(default)]
+    severity: Severity,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawLeave {
+    staff: Spanned<String>,
+    from: toml::value::Datetime,
+    /// `from` when missing
+    to: Option<toml::value::Datetime>,
+    #[serde(default)]
+    severity: Severity,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawAttendance {
+    staff: Spanned<String>,
+    grant: toml::value::Datetime,
+    rate: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawAbsence {
+    staff: Spanned<String>,
+    from: toml::value::Datetime,
+    /// `from` when missing
+    to: Option<toml::value::Datetime>,
+    slot: Option<Slot>,
+    #[serde(default)]
+    severity: Severity,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawNeed {
+    group: String,
+    days: Option<Vec<String>>,
+    slots: Option<Vec<Slot>>,
+    #[serde(default = "one")]
+    count: usize,
+}
+
+fn one() -> usize {
+    1
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawOverride {
+    date: Option<toml::value::Datetime>,
+    week: Option<usize>,
+    day: Option<String>,
+    slot: Slot,
+    #[serde(default)]
+    hole: usize,
+    staff: Spanned<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    #[default]
+    Error,
+    Warning,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct RawChecks {
+    #[serde(default)]
+    basic: RawBasicCheck,
+    #[serde(default)]
+    quota: Vec<Spanned<RawQuotaCheck>>,
+    #[serde(default)]
+    coverage: Vec<Spanned<RawCoverageCheck>>,
+    labour: Option<Spanned<RawLabourCheck>>,
+    #[serde(default)]
+    skill: Vec<Spanned<RawSkillCheck>>,
+    overtime: Option<Spanned<RawOvertimeCheck>>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct RawBasicCheck {
+    #[serde(default)]
+    severity: Severity,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawQuotaCheck {
+    group: Option<String>,
+    min_per_week: Option<usize>,
+    max_per_week: Option<usize>,
+    #[serde(default)]
+    severity: Severity,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawCoverageCheck {
+    days: Option<Vec<String>>,
+    slots: Option<Vec<Slot>>,
+    min: Option<usize>,
+    max: Option<usize>,
+    #[serde(default)]
+    severity: Severity,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawSkillCheck {
+    skill: String,
+    days: Option<Vec<String>>,
+    slots: Option<Vec<Slot>>,
+    #[serde(default = "default_skill_min")]
+    min: usize,
+    #[serde(default)]
+    severity: Severity,
+}
+
+fn default_skill_min() -> usize {
+    1
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawOvertimeCheck {
+    #[serde(default = "default_daily_hours")]
+    daily_hours: u32,
+    #[serde(default = "default_weekly_hours")]
+    weekly_hours: u32,
+    #[serde(default)]
+    severity: Severity,
+}
+
+fn default_daily_hours() -> u32 {
+    8
+}
+
+fn default_weekly_hours() -> u32 {
+    40
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawLabourCheck {
+    max_consecutive_days: Option<usize>,
+    min_days_off_per_week: Option<usize>,
+    #[serde(default)]
+    severity: Severity,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuotaCheckConfig {
+    pub group_id: Option<usize>,
+    pub min_per_week: Option<usize>,
+    pub max_per_week: Option<usize>,
+    pub severity: Severity,
+    pub location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoverageCheckConfig {
+    pub days: Vec<usize>,
+    pub slots: Vec<Slot>,
+    pub min: Option<usize>,
+    pub max: Option<usize>,
+    pub severity: Severity,
+    pub location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LabourCheckConfig {
+    pub max_consecutive_days: Option<usize>,
+    pub min_days_off_per_week: Option<usize>,
+    pub severity: Severity,
+    pub location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkillCheckConfig {
+    pub requirement: SkillRequirement,
+    pub severity: Severity,
+    pub location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OvertimeCheckConfig {
+    pub limits: StatutoryLimits,
+    pub severity: Severity,
+    pub location: Location,
+}
+
+/// Table a staff got a contract, an availability pattern or an absence from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaffCheckConfig {
+    pub group_id: usize,
+    pub staff_id: usize,
+    pub severity: Severity,
+    pub location: Location,
+}
+
+/// Checkers configured under `[checks]`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChecksConfig {
+    /// `BasicChecker` always runs
+    pub basic_severity: Severity,
+    pub quota: Vec<QuotaCheckConfig>,
+    pub coverage: Vec<CoverageCheckConfig>,
+    pub labour: Option<LabourCheckConfig>,
+    pub skill: Vec<SkillCheckConfig>,
+    pub overtime: Option<OvertimeCheckConfig>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawGroup {
+    name: String,
+    staff: Vec<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct RawDay {
+    #[serde(default)]
+    morning: Vec<Spanned<String>>,
+    #[serde(default)]
+    afternoon: Vec<Spanned<String>>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawWeek {
+    #[serde(default)]
+    mon: RawDay,
+    #[serde(default)]
+    tue: RawDay,
+    #[serde(default)]
+    wed: RawDay,
+    #[serde(default)]
+    thu: RawDay,
+    #[serde(default)]
+    fri: RawDay,
+    #[serde(default)]
+    sat: RawDay,
+    #[serde(default)]
+    sun: RawDay,
+}
+
+impl RawWeek {
+    fn into_days(self) -> [RawDay; 7] {
+        [self.mon, self.tue, self.wed, self.thu, self.fri, self.sat, self.sun]
+    }
+}
+
+/// Position of a hole in the rule table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HolePosition {
+    pub week_rule_index: usize,
+    pub day: usize,
+    pub slot: Slot,
+    pub index: usize,
+}
+
+/// Loaded configuration
+#[derive(Clone)]
+pub struct ShiftConfig {
+    pub path: Option<PathBuf>,
+    pub source: String,
+    /// first day of rule week 0
+    pub anchor: Option<NaiveDate>,
+    pub staff_group_list: StaffGroupList,
+    pub rule_table: Vec<WeekRuleDoc>,
+    pub checks: ChecksConfig,
+    /// `[[override]]` entries in file order, the last one wins
+    pub overrides: Vec<ShiftOverride>,
+    /// `[[need]]` entries read by the synthesizer
+    pub needs: Vec<CoverageNeed>,
+    /// `[[absence]]` entries in file order, then the `[[leave]]` and
+    /// `[[compensatory]]` days off after the anchor
+    pub absences: Vec<Absence>,
+    /// `[slots]`, 4 hours each by default
+    pub slot_durations: SlotDurations,
+    /// `[[leave]]` entries in file order
+    pub leaves: Vec<Leave>,
+    /// `[[attendance]]` entries in file order
+    pub attendance: Vec<Attendance>,
+    /// `[holidays]`
+    pub holidays: Option<Holidays>,
+    /// `[[compensatory]]` entries in file order
+    pub compensatory_days: Vec<CompensatoryDay>,
+    /// `[[contract]]` giving each staff their contract, in the order applied
+    pub contract_checks: Vec<StaffCheckConfig>,
+    /// `[[availability]]` entries in file order, one per pattern
+    pub availability_checks: Vec<StaffCheckConfig>,
+    /// table each of `absences` comes from
+    pub absence_checks: Vec<StaffCheckConfig>,
+    hole_spans: Vec<(HolePosition, Range<usize>)>,
+    week_spans: Vec<Range<usize>>,
+    override_spans: Vec<Range<usize>>,
+}
+
+fn parse_hole(
+    raw: &Spanned<String>,
+    staff_group_list: &StaffGroupList,
+    source: &str,
+) -> Result<HoleDoc, ConfigErr> {
+    parse_hole_at(raw.get_ref(), raw.span().start, staff_group_list, source)
+}
+
+/// a staff named like a hole, `cause` reporting one the group does not have
+fn parse_staff_ref(
+    raw: &Spanned<String>,
+    staff_group_list: &StaffGroupList,
+    source: &str,
+    cause: fn(&'static str) -> CauseOfConfigErr,
+) -> Result<HoleDoc, ConfigErr> {
+    let staff = parse_hole(raw, staff_group_list, source)?;
+    if staff_group_list.get_staff(staff.group, staff.id).is_none() {
+        return Err(ConfigErr {
+            location: Some(Location::from_offset(source, raw.span().start)),
+            reason: cause("no such staff in the group"),
+        });
+    }
+    Ok(staff)
+}
+
+/// a hole of a `[[week]]` rule, `B:0|A:0` drawing from A when B is short
+fn parse_rule_hole(
+    raw: &Spanned<String>,
+    staff_group_list: &StaffGroupList,
+    source: &str,
+) -> Result<HoleDoc, ConfigErr> {
+    let mut holes = raw
+        .get_ref()
+        .split('|')
+        .map(|part| parse_hole_at(part, raw.span().start, staff_group_list, source))
+        .collect::<Result<Vec<_>, _>>()?;
+    let groups: Vec<usize> = holes.iter().map(|h| h.group).collect();
+    let mut hole = holes.remove(0);
+    if (1..groups.len()).any(|i| groups[..i].contains(&groups[i])) {
+        return Err(ConfigErr {
+            location: Some(Location::from_offset(source, raw.span().start)),
+            reason: CauseOfConfigErr::MalformedHole(raw.get_ref().clone()),
+        });
+    }
+    hole.fallback = holes;
+    Ok(hole)
+}
+
+fn parse_hole_at(
+    text: &str,
+    offset: usize,
+    staff_group_list: &StaffGroupList,
+    source: &str,
+) -> Result<HoleDoc, ConfigErr> {
+    let err = |reason| ConfigErr { location: Some(Location::from_offset(source, offset)), reason };
+    let (group, id) = text
+        .rsplit_once(':')
+        .and_then(|(group, id)| Some((group.trim(), id.trim().parse::<usize>().ok()?)))
+        .ok_or_else(|| err(CauseOfConfigErr::MalformedHole(text.to_string())))?;
+    let group_id = staff_group_list
+        .0
+        .iter()
+        .position(|g| g.get_name() == group)
+        .ok_or_else(|| err(CauseOfConfigErr::UnknownGroup(group.to_string())))?;
+    Ok(HoleDoc::new(group_id, id))
+}
+
+fn find_group(
+    staff_group_list: &StaffGroupList,
+    name: &str,
+    location: Location,
+) -> Result<usize, ConfigErr> {
+    staff_group_list
+        .0
+        .iter()
+        .position(|g| g.get_name() == name)
+        .ok_or_else(|| ConfigErr { location: Some(location), reason: CauseOfConfigErr::UnknownGroup(name.to_string()) })
+}
+
+/// every day when `None`
+fn parse_days(days: Option<Vec<String>>, location: Location) -> Result<Vec<usize>, ConfigErr> {
+    match days {
+        Some(days) => days
+            .iter()
+            .map(|d| {
+                parse_weekday(d).ok_or_else(|| ConfigErr {
+                    location: Some(location),
+                    reason: CauseOfConfigErr::UnknownWeekday(d.clone()),
+                })
+            })
+            .collect(),
+        None => Ok((0..7).collect()),
+    }
+}
+
+fn parse_checks(raw: RawChecks, staff_group_list: &StaffGroupList, source: &str) -> Result<ChecksConfig, ConfigErr> {
+    let location = |span: Range<usize>| Location::from_offset(source, span.start);
+
+    let quota = raw
+        .quota
+        .into_iter()
+        .map(|spanned| {
+            let location = location(spanned.span());
+            let quota = spanned.into_inner();
+            Ok(QuotaCheckConfig {
+                group_id: quota.group.map(|g| find_group(staff_group_list, &g, location)).transpose()?,
+                min_per_week: quota.min_per_week,
+                max_per_week: quota.max_per_week,
+                severity: quota.severity,
+                location,
+            })
+        })
+        .collect::<Result<_, ConfigErr>>()?;
+
+    let coverage = raw
+        .coverage
+        .into_iter()
+        .map(|spanned| {
+            let location = location(spanned.span());
+            let coverage = spanned.into_inner();
+            let days = parse_days(coverage.days, location)?;
+            Ok(CoverageCheckConfig {
+                days,
+                slots: coverage.slots.unwrap_or_else(|| Slot::ALL.to_vec()),
+                min: coverage.min,
+                max: coverage.max,
+                severity: coverage.severity,
+                location,
+            })
+        })
+        .collect::<Result<_, ConfigErr>>()?;
+
+    let labour = raw.labour.map(|spanned| {
+        let location = location(spanned.span());
+        let labour = spanned.into_inner();
+        LabourCheckConfig {
+            max_consecutive_days: labour.max_consecutive_days,
+            min_days_off_per_week: labour.min_days_off_per_week,
+            severity: labour.severity,
+            location,
+        }
+    });
+
+    let skill = raw
+        .skill
+        .into_iter()
+        .map(|spanned| {
+            let location = location(spanned.span());
+            let skill = spanned.into_inner();
+            let requirement = SkillRequirement {
+                skill: skill.skill,
+                days: parse_days(skill.days, location)?,
+                slots: skill.slots.unwrap_or_else(|| Slot::ALL.to_vec()),
+                min: skill.min,
+            };
+            Ok(SkillCheckConfig { requirement, severity: skill.severity, location })
+        })
+        .collect::<Result<_, ConfigErr>>()?;
+
+    let overtime = raw.overtime.map(|spanned| {
+        let location = location(spanned.span());
+        let overtime = spanned.into_inner();
+        OvertimeCheckConfig {
+            limits: StatutoryLimits { daily_minutes: overtime.daily_hours * 60, weekly_minutes: overtime.weekly_hours * 60 },
+            severity: overtime.severity,
+            location,
+        }
+    });
+
+    Ok(ChecksConfig { basic_severity: raw.basic.severity, quota, coverage, labour, skill, overtime })
+}
+
+fn parse_override(
+    raw: &Spanned<RawOverride>,
+    anchor: Option<NaiveDate>,
+    staff_group_list: &StaffGroupList,
+    source: &str,
+) -> Result<ShiftOverride, ConfigErr> {
+    let location = Some(Location::from_offset(source, raw.span().start));
+    let err = |reason| ConfigErr { location, reason };
+    let o = raw.get_ref();
+    let (week, day) = match (&o.date, o.week, &o.day) {
+        (Some(date), None, None) => {
+            let date = NaiveDate::parse_from_str(&date.to_string(), "%Y-%m-%d")
+                .map_err(|_| err(CauseOfConfigErr::MalformedOverride("`date` is not a date")))?;
+            let calendar = anchor
+                .map(ShiftCalendar::new)
+                .ok_or_else(|| err(CauseOfConfigErr::MalformedOverride("`date` needs `anchor`")))?;
+            calendar
+                .position_of(date)
+                .ok_or_else(|| err(CauseOfConfigErr::MalformedOverride("`date` is before the anchor")))?
+        }
+        (None, Some(week), Some(day)) => {
+            let day = parse_weekday(day).ok_or_else(|| err(CauseOfConfigErr::UnknownWeekday(day.clone())))?;
+            (week, day)
+        }
+        _ => return Err(err(CauseOfConfigErr::MalformedOverride("give either `date` or `week` and `day`"))),
+    };
+    let staff = parse_staff_ref(&o.staff, staff_group_list, source, CauseOfConfigErr::MalformedOverride)?;
+    Ok(ShiftOverride { week, day, slot: o.slot, hole: o.hole, group_id: staff.group, staff_id: staff.id })
+}
+
+fn parse_absence(
+    raw: &Spanned<RawAbsence>,
+    anchor: Option<NaiveDate>,
+    staff_group_list: &StaffGroupList,
+    source: &str,
+) -> Result<Absence, ConfigErr> {
+    let location = Some(Location::from_offset(source, raw.span().start));
+    let err = |reason| ConfigErr { location, reason };
+    let a = raw.get_ref();
+    let calendar = anchor
+        .map(ShiftCalendar::new)
+        .ok_or_else(|| err(CauseOfConfigErr::MalformedAbsence("dates need `anchor`")))?;
+    let day_index = |date: &toml::value::Datetime| {
+        let date = NaiveDate::parse_from_str(&date.to_string(), "%Y-%m-%d")
+            .map_err(|_| err(CauseOfConfigErr::MalformedAbsence("`from` and `to` must be dates")))?;
+        let (week, day) = calendar
+            .position_of(date)
+            .ok_or_else(|| err(CauseOfConfigErr::MalformedAbsence("the dates are before the anchor")))?;
+        Ok(week * 7 + day)
+    };
+    let first_day = day_index(&a.from)?;
+    let last_day = a.to.as_ref().map_or(Ok(first_day), day_index)?;
+    if last_day < first_day {
+        return Err(err(CauseOfConfigErr::MalformedAbsence("`to` is before `from`")));
+    }
+    let staff = parse_staff_ref(&a.staff, staff_group_list, source, CauseOfConfigErr::MalformedAbsence)?;
+    Ok(Absence { group_id: staff.group, staff_id: staff.id, first_day, last_day, slot: a.slot })
+}
+
+fn parse_leave(
+    raw: &Spanned<RawLeave>,
+    staff_group_list: &StaffGroupList,
+    source: &str,
+) -> Result<Leave, ConfigErr> {
+    let err = |reason| ConfigErr { location: Some(Location::from_offset(source, raw.span().start)), reason };
+    let l = raw.get_ref();
+    let date = |date: &toml::value::Datetime| {
+        NaiveDate::parse_from_str(&date.to_string(), "%Y-%m-%d")
+            .map_err(|_| err(CauseOfConfigErr::MalformedLeave("`from` and `to` must be dates")))
+    };
+    let from = date(&l.from)?;
+    let to = l.to.as_ref().map_or(Ok(from), date)?;
+    if to < from {
+        return Err(err(CauseOfConfigErr::MalformedLeave("`to` is before `from`")));
+    }
+    let staff = parse_staff_ref(&l.staff, staff_group_list, source, CauseOfConfigErr::MalformedLeave)?;
+    Ok(Leave { group_id: staff.group, staff_id: staff.id, from, to })
+}
+
+fn parse_holidays(raw: &Spanned<RawHolidays>, source: &str) -> Result<Holidays, ConfigErr> {
+    let location = Location::from_offset(source, raw.span().start);
+    let h = raw.get_ref();
+    let rest_days = parse_days(Some(h.rest_days.clone()), location)?;
+    let closed = h
+        .closed
+        .iter()
+        .map(|d| {
+            NaiveDate::parse_from_str(&d.to_string(), "%Y-%m-%d").map_err(|_| ConfigErr {
+                location: Some(location),
+                reason: CauseOfConfigErr::MalformedHolidays("`closed` must be dates"),
+            })
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(Holidays { rest_days, closed, window_days: h.window_days })
+}
+
+fn parse_compensatory(
+    raw: &Spanned<RawCompensatory>,
+    staff_group_list: &StaffGroupList,
+    source: &str,
+) -> Result<CompensatoryDay, ConfigErr> {
+    let err = |reason| ConfigErr { location: Some(Location::from_offset(source, raw.span().start)), reason };
+    let c = raw.get_ref();
+    let date = |date: &toml::value::Datetime| {
+        NaiveDate::parse_from_str(&date.to_string(), "%Y-%m-%d")
+            .map_err(|_| err(CauseOfConfigErr::MalformedCompensatory("`worked` and `off` must be dates")))
+    };
+    let (worked, off) = (date(&c.worked)?, date(&c.off)?);
+    if worked == off {
+        return Err(err(CauseOfConfigErr::MalformedCompensatory("`off` is the day worked")));
+    }
+    let staff = parse_staff_ref(&c.staff, staff_group_list, source, CauseOfConfigErr::MalformedCompensatory)?;
+    Ok(CompensatoryDay { group_id: staff.group, staff_id: staff.id, worked, off })
+}
+
+fn parse_attendance(
+    raw: &Spanned<RawAttendance>,
+    staff_group_list: &StaffGroupList,
+    source: &str,
+) -> Result<Attendance, ConfigErr> {
+    let err = |reason| ConfigErr { location: Some(Location::from_offset(source, raw.span().start)), reason };
+    let a = raw.get_ref();
+    let grant_date = NaiveDate::parse_from_str(&a.grant.to_string(), "%Y-%m-%d")
+        .map_err(|_| err(CauseOfConfigErr::MalformedAttendance("`grant` is not a date")))?;
+    if !(0.0..=1.0).contains(&a.rate) {
+        return Err(err(CauseOfConfigErr::MalformedAttendance("`rate` is not within 0 and 1")));
+    }
+    let staff = parse_staff_ref(&a.staff, staff_group_list, source, CauseOfConfigErr::MalformedAttendance)?;
+    Ok(Attendance { group_id: staff.group, staff_id: staff.id, grant_date, rate: a.rate })
+}
+
+/// the staff of the preference and the preference
+fn parse_preference(
+    raw: &Spanned<RawPreference>,
+    staff_group_list: &StaffGroupList,
+    source: &str,
+) -> Result<(HoleDoc, StaffPreference), ConfigErr> {
+    let location = Some(Location::from_offset(source, raw.span().start));
+    let err = |reason| ConfigErr { location, reason };
+    let p = raw.get_ref();
+    if p.day.is_none() && p.slot.is_none() && p.date.is_none() {
+        return Err(err(CauseOfConfigErr::MalformedPreference("give `day`, `slot` or `date`")));
+    }
+    let weekday = p
+        .day
+        .as_ref()
+        .map(|d| parse_weekday(d).ok_or_else(|| err(CauseOfConfigErr::UnknownWeekday(d.clone()))))
+        .transpose()?;
+    let date = p
+        .date
+        .as_ref()
+        .map(|d| {
+            NaiveDate::parse_from_str(&d.to_string(), "%Y-%m-%d")
+                .map_err(|_| err(CauseOfConfigErr::MalformedPreference("`date` is not a date")))
+        })
+        .transpose()?;
+    let staff = parse_staff_ref(&p.staff, staff_group_list, source, CauseOfConfigErr::MalformedPreference)?;
+    Ok((staff, StaffPreference { weekday, slot: p.slot, date, weight: p.weight }))
+}
+
+/// the staff of the pattern and the pattern, dates need the anchor
+fn parse_availability(
+    raw: &Spanned<RawAvailability>,
+    staff_group_list: &StaffGroupList,
+    anchor: Option<NaiveDate>,
+    source: &str,
+) -> Result<(HoleDoc, AvailabilityPattern), ConfigErr> {
+    let location = Location::from_offset(source, raw.span().start);
+    let err = |reason| ConfigErr { location: Some(location), reason };
+    let a = raw.get_ref();
+    if a.days.is_none() && a.slots.is_none() {
+        return Err(err(CauseOfConfigErr::MalformedAvailability("give `days` or `slots`")));
+    }
+    let date = |date: &Option<toml::value::Datetime>| {
+        date.as_ref()
+            .map(|d| {
+                NaiveDate::parse_from_str(&d.to_string(), "%Y-%m-%d")
+                    .map_err(|_| err(CauseOfConfigErr::MalformedAvailability("`from` and `to` must be dates")))
+            })
+            .transpose()
+    };
+    let (from, to) = (date(&a.from)?, date(&a.to)?);
+    if (from.is_some() || to.is_some()) && anchor.is_none() {
+        return Err(err(CauseOfConfigErr::MalformedAvailability("`from` and `to` need `anchor`")));
+    }
+    if let (Some(from), Some(to)) = (from, to)
+        && to < from
+    {
+        return Err(err(CauseOfConfigErr::MalformedAvailability("`to` is before `from`")));
+    }
+    let staff = parse_staff_ref(&a.staff, staff_group_list, source, CauseOfConfigErr::MalformedAvailability)?;
+    let pattern = AvailabilityPattern {
+        days: parse_days(a.days.clone(), location)?,
+        slots: a.slots.clone().unwrap_or_else(|| Slot::ALL.to_vec()),
+        from,
+        to,
+        only: a.only,
+    };
+    Ok((staff, pattern))
+}
+
+fn parse_slots(raw: &RawSlots, source: &str) -> Result<SlotDurations, ConfigErr> {
+    let minutes = |slot: &Option<Spanned<RawSlot>>, default: u32| {
+        let Some(spanned) = slot else {
+            return Ok(default);
+        };
+        let err = |reason| ConfigErr { location: Some(Location::from_offset(source, spanned.span().start)), reason };
+        let time = |t: &str| {
+            NaiveTime::parse_from_str(t, "%H:%M").map_err(|_| err(CauseOfConfigErr::MalformedSlot("time is not HH:MM")))
+        };
+        let slot = spanned.get_ref();
+        let length = match (slot.hours, &slot.start, &slot.end) {
+            (Some(hours), None, None) if (0.0..=24.0).contains(&hours) => (hours * 60.0).round() as u32,
+            (Some(_), None, None) => return Err(err(CauseOfConfigErr::MalformedSlot("`hours` is not within 0 and 24"))),
+            (None, Some(start), Some(end)) => {
+                let (start, end) = (time(start)?, time(end)?);
+                // a slot ending past midnight
+                let length = (end - start).num_minutes().rem_euclid(24 * 60);
+                length as u32
+            }
+            _ => return Err(err(CauseOfConfigErr::MalformedSlot("give either `hours` or `start` and `end`"))),
+        };
+        length
+            .checked_sub(slot.break_minutes)
+            .ok_or_else(|| err(CauseOfConfigErr::MalformedSlot("`break_minutes` is longer than the slot")))
+    };
+    let default = SlotDurations::default();
+    Ok(SlotDurations { morning: minutes(&raw.morning, default.morning)?, afternoon: minutes(&raw.afternoon, default.afternoon)? })
+}
+
+/// contracts by employment type first, then the ones naming staff, in file order
+///
+/// returns the table applied to each staff, in the order applied.
+fn apply_contracts(
+    raw: &[Spanned<RawContract>],
+    staff_group_list: &mut StaffGroupList,
+    source: &str,
+) -> Result<Vec<StaffCheckConfig>, ConfigErr> {
+    let mut applied = vec![];
+    for spanned in raw {
+        let location = Some(Location::from_offset(source, spanned.span().start));
+        match (&spanned.get_ref().employment_type, &spanned.get_ref().staff) {
+            (Some(_), None) | (None, Some(_)) => (),
+            _ => {
+                return Err(ConfigErr {
+                    location,
+                    reason: CauseOfConfigErr::MalformedContract("give either `employment_type` or `staff`"),
+                });
+            }
+        }
+    }
+    for spanned in raw {
+        let Some(employment_type) = &spanned.get_ref().employment_type else {
+            continue;
+        };
+        let employment_type = EmploymentType::parse(employment_type);
+        for group in &mut staff_group_list.0 {
+            for index in 0..group.len() {
+                let Some(staff) = group.staff_mut(index) else {
+                    continue;
+                };
+                if staff.profile.employment_type.as_ref() == Some(&employment_type) {
+                    staff.profile.contract = Some(spanned.get_ref().contract());
+                    applied.push(StaffCheckConfig {
+                        group_id: staff.get_group_id(),
+                        staff_id: staff.get_id(),
+                        severity: spanned.get_ref().severity,
+                        location: Location::from_offset(source, spanned.span().start),
+                    });
+                }
+            }
+        }
+    }
+    for spanned in raw {
+        for raw_staff in spanned.get_ref().staff.iter().flatten() {
+            let hole = parse_staff_ref(raw_staff, staff_group_list, source, CauseOfConfigErr::MalformedContract)?;
+            if let Some(staff) = staff_group_list.0[hole.group].staff_mut(hole.id) {
+                staff.profile.contract = Some(spanned.get_ref().contract());
+            }
+            applied.push(StaffCheckConfig {
+                group_id: hole.group,
+                staff_id: hole.id,
+                severity: spanned.get_ref().severity,
+                location: Location::from_offset(source, spanned.span().start),
+            });
+        }
+    }
+    Ok(applied)
+}
+
+/// `[[week]]` tables of a rule table, in the format read by `ShiftConfig`
+pub fn week_tables_to_toml(rule_table: &[WeekRuleDoc], staff_group_list: &StaffGroupList) -> String {
+    const DAY_KEYS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+    let holes = |holes: &[HoleDoc]| {
+        let holes: Vec<String> =
+            holes.iter().map(|h| toml::Value::String(hole_to_toml(h, staff_group_list)).to_string()).collect();
+        format!("[{}]", holes.join(", "))
+    };
+    let mut out = String::new();
+    for week in rule_table {
+        out.push_str("[[week]]\n");
+        for (key, day) in DAY_KEYS.iter().zip(&week.days) {
+            let slots: Vec<String> = [("morning", &day.morning), ("afternoon", &day.afternoon)]
+                .into_iter()
+                .filter(|(_, h)| !h.is_empty())
+                .map(|(slot, h)| format!("{} = {}", slot, holes(h)))
+                .collect();
+            if !slots.is_empty() {
+                out.push_str(&format!("{} = {{ {} }}\n", key, slots.join(", ")));
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// a hole as written in a `[[week]]` rule, `B:0|A:0` with fallbacks
+fn hole_to_toml(hole: &HoleDoc, staff_group_list: &StaffGroupList) -> String {
+    std::iter::once(hole)
+        .chain(&hole.fallback)
+        .map(|h| format!("{}:{}", staff_group_list.0.get(h.group).map_or("", |g| g.get_name()), h.id))
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+/// Put the holes of a day rule into the table of a day, an inline
+/// `mon = { ... }` or a `[week.mon]` sub-table, keeping the way it is written
+fn set_day_rule(week: &mut toml_edit::Table, key: &str, day: &DayRuleDoc, staff_group_list: &StaffGroupList) {
+    let slots = [("morning", &day.morning), ("afternoon", &day.afternoon)];
+    let array = |holes: &[HoleDoc]| holes.iter().map(|h| hole_to_toml(h, staff_group_list)).collect::<toml_edit::Array>();
+    if slots.iter().all(|(_, holes)| holes.is_empty()) {
+        week.remove(key);
+        return;
+    }
+    if let Some(table) = week.get_mut(key).and_then(toml_edit::Item::as_table_mut) {
+        for (slot, holes) in slots {
+            if holes.is_empty() {
+                table.remove(slot);
+            } else {
+                table[slot] = toml_edit::value(array(holes));
+            }
+        }
+        return;
+    }
+    let mut inline = toml_edit::InlineTable::new();
+    for (slot, holes) in slots.into_iter().filter(|(_, holes)| !holes.is_empty()) {
+        inline.insert(slot, array(holes).into());
+    }
+    match week.get_mut(key).and_then(toml_edit::Item::as_value_mut) {
+        // the comment after the old value stays
+        Some(old) => {
+            let decor = old.decor().clone();
+            *old = inline.into();
+            *old.decor_mut() = decor;
+        }
+        None => {
+            week.insert(key, toml_edit::value(inline));
+        }
+    }
+}
+
+/// `[[override]]` tables, by date when there is an anchor
+pub fn overrides_to_toml(overrides: &[ShiftOverride], staff_group_list: &StaffGroupList, anchor: Option<NaiveDate>) -> String {
+    const DAY_KEYS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+    let mut out = String::new();
+    for o in overrides {
+        out.push_str("[[override]]\n");
+        match anchor.map(ShiftCalendar::new) {
+            Some(calendar) => out.push_str(&format!("date = {}\n", calendar.date_of(o.week, o.day))),
+            None => out.push_str(&format!("week = {}\nday = \"{}\"\n", o.week, DAY_KEYS[o.day])),
+        }
+        let slot = match o.slot {
+            Slot::Morning => "morning",
+            Slot::Afternoon => "afternoon",
+        };
+        let group = staff_group_list.0.get(o.group_id).map_or("", |g| g.get_name());
+        let staff = toml::Value::String(format!("{}:{}", group, o.staff_id));
+        out.push_str(&format!("slot = \"{}\"\nhole = {}\nstaff = {}\n\n", slot, o.hole, staff));
+    }
+    out
+}
+
+/// `[[compensatory]]` tables
+pub fn compensatory_days_to_toml(days: &[CompensatoryDay], staff_group_list: &StaffGroupList) -> String {
+    let mut out = String::new();
+    for c in days {
+        let group = staff_group_list.0.get(c.group_id).map_or("", |g| g.get_name());
+        let staff = toml::Value::String(format!("{}:{}", group, c.staff_id));
+        out.push_str(&format!("[[compensatory]]\nstaff = {}\nworked = {}\noff = {}\n\n", staff, c.worked, c.off));
+    }
+    out
+}
+
+impl ShiftConfig {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ConfigErr> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path)
+            .map_err(|e| ConfigErr { location: None, reason: CauseOfConfigErr::Io(path.to_path_buf(), e) })?;
+        let mut config = Self::parse(&source, path.parent())?;
+        config.path = Some(path.to_path_buf());
+        Ok(config)
+    }
+
+    /// `base_dir` resolves the path of the roster csv
+    pub fn parse(source: &str, base_dir: Option<&Path>) -> Result<Self, ConfigErr> {
+        let raw: RawConfig = toml::from_str(source).map_err(|e| ConfigErr {
+            location: e.span().map(|span| Location::from_offset(source, span.start)),
+            reason: CauseOfConfigErr::Toml(e),
+        })?;
+
+        let anchor = raw
+            .anchor
+            .map(|anchor| {
+                let err = |reason| ConfigErr { location: Some(Location::from_offset(source, anchor.span().start)), reason };
+                let date = NaiveDate::parse_from_str(&anchor.get_ref().to_string(), "%Y-%m-%d")
+                    .map_err(|_| err(CauseOfConfigErr::InvalidAnchor(anchor.get_ref().to_string())))?;
+                if date.weekday() != Weekday::Mon {
+                    return Err(err(CauseOfConfigErr::AnchorNotMonday(date)));
+                }
+                Ok(date)
+            })
+            .transpose()?;
+
+        let mut staff_group_list = match (raw.roster, raw.group.is_empty()) {
+            (Some(roster), true) => {
+                let roster_path = base_dir.map_or_else(|| PathBuf::from(&roster), |dir| dir.join(&roster));
+                let file = std::fs::File::open(&roster_path)
+                    .map_err(|e| ConfigErr { location: None, reason: CauseOfConfigErr::Io(roster_path.clone(), e) })?;
+                import_roster_csv(file)
+                    .map_err(|errors| ConfigErr { location: None, reason: CauseOfConfigErr::Roster(errors) })?
+            }
+            (None, false) => {
+                let mut staff_group_list = StaffGroupList::new();
+                for group in &raw.group {
+                    let mut staff_group = StaffGroup::new(&group.name);
+                    for name in &group.staff {
+                        staff_group.add_staff(name);
+                    }
+                    staff_group_list.add_staff_group(staff_group);
+                }
+                staff_group_list
+            }
+            _ => return Err(ConfigErr { location: None, reason: CauseOfConfigErr::RosterSourceErr }),
+        };
+
+        for raw in &raw.preference {
+            let (staff, preference) = parse_preference(raw, &staff_group_list, source)?;
+            if let Some(staff) = staff_group_list.0[staff.group].staff_mut(staff.id) {
+                staff.profile.preferences.push(preference);
+            }
+        }
+
+        let mut availability_checks = vec![];
+        for raw in &raw.availability {
+            let (staff, pattern) = parse_availability(raw, &staff_group_list, anchor, source)?;
+            if let Some(staff) = staff_group_list.0[staff.group].staff_mut(staff.id) {
+                staff.profile.availability.push(pattern);
+            }
+            availability_checks.push(StaffCheckConfig {
+                group_id: staff.group,
+                staff_id: staff.id,
+                severity: raw.get_ref().severity,
+                location: Location::from_offset(source, raw.span().start),
+            });
+        }
+
+        for skill in &raw.skill {
+            for raw in &skill.staff {
+                let staff = parse_staff_ref(raw, &staff_group_list, source, CauseOfConfigErr::MalformedSkill)?;
+                if let Some(staff) = staff_group_list.0[staff.group].staff_mut(staff.id) {
+                    staff.profile.skills.insert(skill.name.clone());
+                }
+            }
+        }
+
+        let contract_checks = apply_contracts(&raw.contract, &mut staff_group_list, source)?;
+
+        let mut rule_table = vec![];
+        let mut hole_spans = vec![];
+        let mut week_spans = vec![];
+        for (week_rule_index, week) in raw.week.into_iter().enumerate() {
+            week_spans.push(week.span());
+            let mut days = vec![];
+            for (day, raw_day) in week.into_inner().into_days().into_iter().enumerate() {
+                let mut parse_slot = |slot: Slot, holes: &[Spanned<String>]| {
+                    holes
+                        .iter()
+                        .enumerate()
+                        .map(|(index, raw)| {
+                            hole_spans.push((HolePosition { week_rule_index, day, slot, index }, raw.span()));
+                            parse_rule_hole(raw, &staff_group_list, source)
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                };
+                let morning = parse_slot(Slot::Morning, &raw_day.morning)?;
+                let afternoon = parse_slot(Slot::Afternoon, &raw_day.afternoon)?;
+                days.push(DayRuleDoc { morning, afternoon });
+            }
+            rule_table.push(WeekRuleDoc { days });
+        }
+
+        let checks = parse_checks(raw.checks, &staff_group_list, source)?;
+
+        let overrides = raw
+            .overrides
+            .iter()
+            .map(|o| parse_override(o, anchor, &staff_group_list, source))
+            .collect::<Result<_, _>>()?;
+        let override_spans = raw.overrides.iter().map(|o| o.span()).collect();
+
+        let mut absences: Vec<Absence> = raw
+            .absence
+            .iter()
+            .map(|a| parse_absence(a, anchor, &staff_group_list, source))
+            .collect::<Result<_, _>>()?;
+        let check = |group_id, staff_id, severity, span: Range<usize>| StaffCheckConfig {
+            group_id,
+            staff_id,
+            severity,
+            location: Location::from_offset(source, span.start),
+        };
+        let mut absence_checks: Vec<StaffCheckConfig> = absences
+            .iter()
+            .zip(&raw.absence)
+            .map(|(a, raw)| check(a.group_id, a.staff_id, raw.get_ref().severity, raw.span()))
+            .collect();
+
+        let leaves: Vec<Leave> = raw
+            .leave
+            .iter()
+            .map(|l| parse_leave(l, &staff_group_list, source))
+            .collect::<Result<_, _>>()?;
+        // leave after the anchor is an absence too, rotation hands its holes on
+        if let Some(calendar) = anchor.map(ShiftCalendar::new) {
+            for (leave, raw) in leaves.iter().zip(&raw.leave) {
+                let day_index = |date| calendar.position_of(date).map(|(week, day)| week * 7 + day);
+                let Some(last_day) = day_index(leave.to) else {
+                    continue;
+                };
+                let first_day = day_index(leave.from).unwrap_or(0);
+                absences.push(Absence { group_id: leave.group_id, staff_id: leave.staff_id, first_day, last_day, slot: None });
+                absence_checks.push(check(leave.group_id, leave.staff_id, raw.get_ref().severity, raw.span()));
+            }
+        }
+
+        let holidays = raw.holidays.as_ref().map(|h| parse_holidays(h, source)).transpose()?;
+        let compensatory_days: Vec<CompensatoryDay> = raw
+            .compensatory
+            .iter()
+            .map(|c| parse_compensatory(c, &staff_group_list, source))
+            .collect::<Result<_, _>>()?;
+        // so is the day off given for holiday work
+        if let Some(calendar) = anchor.map(ShiftCalendar::new) {
+            for (c, raw) in compensatory_days.iter().zip(&raw.compensatory) {
+                if let Some((week, day)) = calendar.position_of(c.off) {
+                    let day_index = week * 7 + day;
+                    absences.push(Absence { group_id: c.group_id, staff_id: c.staff_id, first_day: day_index, last_day: day_index, slot: None });
+                    absence_checks.push(check(c.group_id, c.staff_id, raw.get_ref().severity, raw.span()));
+                }
+            }
+        }
+
+        let attendance = raw
+            .attendance
+            .iter()
+            .map(|a| parse_attendance(a, &staff_group_list, source))
+            .collect::<Result<_, _>>()?;
+
+        let slot_durations = parse_slots(&raw.slots, source)?;
+
+        let needs = raw
+            .need
+            .into_iter()
+            .map(|spanned| {
+                let location = Location::from_offset(source, spanned.span().start);
+                let need = spanned.into_inner();
+                Ok(CoverageNeed {
+                    group_id: find_group(&staff_group_list, &need.group, location)?,
+                    days: parse_days(need.days, location)?,
+                    slots: need.slots.unwrap_or_else(|| Slot::ALL.to_vec()),
+                    count: need.count,
+                })
+            })
+            .collect::<Result<_, ConfigErr>>()?;
+
+        Ok(Self {
+            path: None,
+            source: source.to_string(),
+            anchor,
+            staff_group_list,
+            rule_table,
+            checks,
+            overrides,
+            needs,
+            absences,
+            slot_durations,
+            leaves,
+            attendance,
+            holidays,
+            compensatory_days,
+            contract_checks,
+            availability_checks,
+            absence_checks,
+            hole_spans,
+            week_spans,
+            override_spans,
+        })
+    }
+
+    pub fn week_rule_table<'a>(&self) -> WeekRuleTable<'a, Incomplete> {
+        rule_table_from_doc(&self.rule_table)
+    }
+
+    /// Needs and quotas of the config for `synthesize_rule_table`
+    pub fn synth_request(&self, cycle: Option<usize>) -> SynthRequest {
+        let quotas = self
+            .checks
+            .quota
+            .iter()
+            .map(|q| StaffQuota { group_id: q.group_id, min_per_week: q.min_per_week, max_per_week: q.max_per_week })
+            .collect();
+        SynthRequest { needs: self.needs.clone(), quotas, cycle }
+    }
+
+    /// Generate the schedule of a range of weeks and apply the overrides
+    ///
+    /// fails when there is no `[[week]]` rule or a hole names a group
+    /// without staff.
+    pub fn schedule(&self, week_delta: usize, week_gen_range: usize) -> Result<DecidedSchedule<'_>, SolveErr> {
+        self.generate(&Generator::Rotation, week_delta, week_gen_range)
+    }
+
+    /// Generate with the given generator and apply the overrides
+    ///
+    /// the holes rotation gives to staff who are not available or away on an
+    /// absence, leave or compensatory day off, or that fall back to other
+    /// groups, go to other staff through `assign_available` first.
+    pub fn generate(&self, generator: &Generator, week_delta: usize, week_gen_range: usize) -> Result<DecidedSchedule<'_>, SolveErr> {
+        self.generate_from(&self.week_rule_table(), generator, week_delta, week_gen_range)
+    }
+
+    /// `generate` on another rule table, one a `StartOffset` made for instance
+    pub fn generate_from<'a>(
+        &'a self,
+        week_rule_table: &WeekRuleTable<'a, Incomplete>,
+        generator: &Generator,
+        week_delta: usize,
+        week_gen_range: usize,
+    ) -> Result<DecidedSchedule<'a>, SolveErr> {
+        let calendar = self.anchor.map(ShiftCalendar::new);
+        let mut schedule = generator.generate(
+            week_rule_table,
+            &self.staff_group_list,
+            week_delta,
+            week_gen_range,
+            calendar.as_ref(),
+            &self.absences,
+        )?;
+        apply_overrides(&mut schedule.weeks, &self.staff_group_list, week_delta, &self.overrides);
+        Ok(schedule)
+    }
+
+    /// Last week the dated availability patterns, absences or overrides
+    /// change the schedule in
+    fn last_dated_week(&self) -> Option<usize> {
+        let calendar = self.anchor.map(ShiftCalendar::new);
+        let pattern_weeks = self
+            .staff_group_list
+            .0
+            .iter()
+            .flat_map(|g| g.iter())
+            .flat_map(|s| &s.profile.availability)
+            .flat_map(|p| [p.from, p.to])
+            .flatten()
+            .filter_map(|date| calendar.and_then(|c| c.position_of(date)).map(|(week, _)| week));
+        pattern_weeks
+            .chain(self.absences.iter().map(|a| a.last_day / 7))
+            .chain(self.overrides.iter().map(|o| o.week))
+            .max()
+    }
+
+    /// Repeat periods of the schedule `generate` makes with rotation, over
+    /// weeks covering the dated availability, absences and overrides
+    pub fn analyze_period(&self) -> Option<PeriodAnalysis<'_>> {
+        analyze_period(&self.week_rule_table(), &self.staff_group_list, self.last_dated_week(), |weeks| {
+            self.generate(&Generator::Rotation, 0, weeks).ok()
+        })
+    }
+
+    /// Start offsets ranked against `history` on the weeks `generate` makes
+    /// with rotation from the table of each offset
+    pub fn rank_offsets(&self, history: &AssignmentCounts, week_delta: usize, week_gen_range: usize) -> Vec<OffsetScore> {
+        rank_offsets(&self.week_rule_table(), &self.staff_group_list, history, |table| {
+            self.generate_from(table, &Generator::Rotation, week_delta, week_gen_range).ok()
+        })
+    }
+
+    /// Repair a published schedule for this config and apply the overrides
+    pub fn repair(
+        &self,
+        published: &Published<'_, '_>,
+        config: &SolverConfig,
+        week_delta: usize,
+        week_gen_range: usize,
+    ) -> Result<DecidedSchedule<'_>, SolveErr> {
+        let mut schedule = repair_schedule(
+            published,
+            &self.week_rule_table(),
+            &self.staff_group_list,
+            week_delta,
+            week_gen_range,
+            config,
+        )?;
+        apply_overrides(&mut schedule.weeks, &self.staff_group_list, week_delta, &self.overrides);
+        Ok(schedule)
+    }
+
+    /// Solver constraints taken from `[[checks.quota]]`, `[checks.labour]`,
+    /// `[checks.overtime]`, the `[[contract]]` maximums and `[[absence]]`
+    ///
+    /// the contract minimums and `[[checks.skill]]` are left to the checkers,
+    /// the solver does not keep them.
+    pub fn solver_config(&self) -> SolverConfig {
+        SolverConfig {
+            quotas: self.synth_request(None).quotas,
+            max_consecutive_days: self.checks.labour.as_ref().and_then(|l| l.max_consecutive_days),
+            min_days_off_per_week: self.checks.labour.as_ref().and_then(|l| l.min_days_off_per_week),
+            unavailable: self.absences.iter().flat_map(Absence::unavailability).collect(),
+            calendar: self.anchor.map(ShiftCalendar::new),
+            slot_durations: self.slot_durations,
+            statutory_limits: self.checks.overtime.as_ref().map(|o| o.limits),
+            contract_maximums: true,
+            ..SolverConfig::default()
+        }
+    }
+
+    /// Paid leave of a staff up to a date, `None` without a join date
+    pub fn leave_ledger(&self, staff: &Staff, until: NaiveDate) -> Option<LeaveLedger> {
+        let join_date = staff.profile.join_date?;
+        let is_staff = |group_id, staff_id| (group_id, staff_id) == (staff.get_group_id(), staff.get_id());
+        let attendance = self
+            .attendance
+            .iter()
+            .filter(|a| is_staff(a.group_id, a.staff_id))
+            .map(|a| (a.grant_date, a.rate))
+            .collect();
+        // leave is taken on working days, rest days need the anchor to fall on
+        let is_holiday = |date: NaiveDate| {
+            self.holidays.as_ref().is_some_and(|h| {
+                let day = self.anchor.map(|anchor| (date - anchor).num_days().rem_euclid(7) as usize);
+                match day {
+                    Some(day) => h.is_holiday(day, date),
+                    None => h.closed.contains(&date),
+                }
+            })
+        };
+        let taken: Vec<NaiveDate> = self
+            .leaves
+            .iter()
+            .filter(|l| is_staff(l.group_id, l.staff_id))
+            .flat_map(Leave::dates)
+            .filter(|d| !is_holiday(*d))
+            .collect();
+        Some(build_ledger(join_date, grant_days_per_week(staff), &attendance, &taken, until))
+    }
+
+    /// Rotation meeting the `[[checks.skill]]` requirements where it can
+    pub fn qualified_rotation(&self) -> Generator {
+        Generator::QualifiedRotation(self.checks.skill.iter().map(|s| s.requirement.clone()).collect())
+    }
+
+    /// location of a hole in the config file
+    pub fn hole_location(&self, position: HolePosition) -> Option<Location> {
+        self.hole_spans
+            .iter()
+            .find(|(p, _)| *p == position)
+            .map(|(_, span)| Location::from_offset(&self.source, span.start))
+    }
+
+    /// location of a `[[week]]` table in the config file
+    pub fn week_location(&self, week_rule_index: usize) -> Option<Location> {
+        self.week_spans
+            .get(week_rule_index)
+            .map(|span| Location::from_offset(&self.source, span.start))
+    }
+
+    /// location of an `[[override]]` table in the config file
+    pub fn override_location(&self, index: usize) -> Option<Location> {
+        self.override_spans
+            .get(index)
+            .map(|span| Location::from_offset(&self.source, span.start))
+    }
+
+    /// location of an `[[absence]]` table in the config file
+    pub fn absence_location(&self, index: usize) -> Option<Location> {
+        self.absence_checks.get(index).map(|c| c.location)
+    }
+
+    /// Source of the config with `[[override]]` tables appended
+    pub fn source_with_overrides(&self, overrides: &[ShiftOverride]) -> String {
+        let mut source = self.source.trim_end().to_string();
+        source.push_str("\n\n");
+        source.push_str(overrides_to_toml(overrides, &self.staff_group_list, self.anchor).trim_end());
+        source.push('\n');
+        source
+    }
+
+    /// Source of the config with the planned days off appended as
+    /// `[[compensatory]]` tables and their overrides
+    pub fn source_with_compensation(&self, plans: &[CompensationPlan]) -> String {
+        let days: Vec<CompensatoryDay> = plans.iter().map(|p| p.compensatory_day).collect();
+        let overrides: Vec<ShiftOverride> = plans.iter().flat_map(|p| p.overrides.iter().copied()).collect();
+        let mut source = self.source.trim_end().to_string();
+        source.push_str("\n\n");
+        source.push_str(&compensatory_days_to_toml(&days, &self.staff_group_list));
+        source.push_str(overrides_to_toml(&overrides, &self.staff_group_list, self.anchor).trim_end());
+        source.push('\n');
+        source
+    }
+
+    /// Source of the config with its `[[week]]` tables replaced
+    ///
+    /// the tables are edited in place, each day keeping its inline or
+    /// `[week.<day>]` form and the rest of the file its layout. Added rules
+    /// go after the last one, or to the end of the file when there was none.
+    pub fn source_with_rule_table(&self, rule_table: &[WeekRuleDoc]) -> String {
+        const DAY_KEYS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+        let mut document: toml_edit::DocumentMut = self.source.parse().expect("the source of a config is toml");
+        if rule_table.is_empty() {
+            document.remove("week");
+            return document.to_string();
+        }
+        if !document.contains_array_of_tables("week") {
+            document.insert("week", toml_edit::Item::ArrayOfTables(toml_edit::ArrayOfTables::new()));
+        }
+        let weeks = document["week"].as_array_of_tables_mut().expect("`week` is an array of tables");
+        while weeks.len() > rule_table.len() {
+            weeks.remove(weeks.len() - 1);
+        }
+        while weeks.len() < rule_table.len() {
+            weeks.push(toml_edit::Table::new());
+        }
+        for (week, rule) in weeks.iter_mut().zip(rule_table) {
+            for (key, day) in DAY_KEYS.iter().zip(&rule.days) {
+                set_day_rule(week, key, day, &self.staff_group_list);
+            }
+        }
+        document.to_string()
+    }
+}
@@ -0,0 +1,231 @@
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::calendar::ShiftCalendar;
+use crate::shift_gen::{
+    DecidedSchedule, Slot, Staff, StaffGroupList
+};
+
+/// Minutes worked in each slot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlotDurations {
+    pub morning: u32,
+    pub afternoon: u32,
+}
+
+impl Default for SlotDurations {
+    /// 4 hours each
+    fn default() -> Self {
+        Self { morning: 240, afternoon: 240 }
+    }
+}
+
+impl SlotDurations {
+    pub fn minutes(&self, slot: Slot) -> u32 {
+        match slot {
+            Slot::Morning => self.morning,
+            Slot::Afternoon => self.afternoon,
+        }
+    }
+}
+
+/// `20h`, `22h30`
+pub fn format_hours(minutes: u32) -> String {
+    match minutes % 60 {
+        0 => format!("{}h", minutes / 60),
+        m => format!("{}h{:02}", minutes / 60, m),
+    }
+}
+
+/// Day and hour limits of an employment contract, `None` is no limit
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Contract {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_days_per_week: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_days_per_week: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_hours_per_week: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_hours_per_week: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_days_per_month: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_days_per_month: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_hours_per_month: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_hours_per_month: Option<u32>,
+}
+
+impl Contract {
+    /// (min, max) of a measure over a period, hours in minutes
+    pub fn bounds(&self, period: Period, measure: Measure) -> (Option<u32>, Option<u32>) {
+        let minutes = |h: Option<u32>| h.map(|h| h * 60);
+        match (period, measure) {
+            (Period::Week(_), Measure::Days) => (self.min_days_per_week, self.max_days_per_week),
+            (Period::Week(_), Measure::Hours) => (minutes(self.min_hours_per_week), minutes(self.max_hours_per_week)),
+            (Period::Month { .. }, Measure::Days) => (self.min_days_per_month, self.max_days_per_month),
+            (Period::Month { .. }, Measure::Hours) => (minutes(self.min_hours_per_month), minutes(self.max_hours_per_month)),
+        }
+    }
+}
+
+/// Week or calendar month a contract limit applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Period {
+    /// absolute week index
+    Week(usize),
+    Month { year: i32, month: u32 },
+}
+
+impl std::fmt::Display for Period {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Period::Week(week) => write!(f, "week {}", week),
+            Period::Month { year, month } => write!(f, "{}-{:02}", year, month),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Measure {
+    Days,
+    Hours,
+}
+
+/// Work of a staff within a period
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Usage {
+    /// days with at least one assignment
+    pub days: u32,
+    pub minutes: u32,
+    /// days of the period in the schedule, minimums are only checked on
+    /// periods the schedule covers whole
+    pub covered_days: u32,
+    /// absolute week of the first day of the period in the schedule
+    pub first_week: usize,
+}
+
+impl Usage {
+    /// days, or minutes for hours
+    pub fn value(&self, measure: Measure) -> u32 {
+        match measure {
+            Measure::Days => self.days,
+            Measure::Hours => self.minutes,
+        }
+    }
+}
+
+/// A contract limit a staff does not keep in a period
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Breach {
+    pub period: Period,
+    pub measure: Measure,
+    /// days, or minutes for hours
+    pub value: u32,
+    pub limit: u32,
+    /// above the maximum, else under the minimum
+    pub over: bool,
+}
+
+impl std::fmt::Display for Breach {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let amount = |v: u32| match self.measure {
+            Measure::Days => format!("{} days", v),
+            Measure::Hours => format_hours(v),
+        };
+        let bound = if self.over { "at most" } else { "at least" };
+        write!(f, "{} in {}, contract is {} {}", amount(self.value), self.period, bound, amount(self.limit))
+    }
+}
+
+/// Work of a staff per week and month against their contract
+#[derive(Debug, Clone)]
+pub struct Utilisation<'a> {
+    pub staff: &'a Staff,
+    /// weeks, then months when there is a calendar, in order
+    pub periods: Vec<(Period, Usage)>,
+    /// empty without a contract
+    pub breaches: Vec<Breach>,
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    let next = if month == 12 { NaiveDate::from_ymd_opt(year + 1, 1, 1) } else { NaiveDate::from_ymd_opt(year, month + 1, 1) };
+    next.and_then(|n| n.pred_opt()).map_or(31, |last| last.day())
+}
+
+/// Utilisation of every staff of the roster, in roster order
+///
+/// months need the calendar of the schedule; a month partly generated is
+/// reported with what the schedule holds of it and only checked against
+/// maximums.
+pub fn utilisation<'a>(
+    schedule: &DecidedSchedule<'_>,
+    staff_group_list: &'a StaffGroupList,
+    durations: &SlotDurations,
+    calendar: Option<&ShiftCalendar>,
+) -> Vec<Utilisation<'a>> {
+    let mut periods: BTreeMap<Period, Usage> = BTreeMap::new();
+    let mut worked: BTreeMap<((usize, usize), Period), Usage> = BTreeMap::new();
+    for (week, day, day_shift) in schedule.days() {
+        let mut day_periods = vec![Period::Week(week)];
+        if let Some(date) = calendar.map(|c| c.date_of(week, day)) {
+            day_periods.push(Period::Month { year: date.year(), month: date.month() });
+        }
+        let mut minutes: BTreeMap<(usize, usize), u32> = BTreeMap::new();
+        for slot in Slot::ALL {
+            for staff in day_shift.slot(slot) {
+                *minutes.entry((staff.get_group_id(), staff.get_id())).or_default() += durations.minutes(slot);
+            }
+        }
+        for period in day_periods {
+            let usage = periods.entry(period).or_insert(Usage { first_week: week, ..Usage::default() });
+            usage.covered_days += 1;
+            for (&key, &m) in &minutes {
+                let usage = worked.entry((key, period)).or_default();
+                usage.days += 1;
+                usage.minutes += m;
+            }
+        }
+    }
+
+    staff_group_list
+        .0
+        .iter()
+        .flat_map(|group| group.iter())
+        .map(|staff| {
+            let key = (staff.get_group_id(), staff.get_id());
+            let periods: Vec<(Period, Usage)> = periods
+                .iter()
+                .map(|(&period, covered)| {
+                    let usage = worked.get(&(key, period)).copied().unwrap_or_default();
+                    (period, Usage { covered_days: covered.covered_days, first_week: covered.first_week, ..usage })
+                })
+                .collect();
+            let mut breaches = vec![];
+            if let Some(contract) = &staff.profile.contract {
+                for (period, usage) in &periods {
+                    let complete = match period {
+                        Period::Week(_) => true,
+                        Period::Month { year, month } => usage.covered_days == days_in_month(*year, *month),
+                    };
+                    for measure in [Measure::Days, Measure::Hours] {
+                        let value = usage.value(measure);
+                        let breach = match contract.bounds(*period, measure) {
+                            (_, Some(max)) if value > max => Breach { period: *period, measure, value, limit: max, over: true },
+                            (Some(min), _) if complete && value < min => {
+                                Breach { period: *period, measure, value, limit: min, over: false }
+                            }
+                            _ => continue,
+                        };
+                        breaches.push(breach);
+                    }
+                }
+            }
+            Utilisation { staff, periods, breaches }
+        })
+        .collect()
+}
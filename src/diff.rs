@@ -0,0 +1,197 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::calendar::ShiftCalendar;
+use crate::render::WEEKDAY_NAMES;
+use crate::shift_gen::{
+    DecidedSchedule, Slot, Staff, StaffGroupList
+};
+
+/// Identity of a staff across two rosters
+///
+/// the employee id when there is one, else the group and staff names.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StaffKey(pub String);
+
+impl StaffKey {
+    pub fn of(staff: &Staff, staff_group_list: &StaffGroupList) -> Self {
+        match &staff.profile.employee_id {
+            Some(id) => Self(id.clone()),
+            None => {
+                let group = staff_group_list.0.get(staff.get_group_id()).map_or("", |g| g.get_name());
+                Self(format!("{}/{}", group, staff.name))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SlotDate {
+    pub date: NaiveDate,
+    pub slot: Slot,
+}
+
+impl std::fmt::Display for SlotDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let weekday = WEEKDAY_NAMES[self.date.weekday().num_days_from_monday() as usize];
+        write!(f, "{} {} {}", self.date, weekday, self.slot.label())
+    }
+}
+
+/// Changes in one slot of one date
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlotDiff {
+    pub at: SlotDate,
+    /// names of the staff
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+/// Changes of one staff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaffDiff {
+    pub key: StaffKey,
+    pub name: String,
+    pub added: Vec<SlotDate>,
+    pub removed: Vec<SlotDate>,
+    /// removed from the first slot and added to the second on the same date
+    /// or in the same iso week
+    pub moved: Vec<(SlotDate, SlotDate)>,
+}
+
+/// Difference of two schedules over the same dates
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScheduleDiff {
+    pub by_date: Vec<SlotDiff>,
+    pub by_staff: Vec<StaffDiff>,
+}
+
+type Assignments = BTreeMap<SlotDate, Vec<(StaffKey, String)>>;
+
+fn collect(
+    schedule: &DecidedSchedule<'_>,
+    staff_group_list: &StaffGroupList,
+    calendar: &ShiftCalendar,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Assignments {
+    let mut assignments = Assignments::new();
+    for (week, day, day_shift) in schedule.days() {
+        let date = calendar.date_of(week, day);
+        if date < from || to < date {
+            continue;
+        }
+        for slot in Slot::ALL {
+            let entry = assignments.entry(SlotDate { date, slot }).or_default();
+            entry.extend(day_shift.slot(slot).iter().map(|s| (StaffKey::of(s, staff_group_list), s.name.clone())));
+        }
+    }
+    assignments
+}
+
+/// Side of a schedule compared by `diff_schedules`
+pub struct DiffSide<'s, 'a> {
+    pub schedule: &'s DecidedSchedule<'a>,
+    pub staff_group_list: &'a StaffGroupList,
+    pub calendar: ShiftCalendar,
+}
+
+/// Compare the assignments of two schedules between two dates, both included
+pub fn diff_schedules(old: &DiffSide<'_, '_>, new: &DiffSide<'_, '_>, from: NaiveDate, to: NaiveDate) -> ScheduleDiff {
+    let old = collect(old.schedule, old.staff_group_list, &old.calendar, from, to);
+    let new = collect(new.schedule, new.staff_group_list, &new.calendar, from, to);
+    let empty = vec![];
+
+    let mut by_date = vec![];
+    let mut by_staff: BTreeMap<StaffKey, StaffDiff> = BTreeMap::new();
+    let slots: BTreeSet<&SlotDate> = old.keys().chain(new.keys()).collect();
+    for at in slots {
+        let before = old.get(at).unwrap_or(&empty);
+        let after = new.get(at).unwrap_or(&empty);
+        let added: Vec<&(StaffKey, String)> = after.iter().filter(|s| !before.iter().any(|b| b.0 == s.0)).collect();
+        let removed: Vec<&(StaffKey, String)> = before.iter().filter(|s| !after.iter().any(|a| a.0 == s.0)).collect();
+        if added.is_empty() && removed.is_empty() {
+            continue;
+        }
+        for (is_added, (key, name)) in added.iter().map(|s| (true, s)).chain(removed.iter().map(|s| (false, s))) {
+            let staff = by_staff.entry(key.clone()).or_insert_with(|| StaffDiff {
+                key: key.clone(),
+                name: name.clone(),
+                added: vec![],
+                removed: vec![],
+                moved: vec![],
+            });
+            if is_added {
+                staff.added.push(*at);
+            } else {
+                staff.removed.push(*at);
+            }
+        }
+        by_date.push(SlotDiff {
+            at: *at,
+            added: added.iter().map(|s| s.1.clone()).collect(),
+            removed: removed.iter().map(|s| s.1.clone()).collect(),
+        });
+    }
+
+    let by_staff = by_staff
+        .into_values()
+        .map(|mut staff| {
+            // pair a removal with an addition on the same date, else in the same iso week
+            let mut removed = vec![];
+            for r in std::mem::take(&mut staff.removed) {
+                let paired = staff
+                    .added
+                    .iter()
+                    .position(|a| a.date == r.date)
+                    .or_else(|| staff.added.iter().position(|a| a.date.iso_week() == r.date.iso_week()));
+                match paired {
+                    Some(i) => {
+                        let a = staff.added.remove(i);
+                        staff.moved.push((r, a));
+                    }
+                    None => removed.push(r),
+                }
+            }
+            staff.removed = removed;
+            staff
+        })
+        .collect();
+
+    ScheduleDiff { by_date, by_staff }
+}
+
+impl ScheduleDiff {
+    pub fn is_empty(&self) -> bool {
+        self.by_date.is_empty()
+    }
+
+    /// Text grouped by date, then by staff
+    pub fn render(&self) -> String {
+        let mut out = String::from("# by date\n");
+        for slot in &self.by_date {
+            let changes: Vec<String> = slot
+                .removed
+                .iter()
+                .map(|n| format!("-{}", n))
+                .chain(slot.added.iter().map(|n| format!("+{}", n)))
+                .collect();
+            out.push_str(&format!("{}: {}\n", slot.at, changes.join(" ")));
+        }
+        out.push_str("\n# by staff\n");
+        for staff in &self.by_staff {
+            out.push_str(&format!("{} ({})\n", staff.name, staff.key.0));
+            for (from, to) in &staff.moved {
+                out.push_str(&format!("  moved   {} -> {}\n", from, to));
+            }
+            for at in &staff.removed {
+                out.push_str(&format!("  removed {}\n", at));
+            }
+            for at in &staff.added {
+                out.push_str(&format!("  added   {}\n", at));
+            }
+        }
+        out
+    }
+}
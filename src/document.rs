@@ -0,0 +1,451 @@
+//! Versioned JSON document of a published schedule
+//!
+//! ```json
+//! {
+//!   "schema_version": "1.7",
+//!   "metadata": { "title": "...", "created_at": "...", "note": "..." },
+//!   "roster": [ { "name": "group a", "staff": [ { "name": "...", "employee_id": "E0001" } ] } ],
+//!   "rule_table": [ { "days": [ { "morning": [ { "group": 0, "id": 0 } ], "afternoon": [] } ] } ],
+//!   "params": { "week_delta": 25, "week_gen_range": 5, "start_date": "2025-01-06", "generator": "rotation" },
+//!   "assignments": [ { "week": 25, "days": [ { "morning": [ { "group": 0, "id": 1, "name": "..." } ], "afternoon": [] } ] } ],
+//!   "overrides": [ { "week": 25, "day": 0, "slot": "morning", "hole": 0, "group_id": 0, "staff_id": 2 } ]
+//! }
+//! ```
+//!
+//! `schema_version` is `"<major>.<minor>"`. Minor versions only add optional
+//! fields, so a reader accepts any document with the same major version and
+//! ignores fields it does not know.
+//!
+//! `params` records how the holes were filled: the generator with its
+//! settings, `"rotation"`, `{ "qualified_rotation": [...] }` or
+//! `{ "solver": {...} }`, and the absences rotation handed holes on for.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::contract::Contract;
+use crate::availability::AvailabilityPattern;
+use crate::calendar::ShiftCalendar;
+use crate::preference::StaffPreference;
+use crate::shift_gen::{
+    apply_overrides, DayDecidedShift, DayRule, EmploymentType, Incomplete, ShiftHoll,
+    ShiftOverride, Slot, Staff, StaffGroup, StaffGroupList, StaffProfile, WeekDecidedShift, WeekRule,
+    WeekRuleTable
+};
+use crate::solver::{Absence, Generator, SolveErr};
+
+pub const SCHEMA_MAJOR: u32 = 1;
+pub const SCHEMA_MINOR: u32 = 7;
+
+#[derive(Debug)]
+pub enum DocumentErr {
+    Json(serde_json::Error),
+    MalformedSchemaVersion(String),
+    UnsupportedSchemaVersion(String),
+    /// a rule needs exactly 7 days
+    WeekLengthErr { week: usize, len: usize },
+    EmptyRuleTable,
+    /// a hole or one of its fallbacks names a group the roster does not have
+    GroupIdOutOfRange { group_id: usize },
+    /// a hole or one of its fallbacks names a group without staff
+    EmptyGroup { group_id: usize },
+    /// the archived solver settings find no schedule
+    Solve(SolveErr),
+}
+
+impl std::fmt::Display for DocumentErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DocumentErr::Json(e) => write!(f, "invalid json: {}", e),
+            DocumentErr::MalformedSchemaVersion(v) => write!(f, "malformed schema version `{}`", v),
+            DocumentErr::UnsupportedSchemaVersion(v) => {
+                write!(f, "unsupported schema version `{}` (supported: {}.x)", v, SCHEMA_MAJOR)
+            }
+            DocumentErr::WeekLengthErr { week, len } => {
+                write!(f, "week rule {} has {} days, expected 7", week, len)
+            }
+            DocumentErr::EmptyRuleTable => write!(f, "the rule table is empty"),
+            DocumentErr::GroupIdOutOfRange { group_id } => write!(f, "group id {} is out of range", group_id),
+            DocumentErr::EmptyGroup { group_id } => write!(f, "group {} has no staff", group_id),
+            DocumentErr::Solve(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for DocumentErr {}
+
+impl From<serde_json::Error> for DocumentErr {
+    fn from(e: serde_json::Error) -> Self {
+        DocumentErr::Json(e)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Metadata {
+    #[serde(default)]
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StaffDoc {
+    pub name: String,
+    /// since 1.1
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub employee_id: Option<String>,
+    /// since 1.1
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub join_date: Option<NaiveDate>,
+    /// since 1.1
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub employment_type: Option<EmploymentType>,
+    /// since 1.1
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub attributes: BTreeMap<String, String>,
+    /// since 1.2
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub preferences: Vec<StaffPreference>,
+    /// since 1.3
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub skills: BTreeSet<String>,
+    /// since 1.4
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contract: Option<Contract>,
+    /// since 1.5
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub availability: Vec<AvailabilityPattern>,
+}
+
+impl StaffDoc {
+    fn from_staff(staff: &Staff) -> Self {
+        Self {
+            name: staff.name.clone(),
+            employee_id: staff.profile.employee_id.clone(),
+            join_date: staff.profile.join_date,
+            employment_type: staff.profile.employment_type.clone(),
+            attributes: staff.profile.attributes.clone(),
+            preferences: staff.profile.preferences.clone(),
+            skills: staff.profile.skills.clone(),
+            contract: staff.profile.contract.clone(),
+            availability: staff.profile.availability.clone(),
+        }
+    }
+
+    fn profile(&self) -> StaffProfile {
+        StaffProfile {
+            employee_id: self.employee_id.clone(),
+            join_date: self.join_date,
+            employment_type: self.employment_type.clone(),
+            attributes: self.attributes.clone(),
+            preferences: self.preferences.clone(),
+            skills: self.skills.clone(),
+            contract: self.contract.clone(),
+            availability: self.availability.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroupDoc {
+    pub name: String,
+    pub staff: Vec<StaffDoc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HoleDoc {
+    pub group: usize,
+    pub id: usize,
+    /// since 1.6, holes of other groups filling in in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fallback: Vec<HoleDoc>,
+}
+
+impl HoleDoc {
+    pub fn new(group: usize, id: usize) -> Self {
+        Self { group, id, fallback: vec![] }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DayRuleDoc {
+    pub morning: Vec<HoleDoc>,
+    pub afternoon: Vec<HoleDoc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeekRuleDoc {
+    pub days: Vec<DayRuleDoc>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GenerationParams {
+    pub week_delta: usize,
+    pub week_gen_range: usize,
+    /// date of the first day of week `week_delta`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<NaiveDate>,
+    /// since 1.7, rotation when missing
+    #[serde(default)]
+    pub generator: Generator,
+    /// since 1.7, absences, leave and days off rotation handed the holes of on
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub absences: Vec<Absence>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssignedStaffDoc {
+    pub group: usize,
+    pub id: usize,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DayAssignmentDoc {
+    pub morning: Vec<AssignedStaffDoc>,
+    pub afternoon: Vec<AssignedStaffDoc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeekAssignmentDoc {
+    /// absolute week index (`week_delta + i`)
+    pub week: usize,
+    pub days: Vec<DayAssignmentDoc>,
+}
+
+/// Complete published schedule
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduleDocument {
+    pub schema_version: String,
+    #[serde(default)]
+    pub metadata: Metadata,
+    pub roster: Vec<GroupDoc>,
+    pub rule_table: Vec<WeekRuleDoc>,
+    pub params: GenerationParams,
+    #[serde(default)]
+    pub assignments: Vec<WeekAssignmentDoc>,
+    #[serde(default)]
+    pub overrides: Vec<ShiftOverride>,
+}
+
+fn holes_to_doc(holes: &[ShiftHoll<'_, Incomplete>]) -> Vec<HoleDoc> {
+    holes
+        .iter()
+        .map(|h| HoleDoc {
+            group: h.group_id,
+            id: h.id,
+            fallback: h.fallback.iter().map(|&(group, id)| HoleDoc::new(group, id)).collect(),
+        })
+        .collect()
+}
+
+fn holes_from_doc<'a>(holes: &[HoleDoc]) -> Vec<ShiftHoll<'a, Incomplete>> {
+    holes
+        .iter()
+        .map(|h| ShiftHoll::new(h.group, h.id).with_fallback(h.fallback.iter().map(|f| (f.group, f.id)).collect()))
+        .collect()
+}
+
+pub fn rule_table_to_doc(week_rule_table: &WeekRuleTable<'_, Incomplete>) -> Vec<WeekRuleDoc> {
+    week_rule_table
+        .0
+        .iter()
+        .map(|week_rule| WeekRuleDoc {
+            days: week_rule
+                .0
+                .iter()
+                .map(|day| DayRuleDoc {
+                    morning: holes_to_doc(&day.shift_morning),
+                    afternoon: holes_to_doc(&day.shift_afternoon),
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// rules generation can run on: some rule, every hole in a group with staff
+fn check_rule_table(rule_table: &[WeekRuleDoc], group_lens: &[usize]) -> Result<(), DocumentErr> {
+    if rule_table.is_empty() {
+        return Err(DocumentErr::EmptyRuleTable);
+    }
+    let holes = rule_table.iter().flat_map(|r| &r.days).flat_map(|d| d.morning.iter().chain(&d.afternoon));
+    for group_id in holes.flat_map(|h| std::iter::once(h.group).chain(h.fallback.iter().map(|f| f.group))) {
+        match group_lens.get(group_id) {
+            None => return Err(DocumentErr::GroupIdOutOfRange { group_id }),
+            Some(0) => return Err(DocumentErr::EmptyGroup { group_id }),
+            Some(_) => {}
+        }
+    }
+    Ok(())
+}
+
+/// every rule must have 7 days
+pub fn rule_table_from_doc<'a>(rule_table: &[WeekRuleDoc]) -> WeekRuleTable<'a, Incomplete> {
+    WeekRuleTable(
+        rule_table
+            .iter()
+            .map(|week_rule| {
+                WeekRule(std::array::from_fn(|i| {
+                    let day = &week_rule.days[i];
+                    DayRule {
+                        shift_morning: holes_from_doc(&day.morning),
+                        shift_afternoon: holes_from_doc(&day.afternoon),
+                    }
+                }))
+            })
+            .collect(),
+    )
+}
+
+fn day_assignment_to_doc(day: &DayDecidedShift<'_>, slot: Slot) -> Vec<AssignedStaffDoc> {
+    day.slot(slot)
+        .iter()
+        .map(|s| AssignedStaffDoc { group: s.get_group_id(), id: s.get_id(), name: s.name.clone() })
+        .collect()
+}
+
+fn parse_schema_version(version: &str) -> Result<(u32, u32), DocumentErr> {
+    let malformed = || DocumentErr::MalformedSchemaVersion(version.to_string());
+    let (major, minor) = version.split_once('.').ok_or_else(malformed)?;
+    Ok((
+        major.parse().map_err(|_| malformed())?,
+        minor.parse().map_err(|_| malformed())?,
+    ))
+}
+
+impl ScheduleDocument {
+    /// Snapshot roster, rules, parameters and the resulting assignments
+    pub fn new(
+        metadata: Metadata,
+        staff_group_list: &StaffGroupList,
+        week_rule_table: &WeekRuleTable<'_, Incomplete>,
+        params: GenerationParams,
+        shift: &[WeekDecidedShift<'_>],
+        overrides: &[ShiftOverride],
+    ) -> Self {
+        let roster = staff_group_list
+            .0
+            .iter()
+            .map(|group| GroupDoc {
+                name: group.get_name().to_string(),
+                staff: group.iter().map(StaffDoc::from_staff).collect(),
+            })
+            .collect();
+        let rule_table = rule_table_to_doc(week_rule_table);
+        let assignments = shift
+            .iter()
+            .enumerate()
+            .map(|(i, week_shift)| WeekAssignmentDoc {
+                week: params.week_delta + i,
+                days: week_shift
+                    .0
+                    .iter()
+                    .map(|day| DayAssignmentDoc {
+                        morning: day_assignment_to_doc(day, Slot::Morning),
+                        afternoon: day_assignment_to_doc(day, Slot::Afternoon),
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Self {
+            schema_version: format!("{}.{}", SCHEMA_MAJOR, SCHEMA_MINOR),
+            metadata,
+            roster,
+            rule_table,
+            params,
+            assignments,
+            overrides: overrides.to_vec(),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("schedule document is always serializable")
+    }
+
+    pub fn from_json(s: &str) -> Result<Self, DocumentErr> {
+        #[derive(Deserialize)]
+        struct VersionProbe {
+            schema_version: String,
+        }
+        // check the version before the body so that incompatible documents
+        // are reported as such rather than as a shape mismatch
+        let probe: VersionProbe = serde_json::from_str(s)?;
+        let (major, _minor) = parse_schema_version(&probe.schema_version)?;
+        if major != SCHEMA_MAJOR {
+            return Err(DocumentErr::UnsupportedSchemaVersion(probe.schema_version));
+        }
+        let doc: Self = serde_json::from_str(s)?;
+        if let Some((week, rule)) = doc.rule_table.iter().enumerate().find(|(_, r)| r.days.len() != 7) {
+            return Err(DocumentErr::WeekLengthErr { week, len: rule.days.len() });
+        }
+        check_rule_table(&doc.rule_table, &doc.roster.iter().map(|g| g.staff.len()).collect::<Vec<_>>())?;
+        Ok(doc)
+    }
+
+    pub fn staff_group_list(&self) -> StaffGroupList {
+        let mut staff_group_list = StaffGroupList::new();
+        for group in &self.roster {
+            let mut staff_group = StaffGroup::new(&group.name);
+            for staff in &group.staff {
+                staff_group.add_staff_with_profile(&staff.name, staff.profile());
+            }
+            staff_group_list.add_staff_group(staff_group);
+        }
+        staff_group_list
+    }
+
+    pub fn week_rule_table<'a>(&self) -> WeekRuleTable<'a, Incomplete> {
+        rule_table_from_doc(&self.rule_table)
+    }
+
+    /// Generate the schedule again from the archived rules and apply the overrides
+    ///
+    /// runs the archived generator as `ShiftConfig::generate` does, with the
+    /// calendar of `start_date` and the archived absences. Fails when the
+    /// rules do not fit the staff groups given or the solver finds nothing.
+    pub fn regenerate<'a>(&self, staff_group_list: &'a StaffGroupList) -> Result<Box<[WeekDecidedShift<'a>]>, DocumentErr> {
+        check_rule_table(&self.rule_table, &staff_group_list.0.iter().map(|g| g.len()).collect::<Vec<_>>())?;
+        let params = &self.params;
+        let calendar = params
+            .start_date
+            .and_then(|d| d.checked_sub_days(Days::new(7 * params.week_delta as u64)))
+            .map(ShiftCalendar::new);
+        let schedule = params
+            .generator
+            .generate(
+                &self.week_rule_table(),
+                staff_group_list,
+                params.week_delta,
+                params.week_gen_range,
+                calendar.as_ref(),
+                &params.absences,
+            )
+            .map_err(DocumentErr::Solve)?;
+        let mut shift = schedule.weeks;
+        apply_overrides(&mut shift, staff_group_list, params.week_delta, &self.overrides);
+        Ok(shift)
+    }
+
+    /// true when the archived assignments are exactly what the archived rules produce
+    pub fn is_reproducible(&self) -> bool {
+        let staff_group_list = self.staff_group_list();
+        let Ok(shift) = self.regenerate(&staff_group_list) else {
+            return false;
+        };
+        Self::new(
+            self.metadata.clone(),
+            &staff_group_list,
+            &self.week_rule_table(),
+            self.params.clone(),
+            &shift,
+            &self.overrides,
+        )
+        .assignments
+            == self.assignments
+    }
+}
@@ -0,0 +1,334 @@
+use std::path::{Path, PathBuf};
+
+use crate::calendar::ShiftCalendar;
+use crate::config::{ConfigErr, HolePosition, Location, Severity, ShiftConfig};
+use crate::document::{DayRuleDoc, HoleDoc, WeekRuleDoc};
+use crate::render::{render_day_slot_grid, RenderOptions};
+use crate::shift_gen::Slot;
+use crate::validate::{validate, Diagnostic};
+
+/// Direction of a cursor or hole move
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// Cell of the rule grid: one row per (week rule, slot), one column per day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor {
+    pub week_rule_index: usize,
+    pub slot: Slot,
+    pub day: usize,
+    /// selected hole in the cell
+    pub index: usize,
+}
+
+impl Cursor {
+    pub fn position(&self) -> HolePosition {
+        HolePosition { week_rule_index: self.week_rule_index, day: self.day, slot: self.slot, index: self.index }
+    }
+
+    fn row(&self) -> usize {
+        self.week_rule_index * 2 + Slot::ALL.iter().position(|s| *s == self.slot).unwrap_or(0)
+    }
+
+    fn with_row(self, row: usize) -> Self {
+        Self { week_rule_index: row / 2, slot: Slot::ALL[row % 2], ..self }
+    }
+}
+
+/// Editing state of the rule table of a config
+///
+/// every edit is written back to the config source and parsed again, so the
+/// checkers and the preview always see what `save` would write.
+pub struct RuleEditor {
+    path: Option<PathBuf>,
+    base_dir: Option<PathBuf>,
+    config: ShiftConfig,
+    rule_table: Vec<WeekRuleDoc>,
+    diagnostics: Vec<Diagnostic>,
+    /// why the edited source does not parse, `save` refuses to write it
+    parse_error: Option<ConfigErr>,
+    pub cursor: Cursor,
+    /// first week of the preview and of the checks
+    pub week_delta: usize,
+    pub preview_weeks: usize,
+    dirty: bool,
+}
+
+impl RuleEditor {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, ConfigErr> {
+        let config = ShiftConfig::load(path.as_ref())?;
+        Ok(Self::new(config, path.as_ref().parent().map(Path::to_path_buf)))
+    }
+
+    /// `base_dir` resolves the path of the roster csv
+    pub fn new(config: ShiftConfig, base_dir: Option<PathBuf>) -> Self {
+        let mut rule_table = config.rule_table.clone();
+        if rule_table.is_empty() {
+            rule_table.push(empty_week_rule());
+        }
+        let mut editor = Self {
+            path: config.path.clone(),
+            base_dir,
+            config,
+            rule_table,
+            diagnostics: vec![],
+            parse_error: None,
+            cursor: Cursor { week_rule_index: 0, slot: Slot::Morning, day: 0, index: 0 },
+            week_delta: 0,
+            preview_weeks: 2,
+            dirty: false,
+        };
+        editor.refresh();
+        editor
+    }
+
+    pub fn config(&self) -> &ShiftConfig {
+        &self.config
+    }
+
+    pub fn rule_table(&self) -> &[WeekRuleDoc] {
+        &self.rule_table
+    }
+
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    pub fn parse_error(&self) -> Option<&ConfigErr> {
+        self.parse_error.as_ref()
+    }
+
+    /// edited since the last save
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    pub fn holes(&self, week_rule_index: usize, day: usize, slot: Slot) -> &[HoleDoc] {
+        let day_rule = &self.rule_table[week_rule_index].days[day];
+        match slot {
+            Slot::Morning => &day_rule.morning,
+            Slot::Afternoon => &day_rule.afternoon,
+        }
+    }
+
+    fn holes_mut(&mut self, week_rule_index: usize, day: usize, slot: Slot) -> &mut Vec<HoleDoc> {
+        let day_rule = &mut self.rule_table[week_rule_index].days[day];
+        match slot {
+            Slot::Morning => &mut day_rule.morning,
+            Slot::Afternoon => &mut day_rule.afternoon,
+        }
+    }
+
+    fn selected_holes(&self) -> &[HoleDoc] {
+        self.holes(self.cursor.week_rule_index, self.cursor.day, self.cursor.slot)
+    }
+
+    pub fn selected_hole(&self) -> Option<HoleDoc> {
+        self.selected_holes().get(self.cursor.index).cloned()
+    }
+
+    /// Config source with the edited rule table
+    pub fn source(&self) -> String {
+        self.config.source_with_rule_table(&self.rule_table)
+    }
+
+    /// parse the edited source again and re-run the checkers
+    fn refresh(&mut self) {
+        match ShiftConfig::parse(&self.source(), self.base_dir.as_deref()) {
+            Ok(mut config) => {
+                config.path = self.path.clone();
+                self.diagnostics = validate(&config, self.week_delta, self.preview_weeks);
+                self.config = config;
+                self.parse_error = None;
+            }
+            // the last good config stays for the preview, the error is the
+            // only diagnostic until an edit makes the source parse again
+            Err(mut e) => {
+                let location = e.location.take();
+                let message = e.to_string();
+                e.location = location;
+                self.diagnostics = vec![Diagnostic {
+                    severity: Severity::Error,
+                    check: "config",
+                    file: self.path.as_ref().map(|p| p.display().to_string()),
+                    line: location.map(|l| l.line),
+                    column: location.map(|l| l.column),
+                    week: None,
+                    day: None,
+                    date: None,
+                    message,
+                }];
+                self.parse_error = Some(e);
+            }
+        }
+    }
+
+    fn edited(&mut self) {
+        self.dirty = true;
+        self.refresh();
+    }
+
+    pub fn move_cursor(&mut self, direction: Direction) {
+        let rows = self.rule_table.len() * 2;
+        let cursor = &mut self.cursor;
+        *cursor = match direction {
+            Direction::Up => cursor.with_row((cursor.row() + rows - 1) % rows),
+            Direction::Down => cursor.with_row((cursor.row() + 1) % rows),
+            Direction::Left => Cursor { day: (cursor.day + 6) % 7, ..*cursor },
+            Direction::Right => Cursor { day: (cursor.day + 1) % 7, ..*cursor },
+        };
+        self.cursor.index = self.cursor.index.min(self.selected_holes().len().saturating_sub(1));
+    }
+
+    /// select the next hole of the cell
+    pub fn next_hole(&mut self) {
+        let len = self.selected_holes().len();
+        self.cursor.index = if len == 0 { 0 } else { (self.cursor.index + 1) % len };
+    }
+
+    /// Add a hole after the selected one, a copy of it or the first staff of the first group
+    pub fn add_hole(&mut self) {
+        let hole = self.selected_hole().unwrap_or(HoleDoc::new(0, 0));
+        let index = if self.selected_holes().is_empty() { 0 } else { self.cursor.index + 1 };
+        let Cursor { week_rule_index, day, slot, .. } = self.cursor;
+        let holes = self.holes_mut(week_rule_index, day, slot);
+        holes.insert(index, hole);
+        self.cursor.index = index;
+        self.edited();
+    }
+
+    pub fn delete_hole(&mut self) {
+        let Cursor { week_rule_index, day, slot, index } = self.cursor;
+        let holes = self.holes_mut(week_rule_index, day, slot);
+        if index >= holes.len() {
+            return;
+        }
+        holes.remove(index);
+        self.cursor.index = index.min(holes.len().saturating_sub(1));
+        self.edited();
+    }
+
+    /// Move the selected hole to the neighbouring cell, the cursor follows it
+    pub fn move_hole(&mut self, direction: Direction) {
+        let Some(hole) = self.selected_hole() else {
+            return;
+        };
+        let Cursor { week_rule_index, day, slot, index } = self.cursor;
+        self.holes_mut(week_rule_index, day, slot).remove(index);
+        self.cursor.index = 0;
+        self.move_cursor(direction);
+        let Cursor { week_rule_index, day, slot, .. } = self.cursor;
+        let holes = self.holes_mut(week_rule_index, day, slot);
+        holes.push(hole);
+        self.cursor.index = holes.len() - 1;
+        self.edited();
+    }
+
+    /// Change the group of the selected hole, `step` groups forward or back
+    ///
+    /// groups among the fallbacks of the hole are skipped, a hole may not
+    /// fall back to its own group.
+    pub fn cycle_group(&mut self, step: isize) {
+        let groups = self.config.staff_group_list.0.len() as isize;
+        self.update_selected(|hole| {
+            let next = (1..=groups)
+                .map(|n| (hole.group as isize + step * n).rem_euclid(groups) as usize)
+                .find(|g| !hole.fallback.iter().any(|f| f.group == *g));
+            if let Some(group) = next {
+                hole.group = group;
+            }
+        });
+    }
+
+    /// Change the staff index of the selected hole, it may leave the group on purpose
+    pub fn change_id(&mut self, step: isize) {
+        self.update_selected(|hole| hole.id = hole.id.saturating_add_signed(step));
+    }
+
+    fn update_selected(&mut self, update: impl FnOnce(&mut HoleDoc)) {
+        let Cursor { week_rule_index, day, slot, index } = self.cursor;
+        if let Some(hole) = self.holes_mut(week_rule_index, day, slot).get_mut(index) {
+            update(hole);
+            self.edited();
+        }
+    }
+
+    /// Add an empty week rule after the current one
+    pub fn add_week_rule(&mut self) {
+        let index = self.cursor.week_rule_index + 1;
+        self.rule_table.insert(index, empty_week_rule());
+        self.cursor = Cursor { week_rule_index: index, index: 0, ..self.cursor };
+        self.edited();
+    }
+
+    /// Delete the current week rule, the last one is kept
+    pub fn delete_week_rule(&mut self) {
+        if self.rule_table.len() <= 1 {
+            return;
+        }
+        self.rule_table.remove(self.cursor.week_rule_index);
+        let week_rule_index = self.cursor.week_rule_index.min(self.rule_table.len() - 1);
+        self.cursor = Cursor { week_rule_index, index: 0, ..self.cursor };
+        self.edited();
+    }
+
+    /// Shift the weeks previewed and checked
+    pub fn shift_preview(&mut self, step: isize) {
+        self.week_delta = self.week_delta.saturating_add_signed(step);
+        self.refresh();
+    }
+
+    /// diagnostics located at a hole
+    pub fn diagnostics_at(&self, position: HolePosition) -> Vec<&Diagnostic> {
+        self.config.hole_location(position).map_or(vec![], |location| self.diagnostics_located(location))
+    }
+
+    /// diagnostics located at a `[[week]]` table
+    pub fn week_diagnostics(&self, week_rule_index: usize) -> Vec<&Diagnostic> {
+        self.config.week_location(week_rule_index).map_or(vec![], |location| self.diagnostics_located(location))
+    }
+
+    fn diagnostics_located(&self, location: Location) -> Vec<&Diagnostic> {
+        self.diagnostics
+            .iter()
+            .filter(|d| d.line == Some(location.line) && d.column == Some(location.column))
+            .collect()
+    }
+
+    /// Generated schedule of the previewed weeks with the overrides applied
+    pub fn preview(&self) -> String {
+        // gen_shift divides by the group sizes
+        if self.config.staff_group_list.0.iter().any(|g| g.len() == 0) {
+            return "a group has no staff".to_string();
+        }
+        let schedule = match self.config.schedule(self.week_delta, self.preview_weeks) {
+            Ok(schedule) => schedule,
+            Err(e) => return e.to_string(),
+        };
+        let start_date = self.config.anchor.map(|anchor| ShiftCalendar::new(anchor).date_of(self.week_delta, 0));
+        render_day_slot_grid(&schedule.weeks, &RenderOptions::new().with_start_date(start_date))
+    }
+
+    /// Write the edited source to the config file
+    pub fn save(&mut self) -> std::io::Result<()> {
+        let path = self
+            .path
+            .as_ref()
+            .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "the config has no file"))?;
+        if let Some(e) = &self.parse_error {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("the edited config is invalid, {}", e)));
+        }
+        std::fs::write(path, self.source())?;
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+fn empty_week_rule() -> WeekRuleDoc {
+    WeekRuleDoc { days: (0..7).map(|_| DayRuleDoc { morning: vec![], afternoon: vec![] }).collect() }
+}
@@ -0,0 +1,217 @@
+use chrono::NaiveDate;
+
+use crate::availability::assign_available;
+use crate::calendar::ShiftCalendar;
+use crate::render::WEEKDAY_NAMES;
+use crate::shift_gen::{
+    DecidedSchedule, Incomplete, ShiftOverride, Slot, Staff, StaffGroupList, WeekRuleTable
+};
+use crate::solver::Absence;
+
+/// What replaced the generated staff of a hole
+#[derive(Debug, Clone, Copy)]
+pub enum Replacement<'a> {
+    /// `index` is the position of the override in the list given to `Explainer`
+    Override { index: usize, shift_override: ShiftOverride, staff: &'a Staff },
+    /// `assign_available` gave the hole to another staff, of a fallback group
+    /// when `fallback`
+    Reassigned { staff: &'a Staff, fallback: bool },
+}
+
+impl<'a> Replacement<'a> {
+    pub fn staff(&self) -> &'a Staff {
+        match self {
+            Replacement::Override { staff, .. } | Replacement::Reassigned { staff, .. } => staff,
+        }
+    }
+}
+
+/// Derivation of the staff of one hole, step by step as in `gen_shift`
+#[derive(Debug, Clone, Copy)]
+pub struct Derivation<'a> {
+    pub date: Option<NaiveDate>,
+    /// absolute week index, `week_delta + i` in `gen_shift`
+    pub week: usize,
+    pub day: usize,
+    pub slot: Slot,
+    /// index of the hole in the slot
+    pub hole: usize,
+    /// number of week rules
+    pub cycle: usize,
+    /// `week % cycle`
+    pub week_rule_index: usize,
+    /// `week / cycle`, the number of times the table was used before
+    pub rotation: usize,
+    /// `ShiftHoll::group_id`
+    pub group_id: usize,
+    /// `ShiftHoll::id`
+    pub hole_id: usize,
+    pub group_len: usize,
+    /// `(rotation + hole_id) % group_len`
+    pub staff_index: usize,
+    pub generated: &'a Staff,
+    pub replacement: Option<Replacement<'a>>,
+}
+
+impl<'a> Derivation<'a> {
+    /// staff finally working in the hole
+    pub fn staff(&self) -> &'a Staff {
+        self.replacement.map_or(self.generated, |r| r.staff())
+    }
+
+    /// one line per step
+    pub fn steps(&self, staff_group_list: &StaffGroupList) -> Vec<String> {
+        let group = staff_group_list.0.get(self.group_id).map_or("", |g| g.get_name());
+        let mut steps = vec![
+            format!("week        = {}", self.week),
+            format!(
+                "week rule   = week % cycle = {} % {} = {}",
+                self.week, self.cycle, self.week_rule_index
+            ),
+            format!(
+                "rotation    = week / cycle = {} / {} = {}",
+                self.week, self.cycle, self.rotation
+            ),
+            format!("hole        = {}:{} (group {}, id {})", group, self.hole_id, self.group_id, self.hole_id),
+            format!(
+                "staff index = (rotation + id) % group size = ({} + {}) % {} = {}",
+                self.rotation, self.hole_id, self.group_len, self.staff_index
+            ),
+            format!("generated   = {}", self.generated.name),
+        ];
+        match self.replacement {
+            Some(Replacement::Override { index, staff, .. }) => {
+                steps.push(format!("override    = {} (override #{})", staff.name, index));
+            }
+            Some(Replacement::Reassigned { staff, fallback: false }) => {
+                steps.push(format!("available   = {} (next available in the group)", staff.name));
+            }
+            Some(Replacement::Reassigned { staff, fallback: true }) => {
+                let from = staff_group_list.0.get(staff.get_group_id()).map_or("", |g| g.get_name());
+                steps.push(format!("fallback    = {} (group {} is short, taken from {})", staff.name, group, from));
+            }
+            None => {}
+        }
+        steps.push(format!("result      = {}", self.staff().name));
+        steps
+    }
+
+    /// heading naming the hole
+    pub fn heading(&self) -> String {
+        let day = match self.date {
+            Some(date) => format!("{} {}", date, WEEKDAY_NAMES[self.day]),
+            None => format!("week {} {}", self.week, WEEKDAY_NAMES[self.day]),
+        };
+        format!("{} {} hole {}", day, self.slot.label(), self.hole)
+    }
+}
+
+/// Explains how `gen_shift`, `assign_available` and the overrides decide each hole
+pub struct Explainer<'r, 'a> {
+    week_rule_table: &'r WeekRuleTable<'a, Incomplete>,
+    staff_group_list: &'a StaffGroupList,
+    overrides: &'r [ShiftOverride],
+    calendar: Option<ShiftCalendar>,
+    absences: &'r [Absence],
+}
+
+impl<'r, 'a> Explainer<'r, 'a> {
+    pub fn new(
+        week_rule_table: &'r WeekRuleTable<'a, Incomplete>,
+        staff_group_list: &'a StaffGroupList,
+        overrides: &'r [ShiftOverride],
+        calendar: Option<ShiftCalendar>,
+    ) -> Self {
+        Self { week_rule_table, staff_group_list, overrides, calendar, absences: &[] }
+    }
+
+    /// explain with the holes of absent staff handed on
+    pub fn with_absences(mut self, absences: &'r [Absence]) -> Self {
+        self.absences = absences;
+        self
+    }
+
+    /// `None` when the hole does not exist or its group is empty or unknown
+    pub fn explain_hole(&self, week: usize, day: usize, slot: Slot, hole: usize) -> Option<Derivation<'a>> {
+        let cycle = self.week_rule_table.0.len();
+        if cycle == 0 {
+            return None;
+        }
+        let week_rule_index = week % cycle;
+        let rotation = week / cycle;
+        let day_rule = self.week_rule_table.0[week_rule_index].0.get(day)?;
+        let holes = match slot {
+            Slot::Morning => &day_rule.shift_morning,
+            Slot::Afternoon => &day_rule.shift_afternoon,
+        };
+        let shift_hole = holes.get(hole)?;
+        let group_len = self.staff_group_list.0.get(shift_hole.group_id)?.len();
+        if group_len == 0 {
+            return None;
+        }
+        let staff_index = (rotation + shift_hole.id) % group_len;
+        let generated = self.staff_group_list.get_staff(shift_hole.group_id, staff_index)?;
+
+        // apply_overrides lets the last valid override win
+        let replacement = self
+            .overrides
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(index, o)| {
+                if (o.week, o.day, o.slot, o.hole) != (week, day, slot, hole) {
+                    return None;
+                }
+                let staff = self.staff_group_list.get_staff(o.group_id, o.staff_id)?;
+                Some(Replacement::Override { index, shift_override: *o, staff })
+            })
+            .or_else(|| {
+                let mut schedule = DecidedSchedule::generate(self.week_rule_table, self.staff_group_list, week, 1);
+                assign_available(&mut schedule, self.week_rule_table, self.staff_group_list, self.calendar.as_ref(), self.absences, &[]);
+                let staff = *schedule.week(week)?.0[day].slot(slot).get(hole)?;
+                let fallback = staff.get_group_id() != shift_hole.group_id;
+                (!std::ptr::eq(staff, generated)).then_some(Replacement::Reassigned { staff, fallback })
+            });
+
+        Some(Derivation {
+            date: self.calendar.map(|c| c.date_of(week, day)),
+            week,
+            day,
+            slot,
+            hole,
+            cycle,
+            week_rule_index,
+            rotation,
+            group_id: shift_hole.group_id,
+            hole_id: shift_hole.id,
+            group_len,
+            staff_index,
+            generated,
+            replacement,
+        })
+    }
+
+    fn holes_of(&self, week: usize, day: usize) -> Vec<Derivation<'a>> {
+        Slot::ALL
+            .into_iter()
+            .flat_map(|slot| (0..).map_while(move |hole| self.explain_hole(week, day, slot, hole)))
+            .collect()
+    }
+
+    /// `None` without a calendar or before the anchor
+    pub fn explain_date(&self, date: NaiveDate, slot: Slot, hole: usize) -> Option<Derivation<'a>> {
+        let (week, day) = self.calendar?.position_of(date)?;
+        self.explain_hole(week, day, slot, hole)
+    }
+
+    /// Every hole of a date the staff was generated into or put into by a replacement
+    pub fn explain_staff(&self, staff: &Staff, date: NaiveDate) -> Vec<Derivation<'a>> {
+        let Some((week, day)) = self.calendar.and_then(|c| c.position_of(date)) else {
+            return vec![];
+        };
+        self.holes_of(week, day)
+            .into_iter()
+            .filter(|d| std::ptr::eq(d.generated, staff) || std::ptr::eq(d.staff(), staff))
+            .collect()
+    }
+}
@@ -0,0 +1,115 @@
+use chrono::{NaiveDate, Utc};
+
+use crate::calendar::ShiftCalendar;
+use crate::render::WEEKDAY_NAMES;
+use crate::shift_gen::{
+    DecidedSchedule, Slot, Staff, StaffGroupList
+};
+
+/// every assignment of a schedule in order of week, day, slot and hole
+fn assignments<'s, 'a>(
+    schedule: &'s DecidedSchedule<'a>,
+) -> impl Iterator<Item = (usize, usize, Slot, usize, &'a Staff)> + 's {
+    schedule.days().flat_map(|(week, day, day_shift)| {
+        Slot::ALL.into_iter().flat_map(move |slot| {
+            day_shift
+                .slot(slot)
+                .iter()
+                .enumerate()
+                .map(move |(hole, staff)| (week, day, slot, hole, *staff))
+        })
+    })
+}
+
+/// Write a schedule as csv, one row per assignment
+///
+/// the `date` column is left empty without a calendar. The output can be read
+/// back with `history::import_history_csv`.
+pub fn schedule_to_csv(
+    schedule: &DecidedSchedule<'_>,
+    staff_group_list: &StaffGroupList,
+    calendar: Option<&ShiftCalendar>,
+) -> String {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    let write_err = "writing csv to memory cannot fail";
+    writer
+        .write_record(["week", "day", "date", "slot", "hole", "group", "staff", "employee_id"])
+        .expect(write_err);
+    for (week, day, slot, hole, staff) in assignments(schedule) {
+        let date = calendar.map(|c| c.date_of(week, day).to_string()).unwrap_or_default();
+        let group = staff_group_list.0.get(staff.get_group_id()).map_or("", |g| g.get_name());
+        writer
+            .write_record([
+                week.to_string().as_str(),
+                WEEKDAY_NAMES[day],
+                &date,
+                &slot.label().to_lowercase(),
+                &hole.to_string(),
+                group,
+                &staff.name,
+                staff.profile.employee_id.as_deref().unwrap_or(""),
+            ])
+            .expect(write_err);
+    }
+    String::from_utf8(writer.into_inner().expect(write_err)).expect("csv of utf-8 strings")
+}
+
+fn ics_escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// fold a content line at 75 octets (RFC 5545 3.1)
+fn ics_fold(line: &str) -> String {
+    let mut out = String::new();
+    let mut len = 0;
+    for c in line.chars() {
+        if len + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            len = 1;
+        }
+        out.push(c);
+        len += c.len_utf8();
+    }
+    out.push_str("\r\n");
+    out
+}
+
+fn ics_date(date: NaiveDate) -> String {
+    date.format("%Y%m%d").to_string()
+}
+
+/// Write a schedule as an iCalendar file with one all-day event per assignment
+pub fn schedule_to_ics(schedule: &DecidedSchedule<'_>, calendar: &ShiftCalendar) -> String {
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut out = String::new();
+    for line in ["BEGIN:VCALENDAR", "VERSION:2.0", "PRODID:-//shift_calendar//shiftcal//EN", "CALSCALE:GREGORIAN"] {
+        out.push_str(&ics_fold(line));
+    }
+    for (week, day, slot, hole, staff) in assignments(schedule) {
+        let date = calendar.date_of(week, day);
+        let lines = [
+            "BEGIN:VEVENT".to_string(),
+            format!(
+                "UID:{}-{}-{}-{}-{}@shiftcal",
+                ics_date(date),
+                slot.label().to_lowercase(),
+                hole,
+                staff.get_group_id(),
+                staff.get_id()
+            ),
+            format!("DTSTAMP:{}", stamp),
+            format!("DTSTART;VALUE=DATE:{}", ics_date(date)),
+            format!("DTEND;VALUE=DATE:{}", ics_date(date.succ_opt().unwrap_or(date))),
+            format!("SUMMARY:{}", ics_escape(&format!("{} ({})", staff.name, slot.label()))),
+            "END:VEVENT".to_string(),
+        ];
+        for line in &lines {
+            out.push_str(&ics_fold(line));
+        }
+    }
+    out.push_str(&ics_fold("END:VCALENDAR"));
+    out
+}
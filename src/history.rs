@@ -0,0 +1,140 @@
+//! Import of hand-made schedules
+//!
+//! ```csv
+//! date,slot,staff
+//! 2024-04-01,morning,E0001
+//! 2024-04-01,afternoon,鈴木花子
+//! ```
+//!
+//! `staff` is matched against the employee id first, then against the name.
+//! Rows are placed in the week of their date on the given calendar, so the
+//! result lines up with the weeks produced by `gen_shift`.
+
+use std::io::Read;
+
+use chrono::NaiveDate;
+
+use crate::calendar::ShiftCalendar;
+use crate::shift_gen::{
+    DayDecidedShift, DecidedSchedule, Slot, Staff, StaffGroupList, WeekDecidedShift
+};
+
+const DATE_COLUMN: &str = "date";
+const SLOT_COLUMN: &str = "slot";
+const STAFF_COLUMN: &str = "staff";
+
+#[derive(Debug)]
+pub struct HistoryErr {
+    /// 1-based line number in the csv, header is line 1
+    pub line: usize,
+    pub reason: CauseOfHistoryErr,
+}
+
+#[derive(Debug)]
+pub enum CauseOfHistoryErr {
+    Csv(csv::Error),
+    MissingColumn(&'static str),
+    InvalidDate(String),
+    /// date before the first day of the calendar
+    DateBeforeAnchor(NaiveDate),
+    InvalidSlot(String),
+    UnknownStaff(String),
+    /// name shared by several staff without an employee id to tell them apart
+    AmbiguousStaff(String),
+}
+
+impl std::fmt::Display for HistoryErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.reason {
+            CauseOfHistoryErr::Csv(e) => write!(f, "{}", e),
+            CauseOfHistoryErr::MissingColumn(c) => write!(f, "missing column `{}`", c),
+            CauseOfHistoryErr::InvalidDate(d) => write!(f, "invalid date `{}`", d),
+            CauseOfHistoryErr::DateBeforeAnchor(d) => write!(f, "{} is before the calendar anchor", d),
+            CauseOfHistoryErr::InvalidSlot(s) => write!(f, "invalid slot `{}`", s),
+            CauseOfHistoryErr::UnknownStaff(s) => write!(f, "unknown staff `{}`", s),
+            CauseOfHistoryErr::AmbiguousStaff(s) => write!(f, "ambiguous staff `{}`", s),
+        }
+    }
+}
+
+/// Find a staff by employee id, then by name
+pub fn find_staff<'a>(staff_group_list: &'a StaffGroupList, key: &str) -> Result<&'a Staff, CauseOfHistoryErr> {
+    let all = || staff_group_list.0.iter().flat_map(|group| group.iter());
+    if let Some(staff) = all().find(|s| s.profile.employee_id.as_deref() == Some(key)) {
+        return Ok(staff);
+    }
+    let mut named = all().filter(|s| s.name == key);
+    match (named.next(), named.next()) {
+        (Some(staff), None) => Ok(staff),
+        (Some(_), Some(_)) => Err(CauseOfHistoryErr::AmbiguousStaff(key.to_string())),
+        (None, _) => Err(CauseOfHistoryErr::UnknownStaff(key.to_string())),
+    }
+}
+
+fn empty_week<'a>() -> WeekDecidedShift<'a> {
+    WeekDecidedShift(std::array::from_fn(|_| DayDecidedShift { shift_morning: vec![], shift_afternoon: vec![] }))
+}
+
+/// Read a historical schedule into the decided-schedule model
+///
+/// the schedule covers every week from the earliest to the latest date found.
+pub fn import_history_csv<'a, R: Read>(
+    reader: R,
+    staff_group_list: &'a StaffGroupList,
+    calendar: &ShiftCalendar,
+) -> Result<DecidedSchedule<'a>, Vec<HistoryErr>> {
+    let mut csv_reader = csv::Reader::from_reader(reader);
+    let header = csv_reader
+        .headers()
+        .map_err(|e| vec![HistoryErr { line: 1, reason: CauseOfHistoryErr::Csv(e) }])?
+        .clone();
+    let column = |name: &'static str| {
+        header
+            .iter()
+            .position(|h| h.trim().eq_ignore_ascii_case(name))
+            .ok_or_else(|| vec![HistoryErr { line: 1, reason: CauseOfHistoryErr::MissingColumn(name) }])
+    };
+    let (date_column, slot_column, staff_column) = (column(DATE_COLUMN)?, column(SLOT_COLUMN)?, column(STAFF_COLUMN)?);
+
+    let mut entries: Vec<(usize, usize, Slot, &'a Staff)> = vec![];
+    let mut errors = vec![];
+    for record in csv_reader.records() {
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                let line = e.position().map_or(0, |p| p.line() as usize);
+                errors.push(HistoryErr { line, reason: CauseOfHistoryErr::Csv(e) });
+                continue;
+            }
+        };
+        let line = record.position().map_or(0, |p| p.line() as usize);
+        let field = |i: usize| record.get(i).map(str::trim).unwrap_or("");
+
+        let entry = NaiveDate::parse_from_str(field(date_column), "%Y-%m-%d")
+            .map_err(|_| CauseOfHistoryErr::InvalidDate(field(date_column).to_string()))
+            .and_then(|date| calendar.position_of(date).ok_or(CauseOfHistoryErr::DateBeforeAnchor(date)))
+            .and_then(|(week, day)| {
+                let slot = field(slot_column)
+                    .parse::<Slot>()
+                    .map_err(CauseOfHistoryErr::InvalidSlot)?;
+                let staff = find_staff(staff_group_list, field(staff_column))?;
+                Ok((week, day, slot, staff))
+            });
+        match entry {
+            Ok(entry) => entries.push(entry),
+            Err(reason) => errors.push(HistoryErr { line, reason }),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let week_delta = entries.iter().map(|e| e.0).min().unwrap_or(0);
+    let week_end = entries.iter().map(|e| e.0 + 1).max().unwrap_or(0);
+    let mut weeks: Vec<WeekDecidedShift<'a>> = (week_delta..week_end).map(|_| empty_week()).collect();
+    for (week, day, slot, staff) in entries {
+        weeks[week - week_delta].0[day].slot_mut(slot).push(staff);
+    }
+    Ok(DecidedSchedule::new(week_delta, weeks.into_boxed_slice()))
+}
@@ -0,0 +1,129 @@
+use std::collections::BTreeMap;
+
+use chrono::Datelike;
+use serde::{Deserialize, Serialize};
+
+use crate::calendar::ShiftCalendar;
+use crate::contract::{Period, SlotDurations};
+use crate::shift_gen::{
+    DecidedSchedule, Slot, Staff, StaffGroupList
+};
+
+/// 法定労働時間 (労働基準法32条), in minutes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatutoryLimits {
+    pub daily_minutes: u32,
+    pub weekly_minutes: u32,
+}
+
+impl Default for StatutoryLimits {
+    /// 8 hours a day, 40 hours a week
+    fn default() -> Self {
+        Self { daily_minutes: 8 * 60, weekly_minutes: 40 * 60 }
+    }
+}
+
+/// Minutes worked by a staff on one day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DayHours {
+    /// absolute week index
+    pub week: usize,
+    pub day: usize,
+    pub minutes: u32,
+    /// beyond the daily limit
+    pub overtime: u32,
+}
+
+/// Minutes worked by a staff in one week
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeekHours {
+    /// absolute week index
+    pub week: usize,
+    pub minutes: u32,
+    /// sum of the daily overtime
+    pub daily_overtime: u32,
+    /// beyond the weekly limit, not counting the daily overtime again
+    pub weekly_overtime: u32,
+}
+
+impl WeekHours {
+    pub fn overtime(&self) -> u32 {
+        self.daily_overtime + self.weekly_overtime
+    }
+}
+
+/// Hours of one staff over a schedule
+#[derive(Debug, Clone)]
+pub struct HoursReport<'a> {
+    pub staff: &'a Staff,
+    /// days worked, in order
+    pub days: Vec<DayHours>,
+    /// every week of the schedule, in order
+    pub weeks: Vec<WeekHours>,
+    /// calendar months worked in, only with a calendar
+    pub months: Vec<(Period, u32)>,
+}
+
+impl HoursReport<'_> {
+    pub fn minutes(&self) -> u32 {
+        self.weeks.iter().map(|w| w.minutes).sum()
+    }
+
+    pub fn overtime(&self) -> u32 {
+        self.weeks.iter().map(WeekHours::overtime).sum()
+    }
+}
+
+/// Hours of every staff of the roster, in roster order
+///
+/// a day beyond `daily_minutes` counts its excess as overtime, and a week
+/// beyond `weekly_minutes` once the daily overtime is taken out counts that
+/// excess too, the way 時間外労働 is counted for 36協定. Weeks are rule weeks.
+pub fn working_hours<'a>(
+    schedule: &DecidedSchedule<'_>,
+    staff_group_list: &'a StaffGroupList,
+    durations: &SlotDurations,
+    limits: &StatutoryLimits,
+    calendar: Option<&ShiftCalendar>,
+) -> Vec<HoursReport<'a>> {
+    let mut days: BTreeMap<(usize, usize), Vec<DayHours>> = BTreeMap::new();
+    let mut months: BTreeMap<(usize, usize), BTreeMap<Period, u32>> = BTreeMap::new();
+    for (week, day, day_shift) in schedule.days() {
+        let mut minutes: BTreeMap<(usize, usize), u32> = BTreeMap::new();
+        for slot in Slot::ALL {
+            for staff in day_shift.slot(slot) {
+                *minutes.entry((staff.get_group_id(), staff.get_id())).or_default() += durations.minutes(slot);
+            }
+        }
+        let month = calendar.map(|c| c.date_of(week, day)).map(|d| Period::Month { year: d.year(), month: d.month() });
+        for (key, minutes) in minutes {
+            let overtime = minutes.saturating_sub(limits.daily_minutes);
+            days.entry(key).or_default().push(DayHours { week, day, minutes, overtime });
+            if let Some(month) = month {
+                *months.entry(key).or_default().entry(month).or_default() += minutes;
+            }
+        }
+    }
+
+    staff_group_list
+        .0
+        .iter()
+        .flat_map(|group| group.iter())
+        .map(|staff| {
+            let key = (staff.get_group_id(), staff.get_id());
+            let days = days.remove(&key).unwrap_or_default();
+            let weeks = (schedule.week_delta..schedule.week_delta + schedule.weeks.len())
+                .map(|week| {
+                    let in_week = days.iter().filter(|d| d.week == week);
+                    let minutes = in_week.clone().map(|d| d.minutes).sum();
+                    let daily_overtime = in_week.clone().map(|d| d.overtime).sum();
+                    let regular: u32 = in_week.map(|d| d.minutes - d.overtime).sum();
+                    let weekly_overtime = regular.saturating_sub(limits.weekly_minutes);
+                    WeekHours { week, minutes, daily_overtime, weekly_overtime }
+                })
+                .collect();
+            let months = months.remove(&key).unwrap_or_default().into_iter().collect();
+            HoursReport { staff, days, weeks, months }
+        })
+        .collect()
+}
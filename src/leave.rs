@@ -0,0 +1,201 @@
+use std::collections::BTreeMap;
+
+use chrono::{Months, NaiveDate};
+
+use crate::shift_gen::{
+    EmploymentType, Staff
+};
+
+/// 年次有給休暇 granted to full-time staff, by years of service from 0.5
+const FULL_TIME_GRANTS: [u32; 7] = [10, 11, 12, 14, 16, 18, 20];
+
+/// 比例付与 to staff working 4, 3, 2 and 1 days a week
+const PROPORTIONAL_GRANTS: [[u32; 7]; 4] = [
+    [7, 8, 9, 10, 12, 13, 15],
+    [5, 6, 6, 8, 9, 10, 11],
+    [3, 4, 4, 5, 6, 6, 7],
+    [1, 2, 2, 2, 3, 3, 3],
+];
+
+/// 出勤率 needed in the year before a grant (労働基準法39条)
+pub const MIN_ATTENDANCE: f64 = 0.8;
+
+/// days of leave the employer must see taken within a year of a grant of
+/// 10 days or more (年5日の時季指定義務)
+pub const MANDATORY_DAYS: u32 = 5;
+
+/// Days granted at the `index`-th grant date, 0 being six months after joining
+///
+/// `days_per_week` is `None` for full-time staff and staff working 5 days or
+/// 30 hours a week or more.
+pub fn statutory_grant(index: usize, days_per_week: Option<u32>) -> u32 {
+    let table = match days_per_week {
+        None | Some(5..) => &FULL_TIME_GRANTS,
+        Some(0) => return 0,
+        Some(days) => &PROPORTIONAL_GRANTS[4 - days as usize],
+    };
+    table[index.min(table.len() - 1)]
+}
+
+/// Scheduled days a week of a staff for 比例付与, `None` for a full grant
+///
+/// part-timers take the days of their contract unless it allows 30 hours a
+/// week; without a contract they get the full grant.
+pub fn grant_days_per_week(staff: &Staff) -> Option<u32> {
+    if staff.profile.employment_type != Some(EmploymentType::PartTime) {
+        return None;
+    }
+    let contract = staff.profile.contract.as_ref()?;
+    if contract.max_hours_per_week.is_some_and(|h| h >= 30) {
+        return None;
+    }
+    contract.max_days_per_week
+}
+
+/// Approved paid leave of a staff, read from `[[leave]]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Leave {
+    pub group_id: usize,
+    pub staff_id: usize,
+    pub from: NaiveDate,
+    /// inclusive
+    pub to: NaiveDate,
+}
+
+impl Leave {
+    /// every date of the leave, holidays included
+    pub fn dates(&self) -> impl Iterator<Item = NaiveDate> + '_ {
+        self.from.iter_days().take_while(|d| *d <= self.to)
+    }
+}
+
+/// 出勤率 of a staff in the year before a grant date, read from `[[attendance]]`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Attendance {
+    pub group_id: usize,
+    pub staff_id: usize,
+    pub grant_date: NaiveDate,
+    pub rate: f64,
+}
+
+/// One grant and what has become of it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeaveGrant {
+    pub date: NaiveDate,
+    pub days: u32,
+    /// first day the remaining days are lost, two years after the grant
+    pub expires: NaiveDate,
+    /// days taken out of this grant
+    pub used: u32,
+    /// no days for attendance under `MIN_ATTENDANCE`
+    pub withheld: bool,
+}
+
+impl LeaveGrant {
+    pub fn remaining(&self) -> u32 {
+        self.days - self.used
+    }
+}
+
+/// Days taken within a year of a grant of 10 days or more
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MandatoryLeave {
+    pub grant_date: NaiveDate,
+    /// last day of the year of the grant
+    pub deadline: NaiveDate,
+    pub taken: u32,
+}
+
+impl MandatoryLeave {
+    pub fn is_met(&self) -> bool {
+        self.taken >= MANDATORY_DAYS
+    }
+}
+
+/// Paid leave of one staff up to a date
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeaveLedger {
+    /// grants up to the date, oldest first
+    pub grants: Vec<LeaveGrant>,
+    /// days of leave taken with no balance left
+    pub unpaid: Vec<NaiveDate>,
+    /// one entry per grant of 10 days or more, oldest first
+    pub mandatory: Vec<MandatoryLeave>,
+    pub until: NaiveDate,
+}
+
+impl LeaveLedger {
+    /// days left on `until`
+    pub fn balance(&self) -> u32 {
+        self.grants.iter().filter(|g| g.expires > self.until).map(LeaveGrant::remaining).sum()
+    }
+
+    /// days that expired unused
+    pub fn lost(&self) -> u32 {
+        self.grants.iter().filter(|g| g.expires <= self.until).map(LeaveGrant::remaining).sum()
+    }
+
+    /// years whose deadline passed short of `MANDATORY_DAYS`
+    pub fn missed(&self) -> Vec<MandatoryLeave> {
+        self.mandatory.iter().filter(|m| m.deadline < self.until && !m.is_met()).copied().collect()
+    }
+
+    /// the current year, when it is still short of `MANDATORY_DAYS`
+    pub fn at_risk(&self) -> Option<MandatoryLeave> {
+        self.mandatory.iter().find(|m| m.grant_date <= self.until && self.until <= m.deadline && !m.is_met()).copied()
+    }
+}
+
+/// Build the ledger of a staff from their join date
+///
+/// grants fall six months after joining and every year after that, from
+/// `statutory_grant`; `attendance` holds the 出勤率 of the year before a
+/// grant date when it is known, full otherwise. Every date of `taken` up to
+/// `until` uses a day of the oldest grant still valid on it.
+pub fn build_ledger(
+    join_date: NaiveDate,
+    days_per_week: Option<u32>,
+    attendance: &BTreeMap<NaiveDate, f64>,
+    taken: &[NaiveDate],
+    until: NaiveDate,
+) -> LeaveLedger {
+    let mut grants = vec![];
+    for index in 0.. {
+        let Some(date) = join_date.checked_add_months(Months::new(6 + 12 * index as u32)) else {
+            break;
+        };
+        if date > until {
+            break;
+        }
+        let withheld = attendance.get(&date).is_some_and(|rate| *rate < MIN_ATTENDANCE);
+        let days = if withheld { 0 } else { statutory_grant(index, days_per_week) };
+        let expires = date.checked_add_months(Months::new(24)).unwrap_or(NaiveDate::MAX);
+        grants.push(LeaveGrant { date, days, expires, used: 0, withheld });
+    }
+
+    let mut taken: Vec<NaiveDate> = taken.iter().copied().filter(|d| *d <= until).collect();
+    taken.sort_unstable();
+    taken.dedup();
+    let mut unpaid = vec![];
+    for day in &taken {
+        match grants.iter_mut().find(|g| g.date <= *day && *day < g.expires && g.remaining() > 0) {
+            Some(grant) => grant.used += 1,
+            None => unpaid.push(*day),
+        }
+    }
+
+    let mandatory = grants
+        .iter()
+        .filter(|g| g.days >= 10)
+        .map(|g| {
+            let deadline = g.date.checked_add_months(Months::new(12)).and_then(|d| d.pred_opt()).unwrap_or(NaiveDate::MAX);
+            let taken = taken
+                .iter()
+                .filter(|d| g.date <= **d && **d <= deadline && !unpaid.contains(d))
+                .count() as u32;
+            MandatoryLeave { grant_date: g.date, deadline, taken }
+        })
+        .collect();
+
+    LeaveLedger { grants, unpaid, mandatory, until }
+}
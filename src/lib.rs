@@ -1,3 +1,31 @@
 pub mod shift_gen;
 pub mod rule_checker;
 pub mod rule_checker00;
+pub mod render;
+pub mod document;
+pub mod calendar;
+pub mod xlsx;
+pub mod roster;
+pub mod schedule_checker;
+pub mod stats;
+pub mod history;
+pub mod config;
+pub mod export;
+pub mod validate;
+pub mod query;
+pub mod diff;
+pub mod explain;
+pub mod editor;
+pub mod synth;
+pub mod solver;
+pub mod repair;
+pub mod swap;
+pub mod period;
+pub mod offset;
+pub mod preference;
+pub mod skill;
+pub mod contract;
+pub mod hours;
+pub mod leave;
+pub mod compensatory;
+pub mod availability;
@@ -0,0 +1,149 @@
+use crate::shift_gen::{
+    DecidedSchedule, DayRule, Incomplete, ShiftHoll, StaffGroupList, WeekRule, WeekRuleTable
+};
+use crate::stats::AssignmentCounts;
+
+/// Where a rule table starts: which week rule comes first and which staff
+/// each group starts from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StartOffset {
+    /// week rule used for the weeks the table used to give to rule 0
+    pub rule_offset: usize,
+    /// added to the hole ids of each group, modulo its size
+    pub group_shifts: Vec<usize>,
+}
+
+impl StartOffset {
+    /// Rule table starting at this offset
+    ///
+    /// the week rules are rotated by `rule_offset` and every hole id of a
+    /// group is moved by its shift, so the table can be written back to the
+    /// config and generated for the same weeks.
+    pub fn apply<'a>(
+        &self,
+        week_rule_table: &WeekRuleTable<'a, Incomplete>,
+        staff_group_list: &StaffGroupList,
+    ) -> WeekRuleTable<'a, Incomplete> {
+        let cycle = week_rule_table.0.len();
+        let shift = |holes: &[ShiftHoll<'a, Incomplete>]| -> Vec<ShiftHoll<'a, Incomplete>> {
+            holes
+                .iter()
+                .map(|h| {
+                    let shifted = |(group_id, id): (usize, usize)| {
+                        let len = staff_group_list.0.get(group_id).map_or(0, |g| g.len());
+                        let by = self.group_shifts.get(group_id).copied().unwrap_or(0);
+                        (group_id, if len == 0 { id } else { (id + by) % len })
+                    };
+                    let (group_id, id) = shifted((h.group_id, h.id));
+                    ShiftHoll::new(group_id, id).with_fallback(h.fallback.iter().copied().map(shifted).collect())
+                })
+                .collect()
+        };
+        WeekRuleTable(
+            (0..cycle)
+                .map(|i| {
+                    let week_rule = &week_rule_table.0[(i + self.rule_offset) % cycle];
+                    WeekRule(std::array::from_fn(|day| DayRule {
+                        shift_morning: shift(&week_rule.0[day].shift_morning),
+                        shift_afternoon: shift(&week_rule.0[day].shift_afternoon),
+                    }))
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Imbalance of the counters of each group
+///
+/// for every (weekday, slot) and for the totals, `n * Σc² - (Σc)²` over the
+/// `n` staff of the group: `n²` times the variance, 0 when everybody has the
+/// same count.
+pub fn imbalance(counts: &AssignmentCounts, staff_group_list: &StaffGroupList) -> Vec<u64> {
+    let spread = |values: &[u64]| {
+        let n = values.len() as u64;
+        let sum: u64 = values.iter().sum();
+        let squares: u64 = values.iter().map(|v| v * v).sum();
+        n * squares - sum * sum
+    };
+    staff_group_list
+        .0
+        .iter()
+        .enumerate()
+        .map(|(group_id, group)| {
+            let per_staff: Vec<_> = group.iter().map(|s| counts.get(group_id, s.get_id())).collect();
+            let mut cost = 0;
+            for day in 0..7 {
+                for slot in 0..2 {
+                    let values: Vec<u64> = per_staff.iter().map(|c| c[day][slot] as u64).collect();
+                    cost += spread(&values);
+                }
+            }
+            let totals: Vec<u64> = per_staff.iter().map(|c| c.iter().flatten().sum::<usize>() as u64).collect();
+            cost + spread(&totals)
+        })
+        .collect()
+}
+
+/// An offset and the imbalance of history plus the weeks generated from it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OffsetScore {
+    pub offset: StartOffset,
+    /// `imbalance` of each group
+    pub group_costs: Vec<u64>,
+}
+
+impl OffsetScore {
+    pub fn cost(&self) -> u64 {
+        self.group_costs.iter().fold(0, |sum, c| sum.saturating_add(*c))
+    }
+}
+
+/// Rank the start offsets of a rule table against historical counts
+///
+/// every rule offset is tried, and for each one every shift of every group,
+/// one group after another keeping the shifts already chosen. A group whose
+/// staff fill the fallback holes of another is scored with the shifts of the
+/// groups before it only, so the best combination may be missed then.
+/// The score is the imbalance of `history` plus the weeks `generate` makes
+/// from the rule table of the offset, the ones `ShiftConfig::generate` would
+/// schedule with it. An offset `generate` fails on scores every group at
+/// `u64::MAX`. One score per rule offset is returned, the fairest first, ties
+/// going to the smaller change.
+pub fn rank_offsets<'t, 'a>(
+    week_rule_table: &WeekRuleTable<'t, Incomplete>,
+    staff_group_list: &StaffGroupList,
+    history: &AssignmentCounts,
+    generate: impl Fn(&WeekRuleTable<'t, Incomplete>) -> Option<DecidedSchedule<'a>>,
+) -> Vec<OffsetScore> {
+    let cycle = week_rule_table.0.len();
+    if cycle == 0 || staff_group_list.0.iter().any(|g| g.len() == 0) {
+        return vec![];
+    }
+    let score = |offset: &StartOffset| {
+        let table = offset.apply(week_rule_table, staff_group_list);
+        let Some(schedule) = generate(&table) else {
+            return vec![u64::MAX; staff_group_list.0.len()];
+        };
+        let mut counts = history.clone();
+        counts.add_schedule(&schedule);
+        imbalance(&counts, staff_group_list)
+    };
+
+    let mut scores = vec![];
+    for rule_offset in 0..cycle {
+        let mut offset = StartOffset { rule_offset, group_shifts: vec![0; staff_group_list.0.len()] };
+        for (group_id, group) in staff_group_list.0.iter().enumerate() {
+            let best = (0..group.len())
+                .min_by_key(|&shift| {
+                    offset.group_shifts[group_id] = shift;
+                    (score(&offset)[group_id], shift)
+                })
+                .unwrap_or(0);
+            offset.group_shifts[group_id] = best;
+        }
+        let group_costs = score(&offset);
+        scores.push(OffsetScore { offset, group_costs });
+    }
+    scores.sort_by_key(|s| (s.cost(), s.offset.rule_offset));
+    scores
+}
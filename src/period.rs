@@ -0,0 +1,132 @@
+use crate::shift_gen::{
+    DecidedSchedule, Incomplete, Slot, Staff, StaffGroupList, WeekRuleTable
+};
+use crate::stats::{AssignmentCounts, WeekdaySlotCounts};
+
+/// (weekday, slot) assignments of one staff within the period of their group
+#[derive(Debug, Clone)]
+pub struct StaffCoverage<'a> {
+    pub staff: &'a Staff,
+    pub counts: WeekdaySlotCounts,
+}
+
+impl StaffCoverage<'_> {
+    pub fn count(&self, day: usize, slot: Slot) -> usize {
+        self.counts[day][Slot::ALL.iter().position(|s| *s == slot).unwrap_or(0)]
+    }
+}
+
+/// Repeat period of the assignments of one group
+#[derive(Debug, Clone)]
+pub struct GroupPeriod<'a> {
+    pub group_id: usize,
+    /// weeks after which the group works the same as from week 0, the
+    /// weeks analysed when it does not repeat within them
+    pub period: usize,
+    /// holes its staff work within the period, by (weekday, slot)
+    pub holes: WeekdaySlotCounts,
+    pub staff: Vec<StaffCoverage<'a>>,
+}
+
+impl GroupPeriod<'_> {
+    /// (weekday, slot) the group has holes in but the staff never gets
+    pub fn never(&self, staff_id: usize) -> Vec<(usize, Slot)> {
+        let Some(coverage) = self.staff.get(staff_id) else {
+            return vec![];
+        };
+        (0..7)
+            .flat_map(|day| Slot::ALL.map(|slot| (day, slot)))
+            .filter(|&(day, slot)| coverage.count(day, slot) == 0)
+            .filter(|&(day, slot)| self.holes[day][Slot::ALL.iter().position(|s| *s == slot).unwrap_or(0)] > 0)
+            .collect()
+    }
+}
+
+/// Repeat periods of a generated schedule
+#[derive(Debug, Clone)]
+pub struct PeriodAnalysis<'a> {
+    pub groups: Vec<GroupPeriod<'a>>,
+    /// weeks after which the whole schedule repeats, the lcm of the group periods
+    pub period: usize,
+    /// weeks generated from week 0, every period divides them
+    pub weeks: usize,
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+fn lcm(a: usize, b: usize) -> usize {
+    a / gcd(a, b) * b
+}
+
+/// staff ids of a group in each slot of a week, sorted as the order of the holes does not matter
+fn signature(schedule: &DecidedSchedule<'_>, week: usize, group_id: usize) -> Vec<Vec<usize>> {
+    let week_shift = &schedule.weeks[week];
+    week_shift
+        .0
+        .iter()
+        .flat_map(|day| Slot::ALL.map(|slot| day.slot(slot)))
+        .map(|staff| {
+            let mut ids: Vec<usize> = staff.iter().filter(|s| s.get_group_id() == group_id).map(|s| s.get_id()).collect();
+            ids.sort_unstable();
+            ids
+        })
+        .collect()
+}
+
+/// Find the true repeat period of the schedule of each group
+///
+/// `generate` makes the schedule of the given number of weeks from week 0,
+/// as `ShiftConfig::generate` does. `gen_shift` gives hole `id` of week `w`
+/// to staff `(w / cycle + id) % n`, so rotation repeats after the lcm of
+/// `cycle * n` over the groups, and so do the holes it hands on for
+/// availability patterns and to fallback groups. The weeks analysed are that
+/// bound, stretched to a multiple of it past `last_dated_week`, the last week
+/// dated availability, absences or overrides change. The period of a group
+/// is the smallest divisor of those weeks they repeat with, and the
+/// (weekday, slot) counts of each staff are taken over one period. Without
+/// availability and fallbacks every staff of a group gets the same counts;
+/// `never` tells which combinations a staff is kept off.
+/// `None` when the rule table is empty, a group has no staff or `generate`
+/// fails.
+pub fn analyze_period<'a>(
+    week_rule_table: &WeekRuleTable<'_, Incomplete>,
+    staff_group_list: &'a StaffGroupList,
+    last_dated_week: Option<usize>,
+    generate: impl FnOnce(usize) -> Option<DecidedSchedule<'a>>,
+) -> Option<PeriodAnalysis<'a>> {
+    let cycle = week_rule_table.0.len();
+    if cycle == 0 || staff_group_list.0.iter().any(|g| g.len() == 0) {
+        return None;
+    }
+    let bound = staff_group_list.0.iter().fold(cycle, |acc, g| lcm(acc, cycle * g.len()));
+    let weeks = last_dated_week.map_or(bound, |last| (last / bound + 1) * bound);
+    let schedule = generate(weeks).filter(|s| s.weeks.len() == weeks)?;
+
+    let mut groups = vec![];
+    for (group_id, group) in staff_group_list.0.iter().enumerate() {
+        let signatures: Vec<Vec<Vec<usize>>> = (0..weeks).map(|w| signature(&schedule, w, group_id)).collect();
+        let period = (1..=weeks)
+            .filter(|p| weeks.is_multiple_of(*p))
+            .find(|p| (0..weeks).all(|w| signatures[w] == signatures[(w + p) % weeks]))
+            .unwrap_or(weeks);
+
+        let in_period = DecidedSchedule::new(0, schedule.weeks[..period].to_vec().into_boxed_slice());
+        let counts = AssignmentCounts::from_schedule(&in_period);
+        let mut holes = WeekdaySlotCounts::default();
+        for staff in group.iter() {
+            for (day, slots) in counts.get(group_id, staff.get_id()).iter().enumerate() {
+                holes[day][0] += slots[0];
+                holes[day][1] += slots[1];
+            }
+        }
+        let staff = group
+            .iter()
+            .map(|staff| StaffCoverage { staff, counts: counts.get(group_id, staff.get_id()) })
+            .collect();
+        groups.push(GroupPeriod { group_id, period, holes, staff });
+    }
+    let period = groups.iter().fold(1, |acc, g| lcm(acc, g.period));
+    Some(PeriodAnalysis { groups, period, weeks })
+}
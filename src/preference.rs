@@ -0,0 +1,108 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::calendar::ShiftCalendar;
+use crate::shift_gen::{
+    DecidedSchedule, Slot, Staff, StaffGroupList
+};
+
+/// Like (positive weight) or dislike (negative weight) of a staff
+///
+/// it applies to the assignments matching every field given: `weekday` and
+/// `slot` repeat every week, `date` needs a calendar.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StaffPreference {
+    /// 0 is the first day of a rule week
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weekday: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slot: Option<Slot>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<NaiveDate>,
+    pub weight: i64,
+}
+
+impl StaffPreference {
+    /// `date` is `None` without a calendar, date preferences then never match
+    pub fn matches(&self, day: usize, slot: Slot, date: Option<NaiveDate>) -> bool {
+        self.weekday.is_none_or(|d| d == day)
+            && self.slot.is_none_or(|s| s == slot)
+            && self.date.is_none_or(|d| Some(d) == date)
+    }
+}
+
+/// Sum of the weights of the preferences of a staff matching an assignment
+pub fn preference_weight(staff: &Staff, day: usize, slot: Slot, date: Option<NaiveDate>) -> i64 {
+    staff
+        .profile
+        .preferences
+        .iter()
+        .filter(|p| p.matches(day, slot, date))
+        .map(|p| p.weight)
+        .sum()
+}
+
+/// How well a schedule meets the preferences of one staff
+#[derive(Debug, Clone)]
+pub struct Satisfaction<'a> {
+    pub staff: &'a Staff,
+    pub assignments: usize,
+    /// assignments matching a like
+    pub liked: usize,
+    /// assignments matching a dislike
+    pub disliked: usize,
+    /// sum of `preference_weight` over the assignments
+    pub score: i64,
+}
+
+/// Score of a schedule, the sum of the preference weights of every assignment
+pub fn score_schedule(schedule: &DecidedSchedule<'_>, calendar: Option<&ShiftCalendar>) -> i64 {
+    let mut score = 0;
+    for (week, day, day_shift) in schedule.days() {
+        let date = calendar.map(|c| c.date_of(week, day));
+        for slot in Slot::ALL {
+            score += day_shift.slot(slot).iter().map(|s| preference_weight(s, day, slot, date)).sum::<i64>();
+        }
+    }
+    score
+}
+
+/// Satisfaction of every staff of the roster, in roster order
+pub fn satisfaction_report<'a>(
+    schedule: &DecidedSchedule<'_>,
+    staff_group_list: &'a StaffGroupList,
+    calendar: Option<&ShiftCalendar>,
+) -> Vec<Satisfaction<'a>> {
+    let mut report: Vec<Vec<Satisfaction<'a>>> = staff_group_list
+        .0
+        .iter()
+        .map(|group| {
+            group
+                .iter()
+                .map(|staff| Satisfaction { staff, assignments: 0, liked: 0, disliked: 0, score: 0 })
+                .collect()
+        })
+        .collect();
+    for (week, day, day_shift) in schedule.days() {
+        let date = calendar.map(|c| c.date_of(week, day));
+        for slot in Slot::ALL {
+            for staff in day_shift.slot(slot) {
+                let Some(entry) = report.get_mut(staff.get_group_id()).and_then(|g| g.get_mut(staff.get_id())) else {
+                    continue;
+                };
+                let weights: Vec<i64> = staff
+                    .profile
+                    .preferences
+                    .iter()
+                    .filter(|p| p.matches(day, slot, date))
+                    .map(|p| p.weight)
+                    .collect();
+                entry.assignments += 1;
+                entry.liked += weights.iter().any(|w| *w > 0) as usize;
+                entry.disliked += weights.iter().any(|w| *w < 0) as usize;
+                entry.score += weights.iter().sum::<i64>();
+            }
+        }
+    }
+    report.into_iter().flatten().collect()
+}
@@ -0,0 +1,133 @@
+use chrono::NaiveDate;
+
+use crate::availability::assign_available;
+use crate::calendar::ShiftCalendar;
+use crate::shift_gen::{
+    apply_overrides, DecidedSchedule, Incomplete, ShiftOverride, Slot, Staff, StaffGroupList, WeekDecidedShift,
+    WeekRuleTable
+};
+use crate::solver::Absence;
+
+/// One staff in one hole on one date
+#[derive(Debug, Clone, Copy)]
+pub struct Assignment<'a> {
+    pub date: NaiveDate,
+    /// absolute week index
+    pub week: usize,
+    pub day: usize,
+    pub slot: Slot,
+    pub hole: usize,
+    pub staff: &'a Staff,
+}
+
+/// Questions about the schedule generated from a rule table, by date
+pub struct ShiftQuery<'r, 'a> {
+    week_rule_table: &'r WeekRuleTable<'a, Incomplete>,
+    staff_group_list: &'a StaffGroupList,
+    calendar: ShiftCalendar,
+    overrides: &'r [ShiftOverride],
+    absences: &'r [Absence],
+}
+
+impl<'r, 'a> ShiftQuery<'r, 'a> {
+    pub fn new(
+        week_rule_table: &'r WeekRuleTable<'a, Incomplete>,
+        staff_group_list: &'a StaffGroupList,
+        calendar: ShiftCalendar,
+    ) -> Self {
+        Self { week_rule_table, staff_group_list, calendar, overrides: &[], absences: &[] }
+    }
+
+    /// answer with the overrides applied to the generated weeks
+    pub fn with_overrides(mut self, overrides: &'r [ShiftOverride]) -> Self {
+        self.overrides = overrides;
+        self
+    }
+
+    /// answer with the holes of absent staff handed on
+    pub fn with_absences(mut self, absences: &'r [Absence]) -> Self {
+        self.absences = absences;
+        self
+    }
+
+    /// the week as `ShiftConfig::generate` makes it with rotation: holes of
+    /// unavailable or absent staff handed on, then the overrides applied
+    fn week_shift(&self, week: usize) -> WeekDecidedShift<'a> {
+        let mut schedule = DecidedSchedule::generate(self.week_rule_table, self.staff_group_list, week, 1);
+        assign_available(&mut schedule, self.week_rule_table, self.staff_group_list, Some(&self.calendar), self.absences, &[]);
+        apply_overrides(&mut schedule.weeks, self.staff_group_list, week, self.overrides);
+        schedule.weeks.into_vec().remove(0)
+    }
+
+    fn assignments_of_week(&self, week: usize) -> Vec<Assignment<'a>> {
+        let week_shift = self.week_shift(week);
+        let mut assignments = vec![];
+        for (day, day_shift) in week_shift.0.iter().enumerate() {
+            for slot in Slot::ALL {
+                for (hole, staff) in day_shift.slot(slot).iter().enumerate() {
+                    assignments.push(Assignment {
+                        date: self.calendar.date_of(week, day),
+                        week,
+                        day,
+                        slot,
+                        hole,
+                        staff,
+                    });
+                }
+            }
+        }
+        assignments
+    }
+
+    /// Who works on a date, in every slot or in one slot
+    pub fn who(&self, date: NaiveDate, slot: Option<Slot>) -> Vec<Assignment<'a>> {
+        let Some((week, day)) = self.calendar.position_of(date) else {
+            return vec![];
+        };
+        self.assignments_of_week(week)
+            .into_iter()
+            .filter(|a| a.day == day && slot.is_none_or(|s| s == a.slot))
+            .collect()
+    }
+
+    /// Assignments of a staff between two dates, both included
+    pub fn assignments_between(&self, staff: &Staff, from: NaiveDate, to: NaiveDate) -> Vec<Assignment<'a>> {
+        let first_week = self.calendar.week_of(from).unwrap_or(0);
+        let Some(last_week) = self.calendar.week_of(to) else {
+            return vec![];
+        };
+        (first_week..=last_week)
+            .flat_map(|week| self.assignments_of_week(week))
+            .filter(|a| std::ptr::eq(a.staff, staff) && from <= a.date && a.date <= to)
+            .collect()
+    }
+
+    /// weeks after which the rotation of every group repeats
+    fn period_bound(&self) -> usize {
+        let max_group_len = self.staff_group_list.0.iter().map(|g| g.len()).max().unwrap_or(1);
+        self.week_rule_table.0.len() * max_group_len.max(1)
+    }
+
+    /// Next `n` assignments of a staff on or after a date
+    ///
+    /// fewer than `n` are returned when the staff is not assigned within one
+    /// rotation period.
+    pub fn next_assignments(&self, staff: &Staff, from: NaiveDate, n: usize) -> Vec<Assignment<'a>> {
+        let first_week = self.calendar.week_of(from).unwrap_or(0);
+        let mut found = vec![];
+        let mut idle_weeks = 0;
+        let mut week = first_week;
+        while found.len() < n && idle_weeks <= self.period_bound() {
+            let before = found.len();
+            found.extend(
+                self.assignments_of_week(week)
+                    .into_iter()
+                    .filter(|a| std::ptr::eq(a.staff, staff) && from <= a.date),
+            );
+            idle_weeks = if found.len() == before { idle_weeks + 1 } else { 0 };
+            week += 1;
+        }
+        found.truncate(n);
+        found
+    }
+}
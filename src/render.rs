@@ -0,0 +1,212 @@
+use chrono::{Days, NaiveDate};
+use unicode_width::UnicodeWidthStr;
+
+use crate::shift_gen::{
+    Slot, Staff, StaffGroupList, WeekDecidedShift
+};
+
+pub const WEEKDAY_NAMES: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// ANSI colours assigned to staff groups in order of group id
+const GROUP_COLORS: [u8; 6] = [33, 36, 32, 35, 34, 31];
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RenderOptions {
+    /// colour cells with ANSI escape sequences
+    pub color: bool,
+    /// label days with dates counted from the first day of `shift[0]`
+    pub start_date: Option<NaiveDate>,
+}
+
+impl RenderOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn with_start_date(mut self, start_date: Option<NaiveDate>) -> Self {
+        self.start_date = start_date;
+        self
+    }
+}
+
+impl Slot {
+    /// short marker used by the staff × day matrix
+    pub fn marker(&self) -> &'static str {
+        match self {
+            Slot::Morning => "M",
+            Slot::Afternoon => "A",
+        }
+    }
+}
+
+/// marker for a day without any assignment
+const OFF_MARKER: &str = "–";
+
+struct Cell {
+    text: String,
+    width: usize,
+    color: Option<u8>,
+}
+
+impl Cell {
+    fn plain(text: &str) -> Self {
+        Self { text: text.to_string(), width: text.width(), color: None }
+    }
+
+    fn colored(text: &str, color: Option<u8>) -> Self {
+        Self { text: text.to_string(), width: text.width(), color }
+    }
+
+    /// cell made of several coloured parts joined by `sep`
+    fn joined(parts: &[(&str, Option<u8>)], sep: &str, options: &RenderOptions) -> Self {
+        let width = parts.iter().map(|(s, _)| s.width()).sum::<usize>()
+            + sep.width() * parts.len().saturating_sub(1);
+        let text = parts
+            .iter()
+            .map(|(s, color)| paint(s, *color, options))
+            .collect::<Vec<_>>()
+            .join(sep);
+        Self { text, width, color: None }
+    }
+}
+
+fn paint(text: &str, color: Option<u8>, options: &RenderOptions) -> String {
+    match color {
+        Some(code) if options.color => format!("\x1b[{}m{}\x1b[0m", code, text),
+        _ => text.to_string(),
+    }
+}
+
+fn group_color(staff: &Staff) -> Option<u8> {
+    Some(GROUP_COLORS[staff.get_group_id() % GROUP_COLORS.len()])
+}
+
+/// layout rows into columns padded by their display width
+fn draw_table(header: Vec<Cell>, rows: Vec<Vec<Cell>>, options: &RenderOptions) -> String {
+    let columns = header.len();
+    let widths: Vec<usize> = (0..columns)
+        .map(|c| {
+            std::iter::once(&header)
+                .chain(rows.iter())
+                .filter_map(|row| row.get(c))
+                .map(|cell| cell.width)
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let draw_row = |row: &[Cell]| -> String {
+        let line = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| {
+                format!(
+                    "{}{}",
+                    paint(&cell.text, cell.color, options),
+                    " ".repeat(width - cell.width)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" | ");
+        format!("{}\n", line.trim_end())
+    };
+    let rule = widths
+        .iter()
+        .map(|w| "-".repeat(*w))
+        .collect::<Vec<_>>()
+        .join("-+-");
+
+    let mut out = draw_row(&header);
+    out.push_str(&rule);
+    out.push('\n');
+    for row in &rows {
+        out.push_str(&draw_row(row));
+    }
+    out
+}
+
+fn day_label(week: usize, day: usize, options: &RenderOptions) -> String {
+    match options.start_date {
+        Some(start) => format!("{} {}", start + Days::new((7 * week + day) as u64), WEEKDAY_NAMES[day]),
+        None => format!("W{} {}", week, WEEKDAY_NAMES[day]),
+    }
+}
+
+/// Render a day × slot grid
+///
+/// one row per day, one column per slot listing the assigned staff
+pub fn render_day_slot_grid(shift: &[WeekDecidedShift<'_>], options: &RenderOptions) -> String {
+    let header = std::iter::once(Cell::plain("Day"))
+        .chain(Slot::ALL.iter().map(|slot| Cell::plain(slot.label())))
+        .collect();
+
+    let rows = shift
+        .iter()
+        .enumerate()
+        .flat_map(|(week, week_shift)| {
+            week_shift.0.iter().enumerate().map(move |(day, day_shift)| {
+                std::iter::once(Cell::plain(&day_label(week, day, options)))
+                    .chain(Slot::ALL.iter().map(|slot| {
+                        let parts: Vec<(&str, Option<u8>)> = day_shift
+                            .slot(*slot)
+                            .iter()
+                            .map(|staff| (staff.name.as_str(), group_color(staff)))
+                            .collect();
+                        Cell::joined(&parts, ", ", options)
+                    }))
+                    .collect()
+            })
+        })
+        .collect();
+
+    draw_table(header, rows, options)
+}
+
+/// Render a staff × day matrix
+///
+/// one row per staff, one column per day marked with `M`, `A`, `MA` or `–`
+pub fn render_staff_day_matrix(
+    shift: &[WeekDecidedShift<'_>],
+    staff_group_list: &StaffGroupList,
+    options: &RenderOptions
+) -> String {
+    let header = [Cell::plain("Group"), Cell::plain("Staff")]
+        .into_iter()
+        .chain(shift.iter().enumerate().flat_map(|(week, _)| {
+            (0..7).map(move |day| Cell::plain(&day_label(week, day, options)))
+        }))
+        .collect();
+
+    let rows = staff_group_list
+        .0
+        .iter()
+        .flat_map(|group| group.iter().map(move |staff| (group, staff)))
+        .map(|(group, staff)| {
+            [
+                Cell::colored(group.get_name(), group_color(staff)),
+                Cell::plain(&staff.name),
+            ]
+            .into_iter()
+            .chain(shift.iter().flat_map(|week_shift| week_shift.0.iter()).map(|day_shift| {
+                let marker: String = Slot::ALL
+                    .iter()
+                    .filter(|slot| day_shift.slot(**slot).iter().any(|s| std::ptr::eq(*s, staff)))
+                    .map(|slot| slot.marker())
+                    .collect();
+                if marker.is_empty() {
+                    Cell::plain(OFF_MARKER)
+                } else {
+                    Cell::colored(&marker, group_color(staff))
+                }
+            }))
+            .collect()
+        })
+        .collect();
+
+    draw_table(header, rows, options)
+}
@@ -0,0 +1,51 @@
+use std::collections::HashMap;
+
+use crate::diff::StaffKey;
+use crate::shift_gen::{
+    DecidedSchedule, Incomplete, Slot, StaffGroupList, WeekRuleTable
+};
+use crate::solver::{search_schedule, PublishedSlots, SolveErr, SolverConfig};
+
+/// A schedule already handed out, with the roster its staff come from
+pub struct Published<'s, 'a> {
+    pub schedule: &'s DecidedSchedule<'a>,
+    pub staff_group_list: &'s StaffGroupList,
+}
+
+/// Find a schedule for changed rules, roster or absences that moves as few
+/// assignments of a published schedule as possible
+///
+/// the holes of `week_rule_table` are filled by `solve_schedule` under the
+/// constraints of `config`, with keeping a staff who worked the same slot of
+/// the published schedule costing less than anything else. Staff are matched
+/// across the rosters by `StaffKey`, so someone leaving or joining only
+/// changes their own assignments. The search keeps whatever the constraints
+/// allow but is not exhaustive: the result is a local minimum of the changes.
+pub fn repair_schedule<'a>(
+    published: &Published<'_, '_>,
+    week_rule_table: &WeekRuleTable<'_, Incomplete>,
+    staff_group_list: &'a StaffGroupList,
+    week_delta: usize,
+    week_gen_range: usize,
+    config: &SolverConfig,
+) -> Result<DecidedSchedule<'a>, SolveErr> {
+    let ids: HashMap<StaffKey, (usize, usize)> = staff_group_list
+        .0
+        .iter()
+        .flat_map(|group| group.iter())
+        .map(|staff| (StaffKey::of(staff, staff_group_list), (staff.get_group_id(), staff.get_id())))
+        .collect();
+
+    let mut slots = PublishedSlots::new();
+    for (week, day, day_shift) in published.schedule.days() {
+        for slot in Slot::ALL {
+            for staff in day_shift.slot(slot) {
+                if let Some(&(group_id, staff_id)) = ids.get(&StaffKey::of(staff, published.staff_group_list)) {
+                    slots.entry((week, day, slot, group_id)).or_default().push(staff_id);
+                }
+            }
+        }
+    }
+
+    search_schedule(week_rule_table, staff_group_list, week_delta, week_gen_range, config, Some(&slots))
+}
@@ -0,0 +1,241 @@
+//! CSV roster import
+//!
+//! ```csv
+//! group,name,employee_id,join_date,employment_type,skills,store
+//! A,田中太郎,E0001,2023-04-01,full-time,keyholder;cash,shibuya
+//! B,鈴木花子,E0002,2024-10-01,part-time,,shinjuku
+//! ```
+//!
+//! `group` and `name` are required. `employee_id`, `join_date` (YYYY-MM-DD),
+//! `employment_type` and `skills` (separated by `;`) are optional, every other
+//! column is kept as a free-form attribute of the staff. Groups are created in
+//! order of first appearance.
+
+use std::collections::{BTreeMap, HashSet};
+use std::io::Read;
+
+use chrono::NaiveDate;
+
+use crate::shift_gen::{
+    EmploymentType, StaffGroup, StaffGroupList, StaffProfile
+};
+
+const GROUP_COLUMN: &str = "group";
+const NAME_COLUMN: &str = "name";
+const EMPLOYEE_ID_COLUMN: &str = "employee_id";
+const JOIN_DATE_COLUMN: &str = "join_date";
+const EMPLOYMENT_TYPE_COLUMN: &str = "employment_type";
+const SKILLS_COLUMN: &str = "skills";
+
+#[derive(Debug)]
+pub struct RosterErr {
+    /// 1-based line number in the csv, header is line 1
+    pub line: usize,
+    pub reason: CauseOfRosterErr,
+}
+
+#[derive(Debug)]
+pub enum CauseOfRosterErr {
+    Csv(csv::Error),
+    MissingColumn(&'static str),
+    EmptyField(&'static str),
+    InvalidJoinDate(String),
+    DuplicateEmployeeId(String),
+}
+
+impl std::fmt::Display for RosterErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.reason {
+            CauseOfRosterErr::Csv(e) => write!(f, "{}", e),
+            CauseOfRosterErr::MissingColumn(c) => write!(f, "missing column `{}`", c),
+            CauseOfRosterErr::EmptyField(c) => write!(f, "`{}` is empty", c),
+            CauseOfRosterErr::InvalidJoinDate(d) => write!(f, "invalid join date `{}`", d),
+            CauseOfRosterErr::DuplicateEmployeeId(id) => write!(f, "duplicate employee id `{}`", id),
+        }
+    }
+}
+
+/// Staff read from one csv row
+#[derive(Debug, Clone, PartialEq)]
+pub struct RosterRow {
+    pub line: usize,
+    pub group: String,
+    pub name: String,
+    pub profile: StaffProfile,
+}
+
+/// Result of reading a roster without building it
+#[derive(Debug)]
+pub struct RosterPreview {
+    pub rows: Vec<RosterRow>,
+    pub errors: Vec<RosterErr>,
+}
+
+struct Columns {
+    group: usize,
+    name: usize,
+    employee_id: Option<usize>,
+    join_date: Option<usize>,
+    employment_type: Option<usize>,
+    skills: Option<usize>,
+    attributes: Vec<(usize, String)>,
+}
+
+impl Columns {
+    fn from_header(header: &csv::StringRecord) -> Result<Self, RosterErr> {
+        let find = |name: &str| header.iter().position(|h| h.trim().eq_ignore_ascii_case(name));
+        let required = |name: &'static str| {
+            find(name).ok_or(RosterErr { line: 1, reason: CauseOfRosterErr::MissingColumn(name) })
+        };
+        let known = [GROUP_COLUMN, NAME_COLUMN, EMPLOYEE_ID_COLUMN, JOIN_DATE_COLUMN, EMPLOYMENT_TYPE_COLUMN, SKILLS_COLUMN];
+        Ok(Self {
+            group: required(GROUP_COLUMN)?,
+            name: required(NAME_COLUMN)?,
+            employee_id: find(EMPLOYEE_ID_COLUMN),
+            join_date: find(JOIN_DATE_COLUMN),
+            employment_type: find(EMPLOYMENT_TYPE_COLUMN),
+            skills: find(SKILLS_COLUMN),
+            attributes: header
+                .iter()
+                .enumerate()
+                .filter(|(_, h)| !known.iter().any(|k| h.trim().eq_ignore_ascii_case(k)))
+                .map(|(i, h)| (i, h.trim().to_string()))
+                .collect(),
+        })
+    }
+
+    fn parse_row(&self, line: usize, record: &csv::StringRecord) -> Result<RosterRow, RosterErr> {
+        let field = |i: usize| record.get(i).map(str::trim).unwrap_or("");
+        let optional = |i: Option<usize>| i.map(field).filter(|v| !v.is_empty());
+        let required = |i: usize, column: &'static str| match field(i) {
+            "" => Err(RosterErr { line, reason: CauseOfRosterErr::EmptyField(column) }),
+            v => Ok(v.to_string()),
+        };
+
+        let join_date = optional(self.join_date)
+            .map(|d| {
+                NaiveDate::parse_from_str(d, "%Y-%m-%d")
+                    .map_err(|_| RosterErr { line, reason: CauseOfRosterErr::InvalidJoinDate(d.to_string()) })
+            })
+            .transpose()?;
+
+        Ok(RosterRow {
+            line,
+            group: required(self.group, GROUP_COLUMN)?,
+            name: required(self.name, NAME_COLUMN)?,
+            profile: StaffProfile {
+                employee_id: optional(self.employee_id).map(str::to_string),
+                join_date,
+                employment_type: optional(self.employment_type).map(EmploymentType::parse),
+                attributes: self
+                    .attributes
+                    .iter()
+                    .filter_map(|(i, key)| optional(Some(*i)).map(|v| (key.clone(), v.to_string())))
+                    .collect::<BTreeMap<_, _>>(),
+                preferences: vec![],
+                skills: optional(self.skills)
+                    .map(|v| v.split(';').map(str::trim).filter(|s| !s.is_empty()).map(str::to_string).collect())
+                    .unwrap_or_default(),
+                contract: None,
+                availability: vec![],
+            },
+        })
+    }
+}
+
+/// Read a roster and report every row-level error without building the groups
+pub fn preview_roster_csv<R: Read>(reader: R) -> RosterPreview {
+    let mut csv_reader = csv::ReaderBuilder::new().flexible(true).from_reader(reader);
+    let mut rows = vec![];
+    let mut errors = vec![];
+
+    let columns = match csv_reader.headers().map_err(|e| RosterErr { line: 1, reason: CauseOfRosterErr::Csv(e) })
+        .and_then(Columns::from_header)
+    {
+        Ok(columns) => columns,
+        Err(e) => return RosterPreview { rows, errors: vec![e] },
+    };
+
+    let mut employee_ids = HashSet::new();
+    for record in csv_reader.records() {
+        let row = record
+            .map_err(|e| {
+                let line = e.position().map_or(0, |p| p.line() as usize);
+                RosterErr { line, reason: CauseOfRosterErr::Csv(e) }
+            })
+            .and_then(|record| {
+                let line = record.position().map_or(0, |p| p.line() as usize);
+                columns.parse_row(line, &record)
+            });
+        match row {
+            Ok(row) => {
+                if let Some(id) = &row.profile.employee_id
+                    && !employee_ids.insert(id.clone())
+                {
+                    errors.push(RosterErr { line: row.line, reason: CauseOfRosterErr::DuplicateEmployeeId(id.clone()) });
+                    continue;
+                }
+                rows.push(row)
+            }
+            Err(e) => errors.push(e),
+        }
+    }
+    RosterPreview { rows, errors }
+}
+
+impl RosterPreview {
+    /// group names with their staff count in order of first appearance
+    pub fn group_counts(&self) -> Vec<(&str, usize)> {
+        let mut counts: Vec<(&str, usize)> = vec![];
+        for row in &self.rows {
+            match counts.iter_mut().find(|(g, _)| *g == row.group) {
+                Some((_, n)) => *n += 1,
+                None => counts.push((&row.group, 1)),
+            }
+        }
+        counts
+    }
+
+    /// human readable summary of what an import would create
+    pub fn summary(&self) -> String {
+        let mut out = format!("{} staff in {} groups\n", self.rows.len(), self.group_counts().len());
+        for (group, n) in self.group_counts() {
+            out.push_str(&format!("  {}: {}\n", group, n));
+        }
+        if !self.errors.is_empty() {
+            out.push_str(&format!("{} errors\n", self.errors.len()));
+            for e in &self.errors {
+                out.push_str(&format!("  {}\n", e));
+            }
+        }
+        out
+    }
+
+    pub fn into_staff_group_list(self) -> Result<StaffGroupList, Vec<RosterErr>> {
+        if !self.errors.is_empty() {
+            return Err(self.errors);
+        }
+        let mut groups: Vec<StaffGroup> = vec![];
+        for row in self.rows {
+            let index = match groups.iter().position(|g| g.get_name() == row.group) {
+                Some(index) => index,
+                None => {
+                    groups.push(StaffGroup::new(&row.group));
+                    groups.len() - 1
+                }
+            };
+            groups[index].add_staff_with_profile(&row.name, row.profile);
+        }
+        let mut staff_group_list = StaffGroupList::new();
+        for group in groups {
+            staff_group_list.add_staff_group(group);
+        }
+        Ok(staff_group_list)
+    }
+}
+
+/// Import a roster, failing with every row-level error if any row is invalid
+pub fn import_roster_csv<R: Read>(reader: R) -> Result<StaffGroupList, Vec<RosterErr>> {
+    preview_roster_csv(reader).into_staff_group_list()
+}
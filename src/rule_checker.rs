@@ -69,6 +69,14 @@ fn fill_check_list<'a>(
 {
     let group_index = hole.group_id;
     let staff_index = hole.id;
+    // 代わりのグループのスタッフは範囲だけ確かめる。代わりにしか出てこないスタッフは未使用のまま
+    for &(group_index, staff_index) in &hole.fallback {
+        match check_list.get(group_index).map(|a| a.get(staff_index)) {
+            Some(Some(_)) => {}
+            Some(None) => return Err(RuleErr { reason: CauseOfRuleErr::StaffIdOutOfRangeErr(hole) }),
+            None => return Err(RuleErr { reason: CauseOfRuleErr::GroupIdOutOfRangeErr(hole) }),
+        }
+    }
     if let Some(a) = check_list.get_mut(group_index) {
         if let Some(b) = a.get_mut(staff_index) {
             *b = Some(());
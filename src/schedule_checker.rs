@@ -0,0 +1,372 @@
+use crate::availability::is_available;
+use crate::calendar::ShiftCalendar;
+use crate::contract::{format_hours, utilisation, Breach, SlotDurations};
+use crate::hours::{working_hours, StatutoryLimits};
+use crate::render::WEEKDAY_NAMES;
+use crate::rule_checker::CheckRule;
+use crate::shift_gen::{
+    DayDecidedShift, DecidedSchedule, Slot, Staff, StaffGroupList
+};
+use crate::skill::SkillRequirement;
+
+/// Violation found in a decided schedule
+#[derive(Debug)]
+pub struct ScheduleErr<'a> {
+    /// absolute week index
+    pub week: usize,
+    /// `None` when the violation concerns the whole week
+    pub day: Option<usize>,
+    pub reason: CauseOfScheduleErr<'a>,
+}
+
+#[derive(Debug)]
+pub enum CauseOfScheduleErr<'a> {
+    /// same staff assigned more than once to a slot
+    DoubleBookedErr { staff: &'a Staff, slot: Slot },
+    /// fewer assignments in a week than the quota
+    QuotaUnderErr { staff: &'a Staff, count: usize, min: usize },
+    /// more assignments in a week than the quota
+    QuotaOverErr { staff: &'a Staff, count: usize, max: usize },
+    CoverageUnderErr { slot: Slot, count: usize, min: usize },
+    CoverageOverErr { slot: Slot, count: usize, max: usize },
+    /// fewer staff with a skill in a slot than required
+    SkillUnderErr { slot: Slot, skill: String, count: usize, min: usize },
+    /// working days in a row beyond the limit
+    ConsecutiveDaysErr { staff: &'a Staff, days: usize, max: usize },
+    /// 法定休日 (労働基準法35条)
+    DaysOffErr { staff: &'a Staff, days_off: usize, min: usize },
+    /// 法定労働時間 (労働基準法32条) exceeded on a day
+    DailyOvertimeErr { staff: &'a Staff, minutes: u32, max: u32 },
+    /// 法定労働時間 exceeded in a week, daily overtime not counted
+    WeeklyOvertimeErr { staff: &'a Staff, minutes: u32, max: u32 },
+    /// days or hours outside the employment contract of the staff
+    ContractErr { staff: &'a Staff, breach: Breach },
+    /// staff assigned to a slot their availability keeps them off
+    UnavailableErr { staff: &'a Staff, slot: Slot },
+}
+
+impl std::fmt::Display for CauseOfScheduleErr<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CauseOfScheduleErr::DoubleBookedErr { staff, slot } => {
+                write!(f, "{} is assigned more than once in the {}", staff.name, slot.label().to_lowercase())
+            }
+            CauseOfScheduleErr::QuotaUnderErr { staff, count, min } => {
+                write!(f, "{} has {} assignments in the week, quota is at least {}", staff.name, count, min)
+            }
+            CauseOfScheduleErr::QuotaOverErr { staff, count, max } => {
+                write!(f, "{} has {} assignments in the week, quota is at most {}", staff.name, count, max)
+            }
+            CauseOfScheduleErr::CoverageUnderErr { slot, count, min } => {
+                write!(f, "{} has {} staff, needs at least {}", slot.label(), count, min)
+            }
+            CauseOfScheduleErr::CoverageOverErr { slot, count, max } => {
+                write!(f, "{} has {} staff, allows at most {}", slot.label(), count, max)
+            }
+            CauseOfScheduleErr::SkillUnderErr { slot, skill, count, min } => {
+                write!(f, "{} has {} staff with {}, needs at least {}", slot.label(), count, skill, min)
+            }
+            CauseOfScheduleErr::ConsecutiveDaysErr { staff, days, max } => {
+                write!(f, "{} works {} days in a row, limit is {}", staff.name, days, max)
+            }
+            CauseOfScheduleErr::DaysOffErr { staff, days_off, min } => {
+                write!(f, "{} has {} days off in the week, needs at least {}", staff.name, days_off, min)
+            }
+            CauseOfScheduleErr::DailyOvertimeErr { staff, minutes, max } => {
+                write!(f, "{} works {} in the day, statutory limit is {}", staff.name, format_hours(*minutes), format_hours(*max))
+            }
+            CauseOfScheduleErr::WeeklyOvertimeErr { staff, minutes, max } => {
+                write!(
+                    f,
+                    "{} works {} in the week besides daily overtime, statutory limit is {}",
+                    staff.name,
+                    format_hours(*minutes),
+                    format_hours(*max)
+                )
+            }
+            CauseOfScheduleErr::ContractErr { staff, breach } => write!(f, "{} works {}", staff.name, breach),
+            CauseOfScheduleErr::UnavailableErr { staff, slot } => {
+                write!(f, "{} is not available in the {}", staff.name, slot.label().to_lowercase())
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for ScheduleErr<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.day {
+            Some(day) => write!(f, "week {} {}: {}", self.week, WEEKDAY_NAMES[day], self.reason),
+            None => write!(f, "week {}: {}", self.week, self.reason),
+        }
+    }
+}
+
+fn works_on(day_shift: &DayDecidedShift<'_>, staff: &Staff) -> bool {
+    Slot::ALL
+        .iter()
+        .any(|slot| day_shift.slot(*slot).iter().any(|s| std::ptr::eq(*s, staff)))
+}
+
+fn staff_iter(
+    staff_group_list: &StaffGroupList,
+    group_id: Option<usize>,
+) -> impl Iterator<Item = &Staff> {
+    staff_group_list
+        .0
+        .iter()
+        .filter(move |g| group_id.is_none_or(|id| g.get_group_id() == id))
+        .flat_map(|g| g.iter())
+}
+
+/// 同じ時間帯に同じスタッフが複数回割り当てられていないかを検査するチェッカー
+pub struct DoubleBookingChecker();
+
+impl<'a> CheckRule<'a, DecidedSchedule<'a>> for DoubleBookingChecker {
+    type Error = Vec<ScheduleErr<'a>>;
+
+    fn check_rule(&self, data: &'a DecidedSchedule<'a>) -> Result<(), Self::Error> {
+        let mut errors = vec![];
+        for (week, day, day_shift) in data.days() {
+            for slot in Slot::ALL {
+                let staff_list = day_shift.slot(slot);
+                for (i, staff) in staff_list.iter().enumerate() {
+                    let first = staff_list.iter().position(|s| std::ptr::eq(*s, *staff)) == Some(i);
+                    let repeated = staff_list[i + 1..].iter().any(|s| std::ptr::eq(*s, *staff));
+                    if first && repeated {
+                        errors.push(ScheduleErr {
+                            week,
+                            day: Some(day),
+                            reason: CauseOfScheduleErr::DoubleBookedErr { staff, slot },
+                        });
+                    }
+                }
+            }
+        }
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+}
+
+/// 週あたりの割り当て回数がスタッフごとの上限・下限に収まっているかを検査するチェッカー
+pub struct QuotaChecker<'a> {
+    pub staff_group_list: &'a StaffGroupList,
+    /// every group when `None`
+    pub group_id: Option<usize>,
+    pub min_per_week: Option<usize>,
+    pub max_per_week: Option<usize>,
+}
+
+impl<'a> CheckRule<'a, DecidedSchedule<'a>> for QuotaChecker<'a> {
+    type Error = Vec<ScheduleErr<'a>>;
+
+    fn check_rule(&self, data: &'a DecidedSchedule<'a>) -> Result<(), Self::Error> {
+        let mut errors = vec![];
+        for (i, week_shift) in data.weeks.iter().enumerate() {
+            for staff in staff_iter(self.staff_group_list, self.group_id) {
+                let count = week_shift
+                    .0
+                    .iter()
+                    .flat_map(|day| Slot::ALL.map(|slot| day.slot(slot)))
+                    .map(|list| list.iter().filter(|s| std::ptr::eq(**s, staff)).count())
+                    .sum();
+                let reason = match (self.min_per_week, self.max_per_week) {
+                    (Some(min), _) if count < min => CauseOfScheduleErr::QuotaUnderErr { staff, count, min },
+                    (_, Some(max)) if count > max => CauseOfScheduleErr::QuotaOverErr { staff, count, max },
+                    _ => continue,
+                };
+                errors.push(ScheduleErr { week: data.week_delta + i, day: None, reason });
+            }
+        }
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+}
+
+/// 曜日・時間帯ごとの必要人数を検査するチェッカー
+pub struct CoverageChecker {
+    /// weekdays to check, 0 is the first day of a rule week
+    pub days: Vec<usize>,
+    pub slots: Vec<Slot>,
+    pub min: Option<usize>,
+    pub max: Option<usize>,
+}
+
+impl<'a> CheckRule<'a, DecidedSchedule<'a>> for CoverageChecker {
+    type Error = Vec<ScheduleErr<'a>>;
+
+    fn check_rule(&self, data: &'a DecidedSchedule<'a>) -> Result<(), Self::Error> {
+        let mut errors = vec![];
+        for (week, day, day_shift) in data.days().filter(|(_, day, _)| self.days.contains(day)) {
+            for slot in &self.slots {
+                let count = day_shift.slot(*slot).len();
+                let reason = match (self.min, self.max) {
+                    (Some(min), _) if count < min => CauseOfScheduleErr::CoverageUnderErr { slot: *slot, count, min },
+                    (_, Some(max)) if count > max => CauseOfScheduleErr::CoverageOverErr { slot: *slot, count, max },
+                    _ => continue,
+                };
+                errors.push(ScheduleErr { week, day: Some(day), reason });
+            }
+        }
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+}
+
+/// 曜日・時間帯ごとに必要な資格を持つスタッフの人数を検査するチェッカー
+///
+/// nobody working in a slot needs no qualified staff either.
+pub struct SkillChecker {
+    pub requirement: SkillRequirement,
+}
+
+impl<'a> CheckRule<'a, DecidedSchedule<'a>> for SkillChecker {
+    type Error = Vec<ScheduleErr<'a>>;
+
+    fn check_rule(&self, data: &'a DecidedSchedule<'a>) -> Result<(), Self::Error> {
+        let mut errors = vec![];
+        for (week, day, day_shift) in data.days() {
+            for slot in Slot::ALL.into_iter().filter(|slot| self.requirement.applies(day, *slot)) {
+                let staff = day_shift.slot(slot);
+                let count = self.requirement.count(staff);
+                if !staff.is_empty() && count < self.requirement.min {
+                    let skill = self.requirement.skill.clone();
+                    let reason = CauseOfScheduleErr::SkillUnderErr { slot, skill, count, min: self.requirement.min };
+                    errors.push(ScheduleErr { week, day: Some(day), reason });
+                }
+            }
+        }
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+}
+
+/// 連続勤務日数と週あたりの休日数を検査するチェッカー
+pub struct LabourLawChecker<'a> {
+    pub staff_group_list: &'a StaffGroupList,
+    pub max_consecutive_days: Option<usize>,
+    pub min_days_off_per_week: Option<usize>,
+}
+
+impl<'a> CheckRule<'a, DecidedSchedule<'a>> for LabourLawChecker<'a> {
+    type Error = Vec<ScheduleErr<'a>>;
+
+    fn check_rule(&self, data: &'a DecidedSchedule<'a>) -> Result<(), Self::Error> {
+        let mut errors = vec![];
+        for staff in staff_iter(self.staff_group_list, None) {
+            if let Some(max) = self.max_consecutive_days {
+                let mut run = 0;
+                for (week, day, day_shift) in data.days() {
+                    run = if works_on(day_shift, staff) { run + 1 } else { 0 };
+                    // reported once per run, on the first day beyond the limit
+                    if run == max + 1 {
+                        errors.push(ScheduleErr {
+                            week,
+                            day: Some(day),
+                            reason: CauseOfScheduleErr::ConsecutiveDaysErr { staff, days: run, max },
+                        });
+                    }
+                }
+            }
+            if let Some(min) = self.min_days_off_per_week {
+                for (i, week_shift) in data.weeks.iter().enumerate() {
+                    let days_off = week_shift.0.iter().filter(|day| !works_on(day, staff)).count();
+                    if days_off < min {
+                        errors.push(ScheduleErr {
+                            week: data.week_delta + i,
+                            day: None,
+                            reason: CauseOfScheduleErr::DaysOffErr { staff, days_off, min },
+                        });
+                    }
+                }
+            }
+        }
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+}
+
+/// 雇用契約の週・月あたりの勤務日数と勤務時間を検査するチェッカー
+///
+/// months are only checked with a calendar.
+pub struct ContractChecker<'a> {
+    pub staff_group_list: &'a StaffGroupList,
+    pub durations: SlotDurations,
+    pub calendar: Option<ShiftCalendar>,
+}
+
+impl<'a> CheckRule<'a, DecidedSchedule<'a>> for ContractChecker<'a> {
+    type Error = Vec<ScheduleErr<'a>>;
+
+    fn check_rule(&self, data: &'a DecidedSchedule<'a>) -> Result<(), Self::Error> {
+        let mut errors = vec![];
+        for u in utilisation(data, self.staff_group_list, &self.durations, self.calendar.as_ref()) {
+            for breach in u.breaches {
+                let week = u.periods.iter().find(|(p, _)| *p == breach.period).map_or(data.week_delta, |(_, usage)| usage.first_week);
+                errors.push(ScheduleErr { week, day: None, reason: CauseOfScheduleErr::ContractErr { staff: u.staff, breach } });
+            }
+        }
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+}
+
+/// 勤務できない曜日・時間帯への割り当てを検査するチェッカー
+///
+/// availability dates are only in force with a calendar.
+pub struct AvailabilityChecker {
+    pub calendar: Option<ShiftCalendar>,
+}
+
+impl<'a> CheckRule<'a, DecidedSchedule<'a>> for AvailabilityChecker {
+    type Error = Vec<ScheduleErr<'a>>;
+
+    fn check_rule(&self, data: &'a DecidedSchedule<'a>) -> Result<(), Self::Error> {
+        let mut errors = vec![];
+        for (week, day, day_shift) in data.days() {
+            let date = self.calendar.map(|c| c.date_of(week, day));
+            for slot in Slot::ALL {
+                for staff in day_shift.slot(slot).iter().filter(|s| !is_available(s, day, slot, date)) {
+                    errors.push(ScheduleErr { week, day: Some(day), reason: CauseOfScheduleErr::UnavailableErr { staff, slot } });
+                }
+            }
+        }
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+}
+
+/// 法定労働時間を超える時間外労働を検査するチェッカー
+pub struct OvertimeChecker<'a> {
+    pub staff_group_list: &'a StaffGroupList,
+    pub durations: SlotDurations,
+    pub limits: StatutoryLimits,
+}
+
+impl<'a> CheckRule<'a, DecidedSchedule<'a>> for OvertimeChecker<'a> {
+    type Error = Vec<ScheduleErr<'a>>;
+
+    fn check_rule(&self, data: &'a DecidedSchedule<'a>) -> Result<(), Self::Error> {
+        let mut errors = vec![];
+        for report in working_hours(data, self.staff_group_list, &self.durations, &self.limits, None) {
+            let staff = report.staff;
+            for day in report.days.iter().filter(|d| d.overtime > 0) {
+                let reason = CauseOfScheduleErr::DailyOvertimeErr { staff, minutes: day.minutes, max: self.limits.daily_minutes };
+                errors.push(ScheduleErr { week: day.week, day: Some(day.day), reason });
+            }
+            for week in report.weeks.iter().filter(|w| w.weekly_overtime > 0) {
+                let minutes = week.minutes - week.daily_overtime;
+                let reason = CauseOfScheduleErr::WeeklyOvertimeErr { staff, minutes, max: self.limits.weekly_minutes };
+                errors.push(ScheduleErr { week: week.week, day: None, reason });
+            }
+        }
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+}
+
+/// Run every checker and collect all of their violations
+///
+/// unlike `verify`, this does not stop at the first failing checker.
+pub fn audit<'a>(
+    data: &'a DecidedSchedule<'a>,
+    rules: &[&dyn CheckRule<'a, DecidedSchedule<'a>, Error = Vec<ScheduleErr<'a>>>],
+) -> Vec<ScheduleErr<'a>>
+{
+    rules
+        .iter()
+        .filter_map(|rule| rule.check_rule(data).err())
+        .flatten()
+        .collect()
+}
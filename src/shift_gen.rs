@@ -1,5 +1,13 @@
+use std::collections::{BTreeMap, BTreeSet};
 use std::marker::PhantomData;
 
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::availability::AvailabilityPattern;
+use crate::contract::Contract;
+use crate::preference::StaffPreference;
+
 /// State
 pub struct Unconfirmed;
 /// State
@@ -15,10 +23,14 @@ pub struct Ready;
 pub struct ShiftHoll<'a, State> {
     pub group_id: usize,
     pub id: usize,
+    /// groups drawn from in order when `group_id` is short, with the id to
+    /// rotate from in each
+    pub fallback: Vec<(usize, usize)>,
     staff: Option<&'a Staff>,
     _state: PhantomData<State>
 }
 
+#[derive(Clone)]
 pub struct StaffGroupList(
     pub Vec<StaffGroup>
 );
@@ -28,8 +40,15 @@ impl StaffGroupList {
         Self(Vec::new())
     }
 
+    pub fn get_staff(&self, group_id: usize, staff_id: usize) -> Option<&Staff> {
+        self.0.get(group_id).and_then(|g| g.staff_list.get(staff_id))
+    }
+
     pub fn add_staff_group(&mut self, mut staff_group: StaffGroup) {
         staff_group.group_id = self.0.len();
+        for staff in &mut staff_group.staff_list {
+            staff.group_id = staff_group.group_id;
+        }
         self.0.push(staff_group);
     }
 }
@@ -53,7 +72,19 @@ trait GenDecided {
 
 impl<'a> ShiftHoll<'a, Incomplete> {
     pub fn new(group_id: usize, id: usize) -> Self{
-        Self { group_id , id, staff: None, _state: PhantomData }
+        Self { group_id , id, fallback: vec![], staff: None, _state: PhantomData }
+    }
+
+    pub fn with_fallback(mut self, fallback: Vec<(usize, usize)>) -> Self {
+        self.fallback = fallback;
+        self
+    }
+}
+
+impl<State> ShiftHoll<'_, State> {
+    /// `(group_id, id)` of the hole, then of its fallbacks in order
+    pub fn groups(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        std::iter::once((self.group_id, self.id)).chain(self.fallback.iter().copied())
     }
 }
 
@@ -70,6 +101,7 @@ impl<'a> FillHoll<'a> for ShiftHoll<'a, Incomplete> {
         ShiftHoll {
             group_id: self.group_id,
             id: self.id, 
+            fallback: self.fallback,
             staff: Some(staff),
             _state: PhantomData 
         }
@@ -84,6 +116,37 @@ impl<'a> GenDecided for ShiftHoll<'a, Ready> {
     }
 }
 
+/// Time slot of a day
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Slot {
+    Morning,
+    Afternoon,
+}
+
+impl Slot {
+    pub const ALL: [Slot; 2] = [Slot::Morning, Slot::Afternoon];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Slot::Morning => "Morning",
+            Slot::Afternoon => "Afternoon",
+        }
+    }
+}
+
+impl std::str::FromStr for Slot {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "morning" | "am" | "m" | "午前" => Ok(Slot::Morning),
+            "afternoon" | "pm" | "a" | "午後" => Ok(Slot::Afternoon),
+            _ => Err(s.to_string()),
+        }
+    }
+}
+
 /// Rule Data
 ///
 /// shift a day
@@ -124,6 +187,23 @@ pub struct DayDecidedShift<'a> {
     pub shift_afternoon: Vec<&'a Staff>,
 }
 
+impl<'a> DayDecidedShift<'a> {
+    /// staff assigned to the given slot
+    pub fn slot(&self, slot: Slot) -> &[&'a Staff] {
+        match slot {
+            Slot::Morning => &self.shift_morning,
+            Slot::Afternoon => &self.shift_afternoon,
+        }
+    }
+
+    pub fn slot_mut(&mut self, slot: Slot) -> &mut Vec<&'a Staff> {
+        match slot {
+            Slot::Morning => &mut self.shift_morning,
+            Slot::Afternoon => &mut self.shift_afternoon,
+        }
+    }
+}
+
 impl<'a> GenDecided for  DayRule<'a, Ready> {
     type Output = DayDecidedShift<'a>;
 
@@ -160,6 +240,7 @@ impl<'a> FillHoll<'a> for WeekRule<'a, Incomplete> {
     }
 }
 
+#[derive(Debug, Clone)]
 pub struct WeekDecidedShift<'a>(
     pub [DayDecidedShift<'a>; 7]
 );
@@ -213,26 +294,151 @@ pub fn gen_shift<'a>(
         .into_boxed_slice()
 }
 
+/// Decided shift of consecutive weeks, the first one being week `week_delta`
+#[derive(Debug, Clone)]
+pub struct DecidedSchedule<'a> {
+    pub week_delta: usize,
+    pub weeks: Box<[WeekDecidedShift<'a>]>,
+}
+
+impl<'a> DecidedSchedule<'a> {
+    pub fn new(week_delta: usize, weeks: Box<[WeekDecidedShift<'a>]>) -> Self {
+        Self { week_delta, weeks }
+    }
+
+    pub fn generate(
+        week_rule_table: &WeekRuleTable<'a, Incomplete>,
+        staff_group_list: &'a StaffGroupList,
+        week_delta: usize,
+        week_gen_range: usize) -> Self
+    {
+        Self::new(week_delta, gen_shift(week_rule_table, staff_group_list, week_delta, week_gen_range))
+    }
+
+    /// decided shift of an absolute week index
+    pub fn week(&self, week: usize) -> Option<&WeekDecidedShift<'a>> {
+        week.checked_sub(self.week_delta).and_then(|i| self.weeks.get(i))
+    }
+
+    /// (absolute week index, day, decided shift) of every day
+    pub fn days(&self) -> impl Iterator<Item = (usize, usize, &DayDecidedShift<'a>)> {
+        self.weeks.iter().enumerate().flat_map(move |(i, week_shift)| {
+            week_shift.0.iter().enumerate().map(move |(day, day_shift)| (self.week_delta + i, day, day_shift))
+        })
+    }
+}
+
+/// Manual replacement of a generated assignment
+///
+/// `week` is the absolute week index (`week_delta + i`), so an override stays
+/// attached to the same week whatever range is generated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShiftOverride {
+    pub week: usize,
+    pub day: usize,
+    pub slot: Slot,
+    pub hole: usize,
+    pub group_id: usize,
+    pub staff_id: usize,
+}
+
+/// Replace generated assignments by overrides
+///
+/// overrides outside of the generated range or pointing to a missing hole
+/// or staff are ignored.
+pub fn apply_overrides<'a>(
+    shift: &mut [WeekDecidedShift<'a>],
+    staff_group_list: &'a StaffGroupList,
+    week_delta: usize,
+    overrides: &[ShiftOverride])
+{
+    for o in overrides {
+        let Some(week_shift) = o.week.checked_sub(week_delta).and_then(|i| shift.get_mut(i)) else {
+            continue;
+        };
+        let Some(staff) = staff_group_list.get_staff(o.group_id, o.staff_id) else {
+            continue;
+        };
+        if let Some(hole) = week_shift
+            .0
+            .get_mut(o.day)
+            .and_then(|day| day.slot_mut(o.slot).get_mut(o.hole))
+        {
+            *hole = staff;
+        }
+    }
+}
+
 // ========= names ===========
 
+/// Kind of employment
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EmploymentType {
+    FullTime,
+    PartTime,
+    Other(String),
+}
+
+impl EmploymentType {
+    pub fn parse(s: &str) -> Self {
+        match s.trim().to_lowercase().replace(['_', ' '], "-").as_str() {
+            "full-time" | "fulltime" | "正社員" => EmploymentType::FullTime,
+            "part-time" | "parttime" | "パート" | "アルバイト" => EmploymentType::PartTime,
+            _ => EmploymentType::Other(s.trim().to_string()),
+        }
+    }
+}
+
 /// Staff Info
-#[derive(Debug)]
+///
+/// attributes imported from an HR roster
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StaffProfile {
+    /// stable employee id
+    pub employee_id: Option<String>,
+    pub join_date: Option<NaiveDate>,
+    pub employment_type: Option<EmploymentType>,
+    /// free-form attributes
+    pub attributes: BTreeMap<String, String>,
+    pub preferences: Vec<StaffPreference>,
+    /// qualifications such as `keyholder`
+    pub skills: BTreeSet<String>,
+    /// day and hour limits
+    pub contract: Option<Contract>,
+    /// weekdays and slots the staff can or cannot work
+    pub availability: Vec<AvailabilityPattern>,
+}
+
+/// Staff Info
+#[derive(Debug, Clone)]
 pub struct Staff{
     pub name: String,
     id: usize,
+    group_id: usize,
+    pub profile: StaffProfile,
 }
 
 impl Staff {
     pub fn new(name: &str, ) -> Self {
-        Self { name: name.to_string(), id: 0, }
+        Self { name: name.to_string(), id: 0, group_id: 0, profile: StaffProfile::default(), }
     }
 
     pub fn get_id(&self) -> usize {
         self.id
     }
+
+    pub fn get_group_id(&self) -> usize {
+        self.group_id
+    }
+
+    pub fn has_skill(&self, skill: &str) -> bool {
+        self.profile.skills.contains(skill)
+    }
 }
 
 /// Staff Info
+#[derive(Clone)]
 pub struct StaffGroup {
     name: String,
     group_id: usize,
@@ -249,13 +455,33 @@ impl StaffGroup {
     }
 
     pub fn add_staff(&mut self, name:&str) {
+        self.add_staff_with_profile(name, StaffProfile::default());
+    }
+
+    pub fn add_staff_with_profile(&mut self, name:&str, profile: StaffProfile) {
         self.staff_list.push(
-            Staff { name: name.to_string(), id: self.staff_list.len(), });
+            Staff { name: name.to_string(), id: self.staff_list.len(), group_id: self.group_id, profile, });
     }
 
     pub fn len(&self) -> usize {
         self.staff_list.len()
     }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_group_id(&self) -> usize {
+        self.group_id
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Staff> {
+        self.staff_list.iter()
+    }
+
+    pub fn staff_mut(&mut self, index: usize) -> Option<&mut Staff> {
+        self.staff_list.get_mut(index)
+    }
 }
 
 impl StaffGroup{
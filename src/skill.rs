@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+
+use crate::shift_gen::{
+    Slot, Staff, StaffGroupList, WeekDecidedShift
+};
+
+/// Qualified staff needed in some slots, e.g. a keyholder every morning
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkillRequirement {
+    pub skill: String,
+    /// weekdays, 0 is the first day of a rule week
+    pub days: Vec<usize>,
+    pub slots: Vec<Slot>,
+    /// staff with the skill needed in each of these slots
+    pub min: usize,
+}
+
+impl SkillRequirement {
+    pub fn applies(&self, day: usize, slot: Slot) -> bool {
+        self.days.contains(&day) && self.slots.contains(&slot)
+    }
+
+    /// staff of a slot having the skill
+    pub fn count(&self, staff: &[&Staff]) -> usize {
+        staff.iter().filter(|s| s.has_skill(&self.skill)).count()
+    }
+
+    pub fn is_met(&self, staff: &[&Staff]) -> bool {
+        self.count(staff) >= self.min
+    }
+}
+
+/// Hand holes to qualified staff where rotation left a requirement unmet
+///
+/// rotation picks staff regardless of their skills. In every slot short of
+/// qualified staff, the holes of staff without the skill are taken in order
+/// and given to the next staff of the same group in rotation order who has
+/// it and is not in the slot yet, skipping the unqualified ones. A hole is
+/// only moved when every other requirement the slot met stays met. Returns
+/// the number of holes given to someone else.
+pub fn assign_qualified<'a>(
+    weeks: &mut [WeekDecidedShift<'a>],
+    staff_group_list: &'a StaffGroupList,
+    requirements: &[SkillRequirement],
+) -> usize {
+    let mut changed = 0;
+    for week_shift in weeks.iter_mut() {
+        for (day, day_shift) in week_shift.0.iter_mut().enumerate() {
+            for slot in Slot::ALL {
+                let applying: Vec<&SkillRequirement> = requirements.iter().filter(|r| r.applies(day, slot)).collect();
+                let staff_list = day_shift.slot_mut(slot);
+                for requirement in &applying {
+                    for hole in 0..staff_list.len() {
+                        if requirement.is_met(staff_list) {
+                            break;
+                        }
+                        let holder = staff_list[hole];
+                        if holder.has_skill(&requirement.skill) {
+                            continue;
+                        }
+                        let Some(group) = staff_group_list.0.get(holder.get_group_id()) else {
+                            continue;
+                        };
+                        let met_before: Vec<bool> = applying.iter().map(|r| r.is_met(staff_list)).collect();
+                        let replacement = (1..group.len())
+                            .map(|step| group.pickup_staff((holder.get_id() + step) % group.len()))
+                            .filter(|s| s.has_skill(&requirement.skill))
+                            .filter(|s| !staff_list.iter().any(|t| std::ptr::eq(*t, *s)))
+                            .find(|s| {
+                                let mut trial = staff_list.clone();
+                                trial[hole] = s;
+                                applying.iter().zip(&met_before).all(|(r, met)| !met || r.is_met(&trial))
+                            });
+                        if let Some(staff) = replacement {
+                            staff_list[hole] = staff;
+                            changed += 1;
+                        }
+                    }
+                }
+            }
+        }
+    }
+    changed
+}
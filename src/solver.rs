@@ -0,0 +1,742 @@
+use std::collections::HashMap;
+
+use chrono::Datelike;
+use serde::{Deserialize, Serialize};
+
+use crate::availability::{assign_available, is_available};
+use crate::calendar::ShiftCalendar;
+use crate::contract::{Contract, Measure, Period, SlotDurations};
+use crate::hours::StatutoryLimits;
+use crate::preference::preference_weight;
+use crate::render::WEEKDAY_NAMES;
+use crate::shift_gen::{
+    DayDecidedShift, DecidedSchedule, Incomplete, Slot, StaffGroupList, WeekDecidedShift, WeekRuleTable
+};
+use crate::skill::{assign_qualified, SkillRequirement};
+use crate::synth::{quota_bounds, StaffQuota};
+
+/// A staff who cannot work on a day, or in one slot of it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Unavailability {
+    pub group_id: usize,
+    pub staff_id: usize,
+    /// absolute week index
+    pub week: usize,
+    pub day: usize,
+    /// the whole day when `None`
+    pub slot: Option<Slot>,
+}
+
+/// A staff away over a range of days, read from `[[absence]]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Absence {
+    pub group_id: usize,
+    pub staff_id: usize,
+    /// absolute day index, `week * 7 + day`
+    pub first_day: usize,
+    /// absolute day index, inclusive
+    pub last_day: usize,
+    /// every slot when `None`
+    pub slot: Option<Slot>,
+}
+
+impl Absence {
+    pub fn covers(&self, week: usize, day: usize, slot: Slot) -> bool {
+        (self.first_day..=self.last_day).contains(&(week * 7 + day)) && self.slot.is_none_or(|s| s == slot)
+    }
+
+    /// one unavailability per day of the absence
+    pub fn unavailability(&self) -> impl Iterator<Item = Unavailability> + '_ {
+        (self.first_day..=self.last_day).map(|d| Unavailability {
+            group_id: self.group_id,
+            staff_id: self.staff_id,
+            week: d / 7,
+            day: d % 7,
+            slot: self.slot,
+        })
+    }
+}
+
+/// Wish of a staff to work (positive weight) or not (negative weight)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Preference {
+    pub group_id: usize,
+    pub staff_id: usize,
+    /// absolute week index
+    pub week: usize,
+    pub day: usize,
+    /// the whole day when `None`
+    pub slot: Option<Slot>,
+    pub weight: i64,
+}
+
+/// Constraints and search settings of `solve_schedule`
+///
+/// the holes of the rule table and the limits are hard constraints,
+/// the spread of the work and the preferences are soft ones.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SolverConfig {
+    pub quotas: Vec<StaffQuota>,
+    pub max_consecutive_days: Option<usize>,
+    pub min_days_off_per_week: Option<usize>,
+    pub unavailable: Vec<Unavailability>,
+    /// added to the preferences of the staff profiles
+    pub preferences: Vec<Preference>,
+    /// calendar of the weeks, the date preferences of the staff profiles
+    /// apply only with one and their availability is in force every day
+    /// without one
+    pub calendar: Option<ShiftCalendar>,
+    /// cost of an uneven spread of the work in a group
+    pub fairness_weight: i64,
+    /// nodes the backtracking search may visit
+    pub node_limit: usize,
+    /// moves tried by the local search
+    pub iterations: usize,
+    pub seed: u64,
+    /// minutes of each slot, for the hour limits
+    pub slot_durations: SlotDurations,
+    /// daily and weekly minutes no staff may work over
+    pub statutory_limits: Option<StatutoryLimits>,
+    /// keep the maximums of the contracts of the staff profiles, the monthly
+    /// ones need `calendar`
+    pub contract_maximums: bool,
+}
+
+impl Default for SolverConfig {
+    fn default() -> Self {
+        Self {
+            quotas: vec![],
+            max_consecutive_days: None,
+            min_days_off_per_week: None,
+            unavailable: vec![],
+            preferences: vec![],
+            calendar: None,
+            fairness_weight: 1,
+            node_limit: 1_000_000,
+            iterations: 10_000,
+            seed: 1,
+            slot_durations: SlotDurations::default(),
+            statutory_limits: None,
+            contract_maximums: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolveErr {
+    pub reason: CauseOfSolveErr,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CauseOfSolveErr {
+    EmptyRuleTable,
+    GroupIdOutOfRange { group_id: usize },
+    EmptyGroup { group_id: usize },
+    /// no schedule meets the hard constraints, the search got no further than this hole
+    Infeasible { week: usize, day: usize, slot: Slot },
+    /// the search visited `node_limit` nodes without an answer
+    SearchLimit { week: usize, day: usize, slot: Slot },
+}
+
+impl std::fmt::Display for SolveErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.reason {
+            CauseOfSolveErr::EmptyRuleTable => write!(f, "the rule table is empty"),
+            CauseOfSolveErr::GroupIdOutOfRange { group_id } => write!(f, "group id {} is out of range", group_id),
+            CauseOfSolveErr::EmptyGroup { group_id } => write!(f, "group {} has no staff", group_id),
+            CauseOfSolveErr::Infeasible { week, day, slot } => write!(
+                f,
+                "no schedule meets the constraints, nobody can take week {} {} {}",
+                week, WEEKDAY_NAMES[*day], slot.label()
+            ),
+            CauseOfSolveErr::SearchLimit { week, day, slot } => write!(
+                f,
+                "search limit reached, the search got stuck around week {} {} {}",
+                week, WEEKDAY_NAMES[*day], slot.label()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SolveErr {}
+
+/// a hole of the horizon
+struct Hole {
+    /// week index in the horizon
+    week: usize,
+    day: usize,
+    slot: usize,
+    /// group of the rule, then its fallbacks in order
+    groups: Vec<usize>,
+    /// staff `gen_shift` would pick in each group, used to break ties
+    rotation_staff: Vec<usize>,
+    /// `(group_id, staff_id)` of the groups of the hole in this slot of the
+    /// published schedule, see `repair_schedule`
+    published: Vec<(usize, usize)>,
+    /// preference weight of each staff for the hole, `[group index][staff]`
+    preferences: Vec<Vec<i64>>,
+    /// whether the availability of each staff lets them take the hole,
+    /// `[group index][staff]`
+    available: Vec<Vec<bool>>,
+}
+
+impl Hole {
+    /// index of a group among the groups of the hole
+    fn rank(&self, group_id: usize) -> usize {
+        self.groups.iter().position(|g| *g == group_id).expect("staff come from the groups of the hole")
+    }
+}
+
+/// cost of a hole not keeping its published staff, above any other cost
+const CHANGE_COST: i64 = 1 << 32;
+
+/// cost of each step down the fallback groups of a hole, above fairness and
+/// preferences
+const FALLBACK_COST: i64 = 1 << 24;
+
+/// bookkeeping of one staff
+#[derive(Clone)]
+struct StaffState {
+    /// `[horizon day][slot]`
+    booked: Vec<[bool; 2]>,
+    week_count: Vec<usize>,
+    total: usize,
+    min_per_week: usize,
+    max_per_week: Option<usize>,
+    /// the contract whose maximums are kept
+    contract: Option<Contract>,
+}
+
+impl StaffState {
+    fn works_on(&self, day: usize) -> bool {
+        self.booked.get(day).is_some_and(|b| b[0] || b[1])
+    }
+
+    fn working_days(&self, week: usize) -> usize {
+        (week * 7..week * 7 + 7).filter(|d| self.works_on(*d)).count()
+    }
+
+    /// days worked and minutes over some horizon days
+    fn usage(&self, days: impl IntoIterator<Item = usize>, durations: &SlotDurations) -> (u32, u32) {
+        days.into_iter().filter_map(|d| self.booked.get(d)).fold((0, 0), |(worked, minutes), booked| {
+            let day_minutes: u32 = Slot::ALL.iter().zip(booked).filter(|(_, b)| **b).map(|(s, _)| durations.minutes(*s)).sum();
+            (worked + booked.iter().any(|b| *b) as u32, minutes + day_minutes)
+        })
+    }
+
+    /// length of the working run through `day` if it were worked
+    fn run_through(&self, day: usize) -> usize {
+        let before = (0..day).rev().take_while(|d| self.works_on(*d)).count();
+        let after = (day + 1..self.booked.len()).take_while(|d| self.works_on(*d)).count();
+        before + 1 + after
+    }
+}
+
+struct Search<'c> {
+    config: &'c SolverConfig,
+    week_delta: usize,
+    holes: Vec<Hole>,
+    /// `[group][staff]`
+    staff: Vec<Vec<StaffState>>,
+    /// `(group_id, staff_id)` of each hole
+    assignment: Vec<Option<(usize, usize)>>,
+    /// holes of each group left after a hole in its week, `[hole]`
+    remaining_in_week: Vec<usize>,
+    /// the holes keep their published staff when they can
+    repairing: bool,
+    nodes: usize,
+    deepest: usize,
+}
+
+impl<'c> Search<'c> {
+    fn unavailable(&self, hole: &Hole, (group_id, staff_id): (usize, usize)) -> bool {
+        !hole.available[hole.rank(group_id)][staff_id]
+            || self.config.unavailable.iter().any(|u| {
+                (u.group_id, u.staff_id, u.week, u.day) == (group_id, staff_id, self.week_delta + hole.week, hole.day)
+                    && u.slot.is_none_or(|s| s == Slot::ALL[hole.slot])
+            })
+    }
+
+    fn preference(&self, hole: &Hole, (group_id, staff_id): (usize, usize)) -> i64 {
+        hole.preferences[hole.rank(group_id)][staff_id]
+    }
+
+    /// every hard constraint the assignment could break, the hole being free
+    fn can_assign(&self, h: usize, pick: (usize, usize)) -> bool {
+        let hole = &self.holes[h];
+        let state = &self.staff[pick.0][pick.1];
+        let day = hole.week * 7 + hole.day;
+        if state.booked[day][hole.slot] || self.unavailable(hole, pick) {
+            return false;
+        }
+        if state.max_per_week.is_some_and(|max| state.week_count[hole.week] >= max) {
+            return false;
+        }
+        let durations = &self.config.slot_durations;
+        let minutes = durations.minutes(Slot::ALL[hole.slot]);
+        let week_days = hole.week * 7..hole.week * 7 + 7;
+        if let Some(limits) = self.config.statutory_limits {
+            let (_, day_minutes) = state.usage(day..day + 1, durations);
+            let (_, week_minutes) = state.usage(week_days.clone(), durations);
+            if day_minutes + minutes > limits.daily_minutes || week_minutes + minutes > limits.weekly_minutes {
+                return false;
+            }
+        }
+        if let Some(contract) = &state.contract {
+            let absolute = self.week_delta + hole.week;
+            let mut periods = vec![(Period::Week(absolute), week_days.collect::<Vec<_>>())];
+            if let Some(calendar) = self.config.calendar {
+                let month_of = |d: usize| {
+                    let date = calendar.date_of(self.week_delta + d / 7, d % 7);
+                    Period::Month { year: date.year(), month: date.month() }
+                };
+                let month = month_of(day);
+                let days = (day.saturating_sub(31)..(day + 32).min(state.booked.len())).filter(|d| month_of(*d) == month);
+                periods.push((month, days.collect()));
+            }
+            let new_day = !state.works_on(day) as u32;
+            for (period, days) in periods {
+                let (worked, used) = state.usage(days, durations);
+                let over = |measure, value| contract.bounds(period, measure).1.is_some_and(|max| value > max);
+                if over(Measure::Days, worked + new_day) || over(Measure::Hours, used + minutes) {
+                    return false;
+                }
+            }
+        }
+        if !state.works_on(day) {
+            if let Some(min_off) = self.config.min_days_off_per_week
+                && state.working_days(hole.week) + 1 > 7usize.saturating_sub(min_off)
+            {
+                return false;
+            }
+            if self.config.max_consecutive_days.is_some_and(|max| state.run_through(day) > max) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn assign(&mut self, h: usize, pick: (usize, usize)) {
+        let hole = &self.holes[h];
+        let state = &mut self.staff[pick.0][pick.1];
+        state.booked[hole.week * 7 + hole.day][hole.slot] = true;
+        state.week_count[hole.week] += 1;
+        state.total += 1;
+        self.assignment[h] = Some(pick);
+    }
+
+    fn unassign(&mut self, h: usize) -> Option<(usize, usize)> {
+        let pick = self.assignment[h].take()?;
+        let hole = &self.holes[h];
+        let state = &mut self.staff[pick.0][pick.1];
+        state.booked[hole.week * 7 + hole.day][hole.slot] = false;
+        state.week_count[hole.week] -= 1;
+        state.total -= 1;
+        Some(pick)
+    }
+
+    /// the staff of the group still short of their weekly minimum fit in the holes left
+    fn minimum_reachable(&self, h: usize) -> bool {
+        let hole = &self.holes[h];
+        let short: usize = self.staff[hole.groups[0]]
+            .iter()
+            .map(|s| s.min_per_week.saturating_sub(s.week_count[hole.week]))
+            .sum();
+        short <= self.remaining_in_week[h]
+    }
+
+    /// cost of a staff in a hole that does not depend on the other holes
+    fn hole_cost(&self, h: usize, pick: (usize, usize)) -> i64 {
+        let hole = &self.holes[h];
+        let changed = self.repairing && !hole.published.contains(&pick);
+        CHANGE_COST * changed as i64 + FALLBACK_COST * hole.rank(pick.0) as i64 - self.preference(hole, pick)
+    }
+
+    fn cost(&self, h: usize, pick: (usize, usize)) -> i64 {
+        let total = self.staff[pick.0][pick.1].total as i64;
+        self.config.fairness_weight * total + self.hole_cost(h, pick)
+    }
+
+    fn candidates(&self, h: usize) -> Vec<(usize, usize)> {
+        let hole = &self.holes[h];
+        let mut candidates: Vec<((usize, usize), usize)> = vec![];
+        for (rank, &group_id) in hole.groups.iter().enumerate() {
+            let len = self.staff[group_id].len();
+            let rotation_staff = hole.rotation_staff[rank];
+            for staff_id in (0..len).filter(|s| self.can_assign(h, (group_id, *s))) {
+                candidates.push(((group_id, staff_id), (staff_id + len - rotation_staff) % len));
+            }
+        }
+        candidates.sort_by_key(|(pick, distance)| (self.cost(h, *pick), *distance));
+        candidates.into_iter().map(|(pick, _)| pick).collect()
+    }
+
+    /// chronological backtracking, `Err` when the node limit is reached
+    fn backtrack(&mut self, h: usize) -> Result<bool, ()> {
+        if h == self.holes.len() {
+            return Ok(true);
+        }
+        self.deepest = self.deepest.max(h);
+        for pick in self.candidates(h) {
+            self.nodes += 1;
+            if self.nodes > self.config.node_limit {
+                return Err(());
+            }
+            self.assign(h, pick);
+            if self.minimum_reachable(h) && self.backtrack(h + 1)? {
+                return Ok(true);
+            }
+            self.unassign(h);
+        }
+        Ok(false)
+    }
+
+    fn week_minimum_met(&self, (group_id, staff_id): (usize, usize), week: usize) -> bool {
+        let state = &self.staff[group_id][staff_id];
+        state.week_count[week] >= state.min_per_week
+    }
+
+    /// give a hole to another staff of its groups when it lowers the cost
+    fn try_reassign(&mut self, h: usize, new: (usize, usize)) -> bool {
+        let Some(old) = self.assignment[h] else {
+            return false;
+        };
+        if old == new {
+            return false;
+        }
+        let week = self.holes[h].week;
+        let old_total = self.staff[old.0][old.1].total as i64;
+        let new_total = self.staff[new.0][new.1].total as i64;
+        // change of the sum of squared totals
+        let fairness = (2 * new_total + 1) - (2 * old_total - 1);
+        let delta = self.config.fairness_weight * fairness + self.hole_cost(h, new) - self.hole_cost(h, old);
+        if delta >= 0 {
+            return false;
+        }
+        self.unassign(h);
+        if self.can_assign(h, new) && self.week_minimum_met(old, week) {
+            self.assign(h, new);
+            true
+        } else {
+            self.assign(h, old);
+            false
+        }
+    }
+
+    /// swap the staff of two holes filled from the same group when it lowers the cost
+    fn try_swap(&mut self, a: usize, b: usize) -> bool {
+        let (Some(staff_a), Some(staff_b)) = (self.assignment[a], self.assignment[b]) else {
+            return false;
+        };
+        if a == b || staff_a == staff_b || staff_a.0 != staff_b.0 {
+            return false;
+        }
+        let delta = self.hole_cost(a, staff_b) + self.hole_cost(b, staff_a)
+            - self.hole_cost(a, staff_a)
+            - self.hole_cost(b, staff_b);
+        if delta >= 0 {
+            return false;
+        }
+        self.unassign(a);
+        self.unassign(b);
+        if self.can_assign(a, staff_b) {
+            self.assign(a, staff_b);
+            if self.can_assign(b, staff_a) {
+                self.assign(b, staff_a);
+                let weeks = [self.holes[a].week, self.holes[b].week];
+                if weeks.iter().all(|w| self.week_minimum_met(staff_a, *w) && self.week_minimum_met(staff_b, *w)) {
+                    return true;
+                }
+                self.unassign(b);
+            }
+            self.unassign(a);
+        }
+        self.assign(a, staff_a);
+        self.assign(b, staff_b);
+        false
+    }
+
+    /// give back the changed holes to a published staff left free
+    fn keep_published(&mut self) {
+        for h in 0..self.holes.len() {
+            for pick in self.holes[h].published.clone() {
+                if self.try_reassign(h, pick) {
+                    break;
+                }
+            }
+        }
+    }
+
+    fn local_search(&mut self) {
+        if self.holes.is_empty() {
+            return;
+        }
+        let mut rng = XorShift(self.config.seed.max(1));
+        for _ in 0..self.config.iterations {
+            let h = rng.below(self.holes.len());
+            if rng.below(2) == 0 {
+                let groups = &self.holes[h].groups;
+                let group_id = groups[rng.below(groups.len())];
+                let staff_id = rng.below(self.staff[group_id].len());
+                self.try_reassign(h, (group_id, staff_id));
+            } else {
+                let other = rng.below(self.holes.len());
+                self.try_swap(h, other);
+            }
+        }
+    }
+}
+
+/// xorshift64, enough for picking moves
+struct XorShift(u64);
+
+impl XorShift {
+    fn below(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % n as u64) as usize
+    }
+}
+
+/// Fill the holes of the rule table by constraint search instead of rotation
+///
+/// Every hole of `week_gen_range` weeks from `week_delta` gets a staff of its
+/// group, or of its fallback groups in order when the group cannot fill it.
+/// The hard constraints are the holes, double booking, unavailability, the
+/// weekly quotas, consecutive days and days off, and the statutory limits and
+/// contract maximums when the config sets them; a chronological
+/// backtracking search finds a first schedule, preferring the staff with the
+/// least work, the most wanted and the one `gen_shift` would pick. A local
+/// search then moves and swaps assignments to lower the soft cost: the sum of
+/// squared totals within each group and the unmet preferences.
+///
+/// Contract minimums and skill requirements are not constraints of the
+/// search, the checkers report what the schedule breaches of them.
+pub fn solve_schedule<'a>(
+    week_rule_table: &WeekRuleTable<'_, Incomplete>,
+    staff_group_list: &'a StaffGroupList,
+    week_delta: usize,
+    week_gen_range: usize,
+    config: &SolverConfig,
+) -> Result<DecidedSchedule<'a>, SolveErr> {
+    search_schedule(week_rule_table, staff_group_list, week_delta, week_gen_range, config, None)
+}
+
+/// staff ids of each group in each slot of a published schedule,
+/// by absolute week, day, slot and group id
+pub(crate) type PublishedSlots = HashMap<(usize, usize, Slot, usize), Vec<usize>>;
+
+/// `solve_schedule`, keeping the published staff of each hole above all
+/// other costs when `published` is given
+pub(crate) fn search_schedule<'a>(
+    week_rule_table: &WeekRuleTable<'_, Incomplete>,
+    staff_group_list: &'a StaffGroupList,
+    week_delta: usize,
+    week_gen_range: usize,
+    config: &SolverConfig,
+    published: Option<&PublishedSlots>,
+) -> Result<DecidedSchedule<'a>, SolveErr> {
+    let err = |reason| Err(SolveErr { reason });
+    let cycle = week_rule_table.0.len();
+    if cycle == 0 {
+        return err(CauseOfSolveErr::EmptyRuleTable);
+    }
+
+    let mut holes = vec![];
+    for week in 0..week_gen_range {
+        let absolute = week_delta + week;
+        let week_rule = &week_rule_table.0[absolute % cycle];
+        for (day, day_rule) in week_rule.0.iter().enumerate() {
+            for (slot, shift_holes) in [&day_rule.shift_morning, &day_rule.shift_afternoon].into_iter().enumerate() {
+                for shift_hole in shift_holes {
+                    let date = config.calendar.map(|c| c.date_of(absolute, day));
+                    let mut hole = Hole {
+                        week,
+                        day,
+                        slot,
+                        groups: vec![],
+                        rotation_staff: vec![],
+                        published: vec![],
+                        preferences: vec![],
+                        available: vec![],
+                    };
+                    for (group_id, id) in shift_hole.groups() {
+                        let Some(group) = staff_group_list.0.get(group_id) else {
+                            return err(CauseOfSolveErr::GroupIdOutOfRange { group_id });
+                        };
+                        if group.len() == 0 {
+                            return err(CauseOfSolveErr::EmptyGroup { group_id });
+                        }
+                        let preferences = group
+                            .iter()
+                            .map(|staff| {
+                                let listed: i64 = config
+                                    .preferences
+                                    .iter()
+                                    .filter(|p| {
+                                        (p.group_id, p.staff_id, p.week, p.day) == (group_id, staff.get_id(), absolute, day)
+                                            && p.slot.is_none_or(|s| s == Slot::ALL[slot])
+                                    })
+                                    .map(|p| p.weight)
+                                    .sum();
+                                listed + preference_weight(staff, day, Slot::ALL[slot], date)
+                            })
+                            .collect();
+                        let published = published.and_then(|p| p.get(&(absolute, day, Slot::ALL[slot], group_id)));
+                        hole.groups.push(group_id);
+                        hole.rotation_staff.push((absolute / cycle + id) % group.len());
+                        hole.published.extend(published.into_iter().flatten().map(|staff_id| (group_id, *staff_id)));
+                        hole.preferences.push(preferences);
+                        hole.available.push(group.iter().map(|staff| is_available(staff, day, Slot::ALL[slot], date)).collect());
+                    }
+                    holes.push(hole);
+                }
+            }
+        }
+    }
+
+    let mut remaining_in_week = vec![0; holes.len()];
+    for h in 0..holes.len() {
+        remaining_in_week[h] = holes[h + 1..]
+            .iter()
+            .take_while(|o| o.week == holes[h].week)
+            .filter(|o| o.groups[0] == holes[h].groups[0])
+            .count();
+    }
+
+    let staff = staff_group_list
+        .0
+        .iter()
+        .enumerate()
+        .map(|(group_id, group)| {
+            let (min_per_week, max_per_week) = quota_bounds(&config.quotas, group_id);
+            group
+                .iter()
+                .map(|staff| StaffState {
+                    booked: vec![[false; 2]; week_gen_range * 7],
+                    week_count: vec![0; week_gen_range],
+                    total: 0,
+                    min_per_week,
+                    max_per_week,
+                    contract: staff.profile.contract.clone().filter(|_| config.contract_maximums),
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut search = Search {
+        config,
+        week_delta,
+        assignment: vec![None; holes.len()],
+        holes,
+        staff,
+        remaining_in_week,
+        repairing: published.is_some(),
+        nodes: 0,
+        deepest: 0,
+    };
+    let stuck_at = |search: &Search<'_>| {
+        let hole = &search.holes[search.deepest.min(search.holes.len() - 1)];
+        (week_delta + hole.week, hole.day, Slot::ALL[hole.slot])
+    };
+    match search.backtrack(0) {
+        Ok(true) => {}
+        Ok(false) => {
+            let (week, day, slot) = stuck_at(&search);
+            return err(CauseOfSolveErr::Infeasible { week, day, slot });
+        }
+        Err(()) => {
+            let (week, day, slot) = stuck_at(&search);
+            return err(CauseOfSolveErr::SearchLimit { week, day, slot });
+        }
+    }
+    search.local_search();
+    if search.repairing {
+        search.keep_published();
+    }
+
+    let mut weeks: Vec<WeekDecidedShift<'a>> = (0..week_gen_range)
+        .map(|_| {
+            WeekDecidedShift(std::array::from_fn(|_| DayDecidedShift { shift_morning: vec![], shift_afternoon: vec![] }))
+        })
+        .collect();
+    for (hole, pick) in search.holes.iter().zip(&search.assignment) {
+        let (group_id, staff_id) = pick.expect("every hole is assigned");
+        let staff = staff_group_list
+            .get_staff(group_id, staff_id)
+            .expect("staff ids come from the group");
+        weeks[hole.week].0[hole.day].slot_mut(Slot::ALL[hole.slot]).push(staff);
+    }
+    Ok(DecidedSchedule::new(week_delta, weeks.into_boxed_slice()))
+}
+
+/// How the holes of a rule table are filled
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Generator {
+    /// `gen_shift`, then `assign_available`
+    #[default]
+    Rotation,
+    /// `gen_shift`, then `assign_qualified` and `assign_available` keeping
+    /// the requirements met
+    QualifiedRotation(Vec<SkillRequirement>),
+    /// `solve_schedule`
+    Solver(SolverConfig),
+}
+
+/// the rule table `gen_shift` can run on: some rule, and every group a hole
+/// names in range and with staff
+fn check_rotation_input(
+    week_rule_table: &WeekRuleTable<'_, Incomplete>,
+    staff_group_list: &StaffGroupList,
+) -> Result<(), SolveErr> {
+    let err = |reason| Err(SolveErr { reason });
+    if week_rule_table.0.is_empty() {
+        return err(CauseOfSolveErr::EmptyRuleTable);
+    }
+    let holes = week_rule_table.0.iter().flat_map(|w| &w.0).flat_map(|d| d.shift_morning.iter().chain(&d.shift_afternoon));
+    for (group_id, _) in holes.flat_map(|h| h.groups()) {
+        match staff_group_list.0.get(group_id) {
+            None => return err(CauseOfSolveErr::GroupIdOutOfRange { group_id }),
+            Some(group) if group.len() == 0 => return err(CauseOfSolveErr::EmptyGroup { group_id }),
+            Some(_) => {}
+        }
+    }
+    Ok(())
+}
+
+impl Generator {
+    /// Fill the holes of a range of weeks
+    ///
+    /// rotation hands on the holes of staff who are unavailable on the dates
+    /// of `calendar` or away on one of `absences`, the solver takes both from
+    /// its config.
+    pub fn generate<'a>(
+        &self,
+        week_rule_table: &WeekRuleTable<'a, Incomplete>,
+        staff_group_list: &'a StaffGroupList,
+        week_delta: usize,
+        week_gen_range: usize,
+        calendar: Option<&ShiftCalendar>,
+        absences: &[Absence],
+    ) -> Result<DecidedSchedule<'a>, SolveErr> {
+        match self {
+            Generator::Rotation => {
+                check_rotation_input(week_rule_table, staff_group_list)?;
+                let mut schedule = DecidedSchedule::generate(week_rule_table, staff_group_list, week_delta, week_gen_range);
+                assign_available(&mut schedule, week_rule_table, staff_group_list, calendar, absences, &[]);
+                Ok(schedule)
+            }
+            Generator::QualifiedRotation(requirements) => {
+                check_rotation_input(week_rule_table, staff_group_list)?;
+                let mut schedule = DecidedSchedule::generate(week_rule_table, staff_group_list, week_delta, week_gen_range);
+                assign_qualified(&mut schedule.weeks, staff_group_list, requirements);
+                assign_available(&mut schedule, week_rule_table, staff_group_list, calendar, absences, requirements);
+                Ok(schedule)
+            }
+            Generator::Solver(config) => solve_schedule(week_rule_table, staff_group_list, week_delta, week_gen_range, config),
+        }
+    }
+}
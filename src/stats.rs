@@ -0,0 +1,73 @@
+use std::collections::BTreeMap;
+
+use crate::shift_gen::{
+    DecidedSchedule, Slot, Staff
+};
+
+/// number of assignments of one staff for each (weekday, slot)
+pub type WeekdaySlotCounts = [[usize; 2]; 7];
+
+fn slot_index(slot: Slot) -> usize {
+    match slot {
+        Slot::Morning => 0,
+        Slot::Afternoon => 1,
+    }
+}
+
+/// Fairness counters keyed by (group id, staff id)
+///
+/// counters can be seeded from history and then accumulate generated weeks.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AssignmentCounts(
+    pub BTreeMap<(usize, usize), WeekdaySlotCounts>
+);
+
+impl AssignmentCounts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_schedule(schedule: &DecidedSchedule<'_>) -> Self {
+        let mut counts = Self::new();
+        counts.add_schedule(schedule);
+        counts
+    }
+
+    pub fn add(&mut self, staff: &Staff, day: usize, slot: Slot) {
+        self.0.entry((staff.get_group_id(), staff.get_id())).or_default()[day][slot_index(slot)] += 1;
+    }
+
+    pub fn add_schedule(&mut self, schedule: &DecidedSchedule<'_>) {
+        for (_, day, day_shift) in schedule.days() {
+            for slot in Slot::ALL {
+                for staff in day_shift.slot(slot) {
+                    self.add(staff, day, slot);
+                }
+            }
+        }
+    }
+
+    /// add every counter of `other`
+    pub fn merge(&mut self, other: &AssignmentCounts) {
+        for (key, counts) in &other.0 {
+            let entry = self.0.entry(*key).or_default();
+            for day in 0..7 {
+                for slot in 0..2 {
+                    entry[day][slot] += counts[day][slot];
+                }
+            }
+        }
+    }
+
+    pub fn get(&self, group_id: usize, staff_id: usize) -> WeekdaySlotCounts {
+        self.0.get(&(group_id, staff_id)).copied().unwrap_or_default()
+    }
+
+    pub fn count(&self, group_id: usize, staff_id: usize, day: usize, slot: Slot) -> usize {
+        self.get(group_id, staff_id)[day][slot_index(slot)]
+    }
+
+    pub fn total(&self, group_id: usize, staff_id: usize) -> usize {
+        self.get(group_id, staff_id).iter().flatten().sum()
+    }
+}
@@ -0,0 +1,197 @@
+use crate::config::{Severity, ShiftConfig};
+use crate::render::WEEKDAY_NAMES;
+use crate::shift_gen::{ShiftOverride, Slot, Staff};
+use crate::validate::{validate, Diagnostic};
+
+/// One hole on one day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HoleRef {
+    /// absolute week index
+    pub week: usize,
+    pub day: usize,
+    pub slot: Slot,
+    /// index of the hole in the slot
+    pub hole: usize,
+}
+
+impl HoleRef {
+    fn with_staff(&self, group_id: usize, staff_id: usize) -> ShiftOverride {
+        ShiftOverride { week: self.week, day: self.day, slot: self.slot, hole: self.hole, group_id, staff_id }
+    }
+}
+
+/// Change of assignments agreed between staff
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwapProposal {
+    /// the staff of `hole` gives it to another staff of the group
+    Giveaway { hole: HoleRef, group_id: usize, staff_id: usize },
+    /// the staff of the two holes trade them
+    Swap { a: HoleRef, b: HoleRef },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SwapErr {
+    pub reason: CauseOfSwapErr,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CauseOfSwapErr {
+    NoSuchHole(HoleRef),
+    NoSuchStaff { group_id: usize, staff_id: usize },
+    /// the staff would take a hole they already have
+    SameStaff,
+    /// holes are filled by their own group
+    OtherGroup,
+    /// the change breaks checks the schedule passed
+    Infeasible(Vec<Diagnostic>),
+}
+
+impl std::fmt::Display for SwapErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.reason {
+            CauseOfSwapErr::NoSuchHole(h) => {
+                write!(f, "no hole {} on week {} {} {}", h.hole, h.week, WEEKDAY_NAMES[h.day], h.slot.label())
+            }
+            CauseOfSwapErr::NoSuchStaff { group_id, staff_id } => {
+                write!(f, "no staff {} in group {}", staff_id, group_id)
+            }
+            CauseOfSwapErr::SameStaff => write!(f, "the staff already has the hole"),
+            CauseOfSwapErr::OtherGroup => write!(f, "the staff are not in the same group"),
+            CauseOfSwapErr::Infeasible(violations) => {
+                write!(f, "the change breaks {} check(s)", violations.len())?;
+                violations.iter().try_for_each(|d| write!(f, "\n  {}", d))
+            }
+        }
+    }
+}
+
+impl std::error::Error for SwapErr {}
+
+/// Colleague who could take a hole or trade it for one of theirs
+#[derive(Debug, Clone)]
+pub struct Candidate<'a> {
+    pub staff: &'a Staff,
+    /// the hole can be given to the staff
+    pub can_take: bool,
+    /// holes of the staff in the same week that can be traded for it
+    pub swaps: Vec<HoleRef>,
+}
+
+/// Checks swaps and giveaways against the checkers of a config
+///
+/// a proposal is turned into overrides appended to the config ones, and the
+/// weeks it touches (with one week on each side for runs of working days)
+/// are validated with and without them. It is feasible when it adds no error:
+/// errors the schedule already had do not block it.
+pub struct SwapEngine<'c> {
+    config: &'c ShiftConfig,
+}
+
+impl<'c> SwapEngine<'c> {
+    pub fn new(config: &'c ShiftConfig) -> Self {
+        Self { config }
+    }
+
+    /// staff working in a hole, overrides applied
+    pub fn staff_of(&self, hole: HoleRef) -> Option<&'c Staff> {
+        let schedule = self.config.schedule(hole.week, 1).ok()?;
+        schedule.week(hole.week)?.0.get(hole.day)?.slot(hole.slot).get(hole.hole).copied()
+    }
+
+    /// Overrides putting the proposal in place
+    pub fn overrides(&self, proposal: &SwapProposal) -> Result<Vec<ShiftOverride>, SwapErr> {
+        let err = |reason| Err(SwapErr { reason });
+        let staff_of = |hole: HoleRef| self.staff_of(hole).ok_or(SwapErr { reason: CauseOfSwapErr::NoSuchHole(hole) });
+        match *proposal {
+            SwapProposal::Giveaway { hole, group_id, staff_id } => {
+                let from = staff_of(hole)?;
+                if self.config.staff_group_list.get_staff(group_id, staff_id).is_none() {
+                    return err(CauseOfSwapErr::NoSuchStaff { group_id, staff_id });
+                }
+                if from.get_group_id() != group_id {
+                    return err(CauseOfSwapErr::OtherGroup);
+                }
+                if from.get_id() == staff_id {
+                    return err(CauseOfSwapErr::SameStaff);
+                }
+                Ok(vec![hole.with_staff(group_id, staff_id)])
+            }
+            SwapProposal::Swap { a, b } => {
+                let (staff_a, staff_b) = (staff_of(a)?, staff_of(b)?);
+                if staff_a.get_group_id() != staff_b.get_group_id() {
+                    return err(CauseOfSwapErr::OtherGroup);
+                }
+                if staff_a.get_id() == staff_b.get_id() {
+                    return err(CauseOfSwapErr::SameStaff);
+                }
+                Ok(vec![
+                    a.with_staff(staff_b.get_group_id(), staff_b.get_id()),
+                    b.with_staff(staff_a.get_group_id(), staff_a.get_id()),
+                ])
+            }
+        }
+    }
+
+    /// Validate a proposal, `Ok` holds the overrides to apply
+    pub fn check(&self, proposal: &SwapProposal) -> Result<Vec<ShiftOverride>, SwapErr> {
+        let overrides = self.overrides(proposal)?;
+        let first = overrides.iter().map(|o| o.week).min().unwrap_or(0).saturating_sub(1);
+        let last = overrides.iter().map(|o| o.week).max().unwrap_or(0) + 1;
+        let range = last + 1 - first;
+
+        let errors = |config: &ShiftConfig| {
+            validate(config, first, range)
+                .into_iter()
+                .filter(|d| d.severity == Severity::Error)
+                .collect::<Vec<_>>()
+        };
+        let before = errors(self.config);
+        let mut trial = self.config.clone();
+        trial.overrides.extend_from_slice(&overrides);
+        let violations: Vec<Diagnostic> = errors(&trial).into_iter().filter(|d| !before.contains(d)).collect();
+        if violations.is_empty() {
+            Ok(overrides)
+        } else {
+            Err(SwapErr { reason: CauseOfSwapErr::Infeasible(violations) })
+        }
+    }
+
+    /// Config source with the proposal appended as `[[override]]` tables, if feasible
+    pub fn apply(&self, proposal: &SwapProposal) -> Result<String, SwapErr> {
+        let overrides = self.check(proposal)?;
+        Ok(self.config.source_with_overrides(&overrides))
+    }
+
+    /// Every other staff of the group who could feasibly take the hole or
+    /// trade it for one of their holes in the same week
+    pub fn candidates(&self, hole: HoleRef) -> Result<Vec<Candidate<'c>>, SwapErr> {
+        let owner = self.staff_of(hole).ok_or(SwapErr { reason: CauseOfSwapErr::NoSuchHole(hole) })?;
+        let schedule = self.config.schedule(hole.week, 1).expect("the week of the owner was generated");
+        let week_shift = schedule.week(hole.week).expect("the week was generated");
+        let group = &self.config.staff_group_list.0[owner.get_group_id()];
+        let mut candidates = vec![];
+        for staff in group.iter().filter(|s| s.get_id() != owner.get_id()) {
+            let can_take = self
+                .check(&SwapProposal::Giveaway { hole, group_id: staff.get_group_id(), staff_id: staff.get_id() })
+                .is_ok();
+            let mut swaps = vec![];
+            for (day, day_shift) in week_shift.0.iter().enumerate() {
+                for slot in Slot::ALL {
+                    for (index, s) in day_shift.slot(slot).iter().enumerate() {
+                        if (s.get_group_id(), s.get_id()) != (staff.get_group_id(), staff.get_id()) {
+                            continue;
+                        }
+                        let theirs = HoleRef { week: hole.week, day, slot, hole: index };
+                        if self.check(&SwapProposal::Swap { a: hole, b: theirs }).is_ok() {
+                            swaps.push(theirs);
+                        }
+                    }
+                }
+            }
+            if can_take || !swaps.is_empty() {
+                candidates.push(Candidate { staff, can_take, swaps });
+            }
+        }
+        Ok(candidates)
+    }
+}
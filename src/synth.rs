@@ -0,0 +1,209 @@
+use serde::{Deserialize, Serialize};
+
+use crate::render::WEEKDAY_NAMES;
+use crate::shift_gen::{
+    DayRule, Incomplete, ShiftHoll, Slot, StaffGroupList, WeekRule, WeekRuleTable
+};
+
+/// `count` holes of a group on each of the days and slots
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoverageNeed {
+    pub group_id: usize,
+    /// 0 is the first day of a rule week
+    pub days: Vec<usize>,
+    pub slots: Vec<Slot>,
+    pub count: usize,
+}
+
+/// Bounds of the weekly assignments of every staff of a group, as `QuotaChecker`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StaffQuota {
+    /// every group when `None`
+    pub group_id: Option<usize>,
+    pub min_per_week: Option<usize>,
+    pub max_per_week: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SynthRequest {
+    pub needs: Vec<CoverageNeed>,
+    pub quotas: Vec<StaffQuota>,
+    /// number of week rules, the shortest one using every staff when `None`
+    pub cycle: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SynthErr {
+    pub group_id: Option<usize>,
+    pub reason: CauseOfSynthErr,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CauseOfSynthErr {
+    UnknownGroup,
+    EmptyGroup,
+    /// the group has staff but no hole, `BasicChecker` would reject the table
+    NoHole,
+    /// more holes in one slot than staff in the group
+    SlotOverCapacity { day: usize, slot: Slot, count: usize, staff: usize },
+    /// the holes of a week cannot be shared between the staff within the quota
+    QuotaUnreachable { holes_per_week: usize, staff: usize, min: usize, max: Option<usize> },
+    /// the given cycle has too few holes to use every staff
+    CycleTooShort { cycle: usize, needed: usize },
+    ZeroCycle,
+}
+
+impl std::fmt::Display for SynthErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(group_id) = self.group_id {
+            write!(f, "group {}: ", group_id)?;
+        }
+        write!(f, "{}", self.reason)
+    }
+}
+
+impl std::fmt::Display for CauseOfSynthErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CauseOfSynthErr::UnknownGroup => write!(f, "no such group"),
+            CauseOfSynthErr::EmptyGroup => write!(f, "the group has no staff"),
+            CauseOfSynthErr::NoHole => write!(f, "no hole is needed, its staff would never be used"),
+            CauseOfSynthErr::SlotOverCapacity { day, slot, count, staff } => {
+                write!(f, "{} holes on {} {} but only {} staff", count, WEEKDAY_NAMES[*day], slot.label(), staff)
+            }
+            CauseOfSynthErr::QuotaUnreachable { holes_per_week, staff, min, max } => {
+                write!(f, "{} holes a week cannot give {} staff at least {}", holes_per_week, staff, min)?;
+                match max {
+                    Some(max) => write!(f, " and at most {} each", max),
+                    None => write!(f, " each"),
+                }
+            }
+            CauseOfSynthErr::CycleTooShort { cycle, needed } => {
+                write!(f, "{} week rules leave staff unused, at least {} are needed", cycle, needed)
+            }
+            CauseOfSynthErr::ZeroCycle => write!(f, "the cycle must have at least one week rule"),
+        }
+    }
+}
+
+/// holes of one group needed in a rule week, `[day][slot]`
+type Needs = [[usize; 2]; 7];
+
+fn slot_index(slot: Slot) -> usize {
+    Slot::ALL.iter().position(|s| *s == slot).unwrap_or(0)
+}
+
+/// tightest bounds of the quotas applying to a group
+pub(crate) fn quota_bounds(quotas: &[StaffQuota], group_id: usize) -> (usize, Option<usize>) {
+    quotas
+        .iter()
+        .filter(|q| q.group_id.is_none_or(|g| g == group_id))
+        .fold((0, None), |(min, max), q| {
+            let max = match (max, q.max_per_week) {
+                (Some(a), Some(b)) => Some(usize::min(a, b)),
+                (a, b) => a.or(b),
+            };
+            (min.max(q.min_per_week.unwrap_or(0)), max)
+        })
+}
+
+/// Build a rule table giving every group its needed holes
+///
+/// hole ids are the staff a hole starts from, and `gen_shift` rotates them
+/// by one each time the table is used up. So an even share of the ids within
+/// every week rule is an even share of the work within every week, whatever
+/// the rotation. Ids are handed out least used first: in the week, then on the
+/// day, then in the whole table. Every reason the needs cannot be met is
+/// reported.
+pub fn synthesize_rule_table<'a>(
+    staff_group_list: &StaffGroupList,
+    request: &SynthRequest,
+) -> Result<WeekRuleTable<'a, Incomplete>, Vec<SynthErr>> {
+    let groups = staff_group_list.0.len();
+    let mut errors = vec![];
+    let mut needs: Vec<Needs> = vec![[[0; 2]; 7]; groups];
+    for need in &request.needs {
+        let Some(group_needs) = needs.get_mut(need.group_id) else {
+            errors.push(SynthErr { group_id: Some(need.group_id), reason: CauseOfSynthErr::UnknownGroup });
+            continue;
+        };
+        for &day in need.days.iter().filter(|d| **d < 7) {
+            for &slot in &need.slots {
+                group_needs[day][slot_index(slot)] += need.count;
+            }
+        }
+    }
+
+    let mut cycle = 1;
+    for (group_id, group_needs) in needs.iter().enumerate() {
+        let staff = staff_group_list.0[group_id].len();
+        let err = |reason| SynthErr { group_id: Some(group_id), reason };
+        let holes_per_week: usize = group_needs.iter().flatten().sum();
+        if staff == 0 {
+            errors.push(err(CauseOfSynthErr::EmptyGroup));
+            continue;
+        }
+        if holes_per_week == 0 {
+            errors.push(err(CauseOfSynthErr::NoHole));
+            continue;
+        }
+        for (day, day_needs) in group_needs.iter().enumerate() {
+            for (slot, &count) in Slot::ALL.iter().zip(day_needs) {
+                if count > staff {
+                    errors.push(err(CauseOfSynthErr::SlotOverCapacity { day, slot: *slot, count, staff }));
+                }
+            }
+        }
+        let (min, max) = quota_bounds(&request.quotas, group_id);
+        if holes_per_week < min * staff || max.is_some_and(|max| holes_per_week > max * staff) {
+            errors.push(err(CauseOfSynthErr::QuotaUnreachable { holes_per_week, staff, min, max }));
+        }
+        let needed = staff.div_ceil(holes_per_week);
+        match request.cycle {
+            Some(0) => {}
+            Some(given) if given < needed => errors.push(err(CauseOfSynthErr::CycleTooShort { cycle: given, needed })),
+            _ => cycle = cycle.max(needed),
+        }
+    }
+    if request.cycle == Some(0) {
+        errors.push(SynthErr { group_id: None, reason: CauseOfSynthErr::ZeroCycle });
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    let cycle = request.cycle.unwrap_or(cycle);
+
+    let mut table: Vec<[DayRule<'a, Incomplete>; 7]> = (0..cycle)
+        .map(|_| std::array::from_fn(|_| DayRule { shift_morning: vec![], shift_afternoon: vec![] }))
+        .collect();
+    for (group_id, group_needs) in needs.iter().enumerate() {
+        let staff = staff_group_list.0[group_id].len();
+        let mut total = vec![0usize; staff];
+        for week_rule in table.iter_mut() {
+            let mut in_week = vec![0usize; staff];
+            for (day, day_rule) in week_rule.iter_mut().enumerate() {
+                let mut on_day = vec![0usize; staff];
+                for slot in Slot::ALL {
+                    let holes = match slot {
+                        Slot::Morning => &mut day_rule.shift_morning,
+                        Slot::Afternoon => &mut day_rule.shift_afternoon,
+                    };
+                    let mut in_slot = vec![false; staff];
+                    for _ in 0..group_needs[day][slot_index(slot)] {
+                        let id = (0..staff)
+                            .filter(|id| !in_slot[*id])
+                            .min_by_key(|id| (in_week[*id], on_day[*id], total[*id], *id))
+                            .expect("a slot has no more holes than staff");
+                        in_slot[id] = true;
+                        in_week[id] += 1;
+                        on_day[id] += 1;
+                        total[id] += 1;
+                        holes.push(ShiftHoll::new(group_id, id));
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(WeekRuleTable(table.into_iter().map(WeekRule).collect()))
+}
@@ -0,0 +1,284 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::availability::blocking_patterns;
+use crate::calendar::ShiftCalendar;
+use crate::config::{HolePosition, Location, Severity, ShiftConfig};
+use crate::render::WEEKDAY_NAMES;
+use crate::rule_checker::{verify, BasicChecker, CauseOfRuleErr, CheckRule};
+use crate::schedule_checker::{
+    AvailabilityChecker, CauseOfScheduleErr, ContractChecker, CoverageChecker, DoubleBookingChecker, LabourLawChecker, OvertimeChecker, QuotaChecker, ScheduleErr, SkillChecker
+};
+use crate::shift_gen::{
+    Incomplete, ShiftHoll, Slot, WeekRuleTable
+};
+use crate::solver::Generator;
+
+/// Finding of `validate`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// name of the check
+    pub check: &'static str,
+    pub file: Option<String>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    /// absolute week index
+    pub week: Option<usize>,
+    pub day: Option<usize>,
+    pub date: Option<NaiveDate>,
+    pub message: String,
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}:", file)?;
+            if let (Some(line), Some(column)) = (self.line, self.column) {
+                write!(f, "{}:{}:", line, column)?;
+            }
+            write!(f, " ")?;
+        }
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(f, "{}[{}]: {}", severity, self.check, self.message)?;
+        match (self.date, self.week, self.day) {
+            (Some(date), _, Some(day)) => write!(f, " ({} {})", date, WEEKDAY_NAMES[day]),
+            (_, Some(week), Some(day)) => write!(f, " (week {} {})", week, WEEKDAY_NAMES[day]),
+            (_, Some(week), None) => write!(f, " (week {})", week),
+            _ => Ok(()),
+        }
+    }
+}
+
+struct DiagnosticBuilder<'c> {
+    config: &'c ShiftConfig,
+    calendar: Option<ShiftCalendar>,
+}
+
+impl DiagnosticBuilder<'_> {
+    fn build(
+        &self,
+        severity: Severity,
+        check: &'static str,
+        location: Option<Location>,
+        week: Option<usize>,
+        day: Option<usize>,
+        message: String,
+    ) -> Diagnostic {
+        Diagnostic {
+            severity,
+            check,
+            file: self.config.path.as_ref().map(|p| p.display().to_string()),
+            line: location.map(|l| l.line),
+            column: location.map(|l| l.column),
+            week,
+            day,
+            date: self.calendar.zip(week).zip(day).map(|((c, w), d)| c.date_of(w, d)),
+            message,
+        }
+    }
+}
+
+/// position of a hole of the rule table by address
+fn hole_position(week_rule_table: &WeekRuleTable<'_, Incomplete>, hole: &ShiftHoll<'_, Incomplete>) -> Option<HolePosition> {
+    week_rule_table.0.iter().enumerate().find_map(|(week_rule_index, week_rule)| {
+        week_rule.0.iter().enumerate().find_map(|(day, day_rule)| {
+            [(Slot::Morning, &day_rule.shift_morning), (Slot::Afternoon, &day_rule.shift_afternoon)]
+                .into_iter()
+                .find_map(|(slot, holes)| {
+                    holes
+                        .iter()
+                        .position(|h| std::ptr::eq(h, hole))
+                        .map(|index| HolePosition { week_rule_index, day, slot, index })
+                })
+        })
+    })
+}
+
+fn check_rule_table(config: &ShiftConfig, builder: &DiagnosticBuilder<'_>) -> Option<Diagnostic> {
+    let data = (config.week_rule_table(), config.staff_group_list.clone());
+    let e = verify(&data, &[&BasicChecker()]).err()?;
+    let (location, message) = match e.reason {
+        CauseOfRuleErr::GroupIdOutOfRangeErr(hole) => (
+            hole_position(&data.0, hole).and_then(|p| config.hole_location(p)),
+            // the hole or one of its fallbacks
+            format!(
+                "group id {} is out of range",
+                hole.groups().map(|(g, _)| g).find(|g| data.1.0.get(*g).is_none()).unwrap_or(hole.group_id)
+            ),
+        ),
+        CauseOfRuleErr::StaffIdOutOfRangeErr(hole) => (
+            hole_position(&data.0, hole).and_then(|p| config.hole_location(p)),
+            format!(
+                "staff index {} is out of range of its group",
+                hole.groups().find(|(g, id)| data.1.get_staff(*g, *id).is_none()).map_or(hole.id, |(_, id)| id)
+            ),
+        ),
+        CauseOfRuleErr::AmPmErr() => (None, "morning and afternoon counts do not match".to_string()),
+        CauseOfRuleErr::DupHollErr => (None, "some staff are never used by the rule table".to_string()),
+    };
+    Some(builder.build(config.checks.basic_severity, "basic", location, None, None, message))
+}
+
+/// Run `BasicChecker` on the rule table, and `DoubleBookingChecker` and every
+/// configured checker on the schedule generated for the horizon
+pub fn validate(config: &ShiftConfig, week_delta: usize, week_gen_range: usize) -> Vec<Diagnostic> {
+    validate_with(config, &Generator::Rotation, week_delta, week_gen_range)
+}
+
+/// `validate` on the schedule of another generator
+pub fn validate_with(config: &ShiftConfig, generator: &Generator, week_delta: usize, week_gen_range: usize) -> Vec<Diagnostic> {
+    let builder = DiagnosticBuilder { config, calendar: config.anchor.map(ShiftCalendar::new) };
+    let mut diagnostics: Vec<Diagnostic> = check_rule_table(config, &builder).into_iter().collect();
+
+    // gen_shift divides by these lengths
+    if config.rule_table.is_empty() {
+        diagnostics.push(builder.build(Severity::Error, "basic", None, None, None, "no `[[week]]` rule".to_string()));
+        return diagnostics;
+    }
+    if let Some(group) = config.staff_group_list.0.iter().find(|g| g.len() == 0) {
+        let message = format!("group `{}` has no staff", group.get_name());
+        diagnostics.push(builder.build(Severity::Error, "basic", None, None, None, message));
+        return diagnostics;
+    }
+
+    let staff_group_list = &config.staff_group_list;
+    let cycle = config.rule_table.len();
+    let schedule = match config.generate(generator, week_delta, week_gen_range) {
+        Ok(schedule) => schedule,
+        Err(e) => {
+            diagnostics.push(builder.build(Severity::Error, "solver", None, None, None, e.to_string()));
+            return diagnostics;
+        }
+    };
+
+    let mut report = |check: &'static str, severity: Severity, check_location: Option<Location>, errors: Vec<ScheduleErr<'_>>| {
+        for e in errors {
+            let location = match (&e.reason, e.day) {
+                (CauseOfScheduleErr::DoubleBookedErr { staff, slot }, Some(day))
+                | (CauseOfScheduleErr::UnavailableErr { staff, slot }, Some(day)) => schedule
+                    .week(e.week)
+                    .and_then(|w| w.0[day].slot(*slot).iter().position(|s| std::ptr::eq(*s, *staff)))
+                    .and_then(|index| {
+                        config.hole_location(HolePosition { week_rule_index: e.week % cycle, day, slot: *slot, index })
+                    })
+                    .or(check_location),
+                (CauseOfScheduleErr::CoverageUnderErr { .. }, _)
+                | (CauseOfScheduleErr::CoverageOverErr { .. }, _)
+                | (CauseOfScheduleErr::SkillUnderErr { .. }, _)
+                | (CauseOfScheduleErr::ConsecutiveDaysErr { .. }, _) => config.week_location(e.week % cycle),
+                _ => check_location,
+            };
+            diagnostics.push(builder.build(severity, check, location, Some(e.week), e.day, e.reason.to_string()));
+        }
+    };
+
+    // overrides can put a staff twice in a slot
+    let double_booking = DoubleBookingChecker().check_rule(&schedule).err().unwrap_or_default();
+    report("basic", config.checks.basic_severity, None, double_booking);
+    for quota in &config.checks.quota {
+        let checker = QuotaChecker {
+            staff_group_list,
+            group_id: quota.group_id,
+            min_per_week: quota.min_per_week,
+            max_per_week: quota.max_per_week,
+        };
+        report("quota", quota.severity, Some(quota.location), checker.check_rule(&schedule).err().unwrap_or_default());
+    }
+    for coverage in &config.checks.coverage {
+        let checker = CoverageChecker {
+            days: coverage.days.clone(),
+            slots: coverage.slots.clone(),
+            min: coverage.min,
+            max: coverage.max,
+        };
+        report("coverage", coverage.severity, Some(coverage.location), checker.check_rule(&schedule).err().unwrap_or_default());
+    }
+    for skill in &config.checks.skill {
+        let checker = SkillChecker { requirement: skill.requirement.clone() };
+        report("skill", skill.severity, Some(skill.location), checker.check_rule(&schedule).err().unwrap_or_default());
+    }
+    if let Some(labour) = &config.checks.labour {
+        let checker = LabourLawChecker {
+            staff_group_list,
+            max_consecutive_days: labour.max_consecutive_days,
+            min_days_off_per_week: labour.min_days_off_per_week,
+        };
+        report("labour", labour.severity, Some(labour.location), checker.check_rule(&schedule).err().unwrap_or_default());
+    }
+    if let Some(overtime) = &config.checks.overtime {
+        let checker = OvertimeChecker { staff_group_list, durations: config.slot_durations, limits: overtime.limits };
+        report("overtime", overtime.severity, Some(overtime.location), checker.check_rule(&schedule).err().unwrap_or_default());
+    }
+    if staff_group_list.0.iter().flat_map(|g| g.iter()).any(|s| s.profile.contract.is_some()) {
+        let checker = ContractChecker {
+            staff_group_list,
+            durations: config.slot_durations,
+            calendar: config.anchor.map(ShiftCalendar::new),
+        };
+        // at the `[[contract]]` the staff got their contract from, a roster
+        // contract has none
+        for e in checker.check_rule(&schedule).err().unwrap_or_default() {
+            let CauseOfScheduleErr::ContractErr { staff, .. } = &e.reason else {
+                continue;
+            };
+            let (severity, location) = config
+                .contract_checks
+                .iter()
+                .rev()
+                .find(|c| (c.group_id, c.staff_id) == (staff.get_group_id(), staff.get_id()))
+                .map_or((Severity::Error, None), |c| (c.severity, Some(c.location)));
+            report("contract", severity, location, vec![e]);
+        }
+    }
+    if staff_group_list.0.iter().flat_map(|g| g.iter()).any(|s| !s.profile.availability.is_empty()) {
+        let calendar = config.anchor.map(ShiftCalendar::new);
+        let checker = AvailabilityChecker { calendar };
+        for e in checker.check_rule(&schedule).err().unwrap_or_default() {
+            let (CauseOfScheduleErr::UnavailableErr { staff, slot }, Some(day)) = (&e.reason, e.day) else {
+                continue;
+            };
+            // the patterns are the `[[availability]]` entries of the staff in
+            // file order, an error when any of the ones breached is
+            let entries: Vec<_> = config
+                .availability_checks
+                .iter()
+                .filter(|c| (c.group_id, c.staff_id) == (staff.get_group_id(), staff.get_id()))
+                .collect();
+            let breached: Vec<_> = blocking_patterns(staff, day, *slot, calendar.map(|c| c.date_of(e.week, day)))
+                .into_iter()
+                .filter_map(|i| entries.get(i))
+                .collect();
+            let severity = if !breached.is_empty() && breached.iter().all(|c| c.severity == Severity::Warning) {
+                Severity::Warning
+            } else {
+                Severity::Error
+            };
+            report("availability", severity, breached.first().map(|c| c.location), vec![e]);
+        }
+    }
+    for (index, absence) in config.absences.iter().enumerate() {
+        for (week, day, day_shift) in schedule.days() {
+            for slot in Slot::ALL.into_iter().filter(|slot| absence.covers(week, day, *slot)) {
+                if let Some((hole, staff)) = day_shift
+                    .slot(slot)
+                    .iter()
+                    .enumerate()
+                    .find(|(_, s)| (s.get_group_id(), s.get_id()) == (absence.group_id, absence.staff_id))
+                {
+                    let location = config
+                        .hole_location(HolePosition { week_rule_index: week % cycle, day, slot, index: hole })
+                        .or(config.absence_location(index));
+                    let message = format!("{} is absent but scheduled for {}", staff.name, slot.label());
+                    let severity = config.absence_checks.get(index).map_or(Severity::Error, |c| c.severity);
+                    diagnostics.push(builder.build(severity, "absence", location, Some(week), Some(day), message));
+                }
+            }
+        }
+    }
+
+    diagnostics
+}
@@ -0,0 +1,169 @@
+use std::path::Path;
+
+use chrono::{Datelike, NaiveDate};
+use rust_xlsxwriter::{Color, Format, FormatBorder, Workbook, Worksheet, XlsxError};
+
+use crate::calendar::ShiftCalendar;
+use crate::render::WEEKDAY_NAMES;
+use crate::shift_gen::{
+    DayDecidedShift, Slot, Staff, StaffGroupList, WeekDecidedShift
+};
+
+/// fill colours assigned to staff groups in order of group id
+const GROUP_FILLS: [u32; 6] = [0xFFF2CC, 0xDDEBF7, 0xE2EFDA, 0xFCE4D6, 0xEDE1F5, 0xF8D7DA];
+
+const SUMMARY_SHEET_NAME: &str = "Summary";
+
+fn header_format() -> Format {
+    Format::new()
+        .set_bold()
+        .set_background_color(Color::RGB(0xD9D9D9))
+        .set_border(FormatBorder::Thin)
+}
+
+fn group_format(group_id: usize) -> Format {
+    Format::new()
+        .set_background_color(Color::RGB(GROUP_FILLS[group_id % GROUP_FILLS.len()]))
+        .set_border(FormatBorder::Thin)
+}
+
+fn write_header(worksheet: &mut Worksheet, titles: &[String]) -> Result<(), XlsxError> {
+    let format = header_format();
+    for (col, title) in titles.iter().enumerate() {
+        worksheet.write_string_with_format(0, col as u16, title, &format)?;
+    }
+    Ok(())
+}
+
+/// one sheet per month with a day × slot grid, one column per hole
+fn write_month_sheet(
+    workbook: &mut Workbook,
+    days: &[(NaiveDate, &DayDecidedShift<'_>)],
+    hole_columns: &[usize; 2],
+) -> Result<(), XlsxError> {
+    let Some((first, _)) = days.first() else {
+        return Ok(());
+    };
+    let worksheet = workbook.add_worksheet();
+    worksheet.set_name(format!("{:04}-{:02}", first.year(), first.month()))?;
+
+    let mut titles = vec!["Date".to_string(), "Weekday".to_string()];
+    for (slot, count) in Slot::ALL.iter().zip(hole_columns) {
+        titles.extend((1..=*count).map(|n| format!("{} {}", slot.label(), n)));
+    }
+    write_header(worksheet, &titles)?;
+    worksheet.set_freeze_panes(1, 2)?;
+    worksheet.set_column_width(0, 12)?;
+    for col in 2..titles.len() {
+        worksheet.set_column_width(col as u16, 16)?;
+    }
+
+    for (row, (date, day_shift)) in days.iter().enumerate() {
+        let row = row as u32 + 1;
+        worksheet.write_string(row, 0, date.format("%Y-%m-%d").to_string())?;
+        worksheet.write_string(row, 1, WEEKDAY_NAMES[date.weekday().num_days_from_monday() as usize])?;
+        let mut col = 2;
+        for (slot, count) in Slot::ALL.iter().zip(hole_columns) {
+            for (i, staff) in day_shift.slot(*slot).iter().enumerate() {
+                worksheet.write_string_with_format(
+                    row,
+                    (col + i) as u16,
+                    &staff.name,
+                    &group_format(staff.get_group_id()),
+                )?;
+            }
+            col += count;
+        }
+    }
+    Ok(())
+}
+
+/// per-staff counts per slot type
+fn write_summary_sheet(
+    workbook: &mut Workbook,
+    shift: &[WeekDecidedShift<'_>],
+    staff_group_list: &StaffGroupList,
+) -> Result<(), XlsxError> {
+    let worksheet = workbook.add_worksheet();
+    worksheet.set_name(SUMMARY_SHEET_NAME)?;
+
+    let mut titles = vec!["Group".to_string(), "Staff".to_string()];
+    titles.extend(Slot::ALL.iter().map(|slot| slot.label().to_string()));
+    titles.push("Total".to_string());
+    write_header(worksheet, &titles)?;
+    worksheet.set_freeze_panes(1, 0)?;
+    worksheet.set_column_width(0, 14)?;
+    worksheet.set_column_width(1, 20)?;
+
+    let count = |staff: &Staff, slot: Slot| -> usize {
+        shift
+            .iter()
+            .flat_map(|week_shift| week_shift.0.iter())
+            .map(|day| day.slot(slot).iter().filter(|s| std::ptr::eq(**s, staff)).count())
+            .sum()
+    };
+
+    let staff_iter = staff_group_list
+        .0
+        .iter()
+        .flat_map(|group| group.iter().map(move |staff| (group, staff)));
+    for (row, (group, staff)) in staff_iter.enumerate() {
+        let row = row as u32 + 1;
+        let format = group_format(group.get_group_id());
+        worksheet.write_string_with_format(row, 0, group.get_name(), &format)?;
+        worksheet.write_string_with_format(row, 1, &staff.name, &format)?;
+        let mut total = 0;
+        for (i, slot) in Slot::ALL.iter().enumerate() {
+            let n = count(staff, *slot);
+            total += n;
+            worksheet.write_number(row, 2 + i as u16, n as f64)?;
+        }
+        worksheet.write_number(row, 2 + Slot::ALL.len() as u16, total as f64)?;
+    }
+    Ok(())
+}
+
+/// Build a workbook of a generated schedule
+///
+/// `start_date` is the date of the first day of `shift[0]`.
+pub fn xlsx_workbook(
+    shift: &[WeekDecidedShift<'_>],
+    staff_group_list: &StaffGroupList,
+    start_date: NaiveDate,
+) -> Result<Workbook, XlsxError> {
+    let calendar = ShiftCalendar::new(start_date);
+    let days: Vec<(NaiveDate, &DayDecidedShift<'_>)> = shift
+        .iter()
+        .enumerate()
+        .flat_map(|(week, week_shift)| {
+            week_shift.0.iter().enumerate().map(move |(day, day_shift)| (calendar.date_of(week, day), day_shift))
+        })
+        .collect();
+    let hole_columns: [usize; 2] = Slot::ALL.map(|slot| {
+        days.iter().map(|(_, day)| day.slot(slot).len()).max().unwrap_or(0)
+    });
+
+    let mut workbook = Workbook::new();
+    for month in days.chunk_by(|(a, _), (b, _)| (a.year(), a.month()) == (b.year(), b.month())) {
+        write_month_sheet(&mut workbook, month, &hole_columns)?;
+    }
+    write_summary_sheet(&mut workbook, shift, staff_group_list)?;
+    Ok(workbook)
+}
+
+pub fn xlsx_buffer(
+    shift: &[WeekDecidedShift<'_>],
+    staff_group_list: &StaffGroupList,
+    start_date: NaiveDate,
+) -> Result<Vec<u8>, XlsxError> {
+    xlsx_workbook(shift, staff_group_list, start_date)?.save_to_buffer()
+}
+
+pub fn save_xlsx<P: AsRef<Path>>(
+    shift: &[WeekDecidedShift<'_>],
+    staff_group_list: &StaffGroupList,
+    start_date: NaiveDate,
+    path: P,
+) -> Result<(), XlsxError> {
+    xlsx_workbook(shift, staff_group_list, start_date)?.save(path)
+}
@@ -0,0 +1,66 @@
+#[cfg(test)]
+mod availability_test {
+    use chrono::NaiveDate;
+    use shift_calendar::availability::*;
+    use shift_calendar::calendar::ShiftCalendar;
+    use shift_calendar::config::*;
+    use shift_calendar::rule_checker::CheckRule;
+    use shift_calendar::schedule_checker::*;
+    use shift_calendar::shift_gen::*;
+    use shift_calendar::solver::Generator;
+
+    const CONFIG: &str = r#"anchor = 2025-01-06
+
+[[group]]
+name = "A"
+staff = ["a0", "a1", "a2"]
+
+[[availability]]
+staff = "A:1"
+days = ["wed"]
+
+[[availability]]
+staff = "A:2"
+days = ["sat", "sun"]
+only = true
+from = 2025-01-13
+
+[[week]]
+mon = { morning = ["A:0"] }
+wed = { morning = ["A:1"] }
+sat = { morning = ["A:2"] }
+"#;
+
+    fn names(schedule: &DecidedSchedule<'_>, week: usize, day: usize) -> Vec<String> {
+        schedule.week(week).unwrap().0[day].slot(Slot::Morning).iter().map(|s| s.name.clone()).collect()
+    }
+
+    #[test]
+    fn rotation_solver_and_checker_keep_availability() {
+        let config = ShiftConfig::parse(CONFIG, None).unwrap();
+        let a2 = config.staff_group_list.get_staff(0, 2).unwrap();
+        let date = |s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok();
+        // the weekend pattern is not in force yet
+        assert!(is_available(a2, 2, Slot::Morning, date("2025-01-08")));
+        assert!(!is_available(a2, 2, Slot::Morning, date("2025-01-15")));
+        assert!(is_available(a2, 5, Slot::Afternoon, date("2025-01-18")));
+
+        let checker = AvailabilityChecker { calendar: config.anchor.map(ShiftCalendar::new) };
+        // plain rotation: a1 on wed of week 0, a2 on wed of week 1
+        let rotation = DecidedSchedule::generate(&config.week_rule_table(), &config.staff_group_list, 0, 2);
+        let errors = checker.check_rule(&rotation).unwrap_err();
+        let found: Vec<(usize, Option<usize>, String)> = errors.iter().map(|e| (e.week, e.day, e.reason.to_string())).collect();
+        assert_eq!(found, [
+            (0, Some(2), "a1 is not available in the morning".to_string()),
+            (1, Some(2), "a2 is not available in the morning".to_string()),
+        ]);
+
+        let schedule = config.schedule(0, 2).unwrap();
+        assert!(checker.check_rule(&schedule).is_ok());
+        assert_eq!(names(&schedule, 0, 2), ["a2"]);
+        assert_eq!(names(&schedule, 1, 2), ["a0"]);
+
+        let solved = config.generate(&Generator::Solver(config.solver_config()), 0, 2).unwrap();
+        assert!(checker.check_rule(&solved).is_ok());
+    }
+}
@@ -0,0 +1,109 @@
+#[cfg(test)]
+mod cli_test {
+    use std::path::PathBuf;
+    use std::process::Command;
+
+    const CONFIG: &str = r#"anchor = 2025-01-06
+
+[[group]]
+name = "A"
+staff = ["田中", "鈴木"]
+
+[[group]]
+name = "B"
+staff = ["佐藤", "高橋"]
+
+[[week]]
+mon = { morning = ["A:0", "B:0"], afternoon = ["B:1"] }
+tue = { afternoon = ["A:1"] }
+"#;
+
+    fn write_config(name: &str, content: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("shiftcal_cli_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn shiftcal(args: &[&str]) -> std::process::Output {
+        Command::new(env!("CARGO_BIN_EXE_shiftcal")).args(args).output().unwrap()
+    }
+
+    #[test]
+    fn generate_csv() {
+        let config = write_config("generate.toml", CONFIG);
+        let output = shiftcal(&[
+            "generate", "-c", config.to_str().unwrap(), "--start", "2025-01-15", "--weeks", "1", "-f", "csv",
+        ]);
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert_eq!(lines[0], "week,day,date,slot,hole,group,staff,employee_id");
+        assert_eq!(lines[1], "1,Mon,2025-01-13,morning,0,A,鈴木,");
+        assert_eq!(lines.len(), 5);
+    }
+
+    #[test]
+    fn generate_ics_needs_anchor() {
+        let config = write_config("no_anchor.toml", CONFIG.trim_start_matches("anchor = 2025-01-06\n"));
+        let output = shiftcal(&["generate", "-c", config.to_str().unwrap(), "-f", "ics"]);
+        assert!(!output.status.success());
+        assert!(String::from_utf8(output.stderr).unwrap().contains("needs `anchor`"));
+    }
+
+    #[test]
+    fn validate_exit_code() {
+        let warning = format!("{}\n[checks.labour]\nmax_consecutive_days = 0\nseverity = \"warning\"\n", CONFIG);
+        let config = write_config("validate_warning.toml", &warning);
+        let output = shiftcal(&["validate", "-c", config.to_str().unwrap(), "--weeks", "1"]);
+        assert!(output.status.success());
+        assert!(String::from_utf8(output.stdout).unwrap().contains("validate_warning.toml:11:1: warning[labour]"));
+
+        let error = warning.replace("severity = \"warning\"", "severity = \"error\"");
+        let config = write_config("validate_error.toml", &error);
+        let output = shiftcal(&["validate", "-c", config.to_str().unwrap(), "--weeks", "1", "-f", "json"]);
+        assert_eq!(output.status.code(), Some(1));
+        let diagnostics: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(diagnostics[0]["severity"], "error");
+        assert_eq!(diagnostics[0]["date"], "2025-01-06");
+    }
+
+    #[test]
+    fn diff_exit_code() {
+        let old = write_config("diff_old.toml", CONFIG);
+        let new = write_config("diff_new.toml", &CONFIG.replace("tue = { afternoon", "wed = { afternoon"));
+        let output = shiftcal(&["diff", old.to_str().unwrap(), old.to_str().unwrap(), "--from", "2025-01-06", "--to", "2025-01-19"]);
+        assert!(output.status.success());
+        let output = shiftcal(&["diff", old.to_str().unwrap(), new.to_str().unwrap(), "--from", "2025-01-06", "--to", "2025-01-19"]);
+        assert_eq!(output.status.code(), Some(1));
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.contains("2025-01-07 Tue Afternoon: -鈴木"));
+        assert!(stdout.contains("  moved   2025-01-14 Tue Afternoon -> 2025-01-15 Wed Afternoon"));
+    }
+
+    #[test]
+    fn synth_in_place_keeps_a_loadable_config() {
+        let source = r#"[[group]]
+name = "A"
+staff = ["田中", "鈴木"]
+
+[[need]]
+group = "A"
+days = ["mon", "wed"]
+slots = ["morning"]
+count = 1
+
+[[week]]
+[week.wed]
+morning = ["A:0"]
+"#;
+        let config = write_config("synth_in_place.toml", source);
+        let output = shiftcal(&["synth", "-c", config.to_str().unwrap(), "--in-place"]);
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        let written = std::fs::read_to_string(&config).unwrap();
+        let reloaded = shift_calendar::config::ShiftConfig::parse(&written, None).unwrap();
+        assert!(!reloaded.rule_table.is_empty());
+        assert!(written.contains("[week.wed]"));
+    }
+}
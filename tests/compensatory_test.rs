@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod compensatory_test {
+    use chrono::NaiveDate;
+    use shift_calendar::calendar::ShiftCalendar;
+    use shift_calendar::compensatory::*;
+    use shift_calendar::config::*;
+    use shift_calendar::shift_gen::*;
+    use shift_calendar::solver::Generator;
+
+    const CONFIG: &str = r#"anchor = 2025-01-06
+
+[[group]]
+name = "A"
+staff = ["a0", "a1", "a2"]
+
+[[week]]
+mon = { morning = ["A:0"] }
+tue = { morning = ["A:2"] }
+wed = { morning = ["A:1"] }
+sun = { morning = ["A:0"] }
+
+[holidays]
+rest_days = ["sun"]
+closed = [2025-01-08]
+window_days = 3
+
+[[compensatory]]
+staff = "A:1"
+worked = 2025-01-08
+off = 2025-01-07
+"#;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn holiday_work_balances_and_plans() {
+        let config = ShiftConfig::parse(CONFIG, None).unwrap();
+        // the substitute day keeps a1 off on tue
+        assert_eq!(config.absences.len(), 1);
+        assert_eq!((config.absences[0].staff_id, config.absences[0].first_day), (1, 1));
+
+        let calendar = ShiftCalendar::new(config.anchor.unwrap());
+        let holidays = config.holidays.as_ref().unwrap();
+        let schedule = config.generate(&Generator::Rotation, 0, 2).unwrap();
+        let works = holiday_work(&schedule, &calendar, holidays, &config.compensatory_days);
+        let summary: Vec<(NaiveDate, &str, Option<CompensationKind>)> =
+            works.iter().map(|w| (w.date, w.staff.name.as_str(), w.compensation.map(|c| c.kind()))).collect();
+        assert_eq!(summary, [
+            (date("2025-01-08"), "a1", Some(CompensationKind::Substitute)),
+            (date("2025-01-12"), "a0", None),
+            (date("2025-01-19"), "a1", None),
+        ]);
+
+        let balances = compensation_balances(&works, &config.staff_group_list, date("2025-01-20"));
+        let owed: Vec<(&str, usize, usize)> =
+            balances.iter().map(|b| (b.staff.name.as_str(), b.owed.len(), b.overdue.len())).collect();
+        assert_eq!(owed, [("a0", 1, 1), ("a1", 1, 0), ("a2", 0, 0)]);
+
+        let plans = plan_compensatory_days(&schedule, &config.staff_group_list, &calendar, holidays, &works, &config.absences);
+        // a0 is back on tue of week 1, a1 takes it; nothing is left of the
+        // schedule within the window of the last sunday
+        assert_eq!(plans.len(), 1);
+        assert_eq!(plans[0].compensatory_day.off, date("2025-01-14"));
+        assert_eq!(
+            plans[0].overrides,
+            [ShiftOverride { week: 1, day: 1, slot: Slot::Morning, hole: 0, group_id: 0, staff_id: 1 }]
+        );
+
+        let applied = ShiftConfig::parse(&config.source_with_compensation(&plans), None).unwrap();
+        assert_eq!(applied.compensatory_days.len(), 2);
+        assert_eq!(applied.overrides, plans[0].overrides);
+        let schedule = applied.generate(&Generator::Rotation, 0, 2).unwrap();
+        let works = holiday_work(&schedule, &calendar, holidays, &applied.compensatory_days);
+        assert_eq!(works[1].compensation.map(|c| c.kind()), Some(CompensationKind::Compensatory));
+    }
+
+    #[test]
+    fn rotation_hands_on_the_day_off() {
+        let source = r#"anchor = 2025-01-06
+
+[[group]]
+name = "A"
+staff = ["a0", "a1"]
+
+[[week]]
+mon = { morning = ["A:0"] }
+
+[holidays]
+rest_days = ["sun"]
+
+[[compensatory]]
+staff = "A:0"
+worked = 2025-01-05
+off = 2025-01-06
+"#;
+        let config = ShiftConfig::parse(source, None).unwrap();
+        let schedule = config.generate(&Generator::Rotation, 0, 2).unwrap();
+        let mondays: Vec<&str> = schedule.weeks.iter().map(|w| w.0[0].slot(Slot::Morning)[0].name.as_str()).collect();
+        assert_eq!(mondays, ["a1", "a1"]);
+    }
+}
@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod config_test {
+    use chrono::NaiveDate;
+    use shift_calendar::config::*;
+    use shift_calendar::shift_gen::*;
+
+    const CONFIG: &str = r#"anchor = 2025-01-06
+
+[[group]]
+name = "A"
+staff = ["田中", "鈴木"]
+
+[[group]]
+name = "B"
+staff = ["佐藤", "高橋"]
+
+[[week]]
+mon = { morning = ["A:0", "B:0"], afternoon = ["B:1"] }
+tue = { afternoon = ["A:1"] }
+"#;
+
+    #[test]
+    fn parse_config() {
+        let config = ShiftConfig::parse(CONFIG, None).unwrap();
+        assert_eq!(config.anchor, NaiveDate::from_ymd_opt(2025, 1, 6));
+        assert_eq!(config.staff_group_list.0.len(), 2);
+
+        let week_rule_table = config.week_rule_table();
+        let monday = &week_rule_table.0[0].0[0];
+        assert_eq!((monday.shift_morning[1].group_id, monday.shift_morning[1].id), (1, 0));
+        assert!(week_rule_table.0[0].0[6].shift_morning.is_empty());
+
+        let position = HolePosition { week_rule_index: 0, day: 1, slot: Slot::Afternoon, index: 0 };
+        assert_eq!(config.hole_location(position), Some(Location { line: 13, column: 22 }));
+    }
+
+    #[test]
+    fn error_location() {
+        let source = CONFIG.replace("\"B:1\"", "\"C:1\"");
+        let e = ShiftConfig::parse(&source, None).err().unwrap();
+        assert!(matches!(e.reason, CauseOfConfigErr::UnknownGroup(ref g) if g == "C"));
+        assert_eq!(e.location, Some(Location { line: 12, column: 48 }));
+
+        let source = CONFIG.replace("\"A:1\"", "\"A1\"");
+        let e = ShiftConfig::parse(&source, None).err().unwrap();
+        assert!(matches!(e.reason, CauseOfConfigErr::MalformedHole(_)));
+        assert_eq!(e.to_string(), "13:22: malformed hole `A1`, expected `<group>:<index>`");
+
+        let source = CONFIG.replace("2025-01-06", "2025-01-07");
+        let e = ShiftConfig::parse(&source, None).err().unwrap();
+        assert_eq!(e.to_string(), "1:10: anchor `2025-01-07` is a Tuesday, expected a Monday");
+
+        // a config without rules parses, generating from it fails
+        let config = ShiftConfig::parse(CONFIG.split("[[week]]").next().unwrap(), None).unwrap();
+        assert_eq!(config.schedule(0, 1).err().unwrap().to_string(), "the rule table is empty");
+    }
+}
@@ -0,0 +1,51 @@
+#[cfg(test)]
+mod contract_test {
+    use shift_calendar::calendar::ShiftCalendar;
+    use shift_calendar::config::*;
+    use shift_calendar::contract::*;
+
+    const CONFIG: &str = r#"anchor = 2025-01-06
+
+[[group]]
+name = "A"
+staff = ["a0", "a1"]
+
+[slots]
+afternoon = { hours = 5.5 }
+
+[[contract]]
+staff = ["A:0"]
+max_days_per_week = 1
+max_hours_per_week = 10
+min_days_per_month = 10
+
+[[week]]
+mon = { morning = ["A:0"], afternoon = ["A:1"] }
+tue = { morning = ["A:1"], afternoon = ["A:0"] }
+"#;
+
+    #[test]
+    fn utilisation_against_contract() {
+        let config = ShiftConfig::parse(CONFIG, None).unwrap();
+        assert_eq!(config.slot_durations, SlotDurations { morning: 240, afternoon: 330 });
+        let a0 = config.staff_group_list.get_staff(0, 0).unwrap();
+        assert_eq!(a0.profile.contract.as_ref().and_then(|c| c.max_hours_per_week), Some(10));
+        assert!(config.staff_group_list.get_staff(0, 1).unwrap().profile.contract.is_none());
+
+        // 2025-01-06 to 2025-02-09
+        let calendar = ShiftCalendar::new(config.anchor.unwrap());
+        let schedule = config.schedule(0, 5).unwrap();
+        let report = utilisation(&schedule, &config.staff_group_list, &config.slot_durations, Some(&calendar));
+        let (period, usage) = report[0].periods[0];
+        assert_eq!((period, usage.days, usage.minutes), (Period::Week(0), 2, 570));
+        let january = Period::Month { year: 2025, month: 1 };
+        let (_, usage) = report[0].periods.iter().find(|(p, _)| *p == january).unwrap();
+        assert_eq!((usage.days, usage.covered_days), (8, 26));
+
+        // every week breaks the day limit only, january is not complete
+        assert_eq!(report[0].breaches.len(), 5);
+        assert!(report[0].breaches.iter().all(|b| b.measure == Measure::Days && b.over));
+        assert_eq!(report[0].breaches[0].to_string(), "2 days in week 0, contract is at most 1 days");
+        assert!(report[1].breaches.is_empty());
+    }
+}
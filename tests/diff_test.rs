@@ -0,0 +1,81 @@
+#[cfg(test)]
+mod diff_test {
+    use chrono::NaiveDate;
+    use shift_calendar::calendar::*;
+    use shift_calendar::diff::*;
+    use shift_calendar::shift_gen::*;
+
+    fn day_rule<'a>(m: &[(usize, usize)], a: &[(usize, usize)]) -> DayRule<'a, Incomplete> {
+        DayRule {
+            shift_morning: m.iter().map(|&(g, i)| ShiftHoll::new(g, i)).collect(),
+            shift_afternoon: a.iter().map(|&(g, i)| ShiftHoll::new(g, i)).collect(),
+        }
+    }
+
+    fn date(m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, m, d).unwrap()
+    }
+
+    fn staff_group_list(names: &[&str]) -> StaffGroupList {
+        let mut staff_group = StaffGroup::new("group a");
+        for name in names {
+            staff_group.add_staff(name);
+        }
+        let mut staff_group_list = StaffGroupList::new();
+        staff_group_list.add_staff_group(staff_group);
+        staff_group_list
+    }
+
+    #[test]
+    fn moved_added_removed() {
+        let empty = || day_rule(&[], &[]);
+        let old_table = WeekRuleTable(vec![WeekRule([
+            day_rule(&[(0, 0)], &[(0, 1)]),
+            empty(), empty(), empty(), empty(), empty(), empty(),
+        ])]);
+        let new_table = WeekRuleTable(vec![WeekRule([
+            day_rule(&[(0, 0)], &[]),
+            day_rule(&[], &[(0, 1)]),
+            empty(), empty(), empty(),
+            day_rule(&[(0, 2)], &[]),
+            empty(),
+        ])]);
+        let old_list = staff_group_list(&["田中", "鈴木"]);
+        let new_list = staff_group_list(&["田中", "鈴木", "佐藤"]);
+        let calendar = ShiftCalendar::new(date(1, 6));
+        let old_schedule = DecidedSchedule::generate(&old_table, &old_list, 0, 1);
+        let new_schedule = DecidedSchedule::generate(&new_table, &new_list, 0, 1);
+
+        let diff = diff_schedules(
+            &DiffSide { schedule: &old_schedule, staff_group_list: &old_list, calendar },
+            &DiffSide { schedule: &new_schedule, staff_group_list: &new_list, calendar },
+            date(1, 6),
+            date(1, 12),
+        );
+
+        let by_date: Vec<(NaiveDate, Slot, Vec<String>, Vec<String>)> =
+            diff.by_date.iter().map(|d| (d.at.date, d.at.slot, d.added.clone(), d.removed.clone())).collect();
+        assert_eq!(by_date, [
+            (date(1, 6), Slot::Afternoon, vec![], vec!["鈴木".to_string()]),
+            (date(1, 7), Slot::Afternoon, vec!["鈴木".to_string()], vec![]),
+            (date(1, 11), Slot::Morning, vec!["佐藤".to_string()], vec![]),
+        ]);
+
+        let suzuki = diff.by_staff.iter().find(|s| s.name == "鈴木").unwrap();
+        assert_eq!(suzuki.key, StaffKey("group a/鈴木".to_string()));
+        assert_eq!(suzuki.moved.len(), 1);
+        assert!(suzuki.added.is_empty() && suzuki.removed.is_empty());
+        let sato = diff.by_staff.iter().find(|s| s.name == "佐藤").unwrap();
+        assert_eq!(sato.added.len(), 1);
+        assert!(diff.render().contains("moved   2025-01-06 Mon Afternoon -> 2025-01-07 Tue Afternoon"));
+
+        // outside of the dates nothing is compared
+        let same = diff_schedules(
+            &DiffSide { schedule: &old_schedule, staff_group_list: &old_list, calendar },
+            &DiffSide { schedule: &new_schedule, staff_group_list: &new_list, calendar },
+            date(1, 8),
+            date(1, 10),
+        );
+        assert!(same.is_empty());
+    }
+}
@@ -0,0 +1,196 @@
+#[cfg(test)]
+mod document_test {
+    use shift_calendar::calendar::ShiftCalendar;
+    use shift_calendar::config::ShiftConfig;
+    use shift_calendar::shift_gen::*;
+    use shift_calendar::document::*;
+    use shift_calendar::solver::Generator;
+
+    fn day_rule<'a>(m: &[(usize, usize)], a: &[(usize, usize)]) -> DayRule<'a, Incomplete> {
+        DayRule {
+            shift_morning: m.iter().map(|&(g, i)| ShiftHoll::new(g, i)).collect(),
+            shift_afternoon: a.iter().map(|&(g, i)| ShiftHoll::new(g, i)).collect(),
+        }
+    }
+
+    fn create_test_data<'a>() -> (WeekRuleTable<'a, Incomplete>, StaffGroupList) {
+        let week_rule0 = WeekRule([
+            day_rule(&[(0, 0), (1, 0)], &[(1, 1)]),
+            day_rule(&[], &[(0, 1)]),
+            day_rule(&[], &[]),
+            day_rule(&[(1, 2)], &[]),
+            day_rule(&[], &[(0, 2)]),
+            day_rule(&[], &[]),
+            day_rule(&[], &[]),
+        ]);
+        let week_rule1 = WeekRule([
+            day_rule(&[(0, 2), (1, 2)], &[(1, 0)]),
+            day_rule(&[], &[(0, 0)]),
+            day_rule(&[], &[]),
+            day_rule(&[(1, 1)], &[]),
+            day_rule(&[], &[(0, 1)]),
+            day_rule(&[], &[]),
+            day_rule(&[], &[]),
+        ]);
+
+        let mut staff_group_a = StaffGroup::new("group a");
+        for name in ["nameA0", "nameA1", "nameA2"] {
+            staff_group_a.add_staff(name);
+        }
+        let mut staff_group_b = StaffGroup::new("group b");
+        for name in ["nameB0", "nameB1", "nameB2", "nameB3"] {
+            staff_group_b.add_staff(name);
+        }
+        let mut staff_group_list = StaffGroupList::new();
+        staff_group_list.add_staff_group(staff_group_a);
+        staff_group_list.add_staff_group(staff_group_b);
+
+        (WeekRuleTable(vec![week_rule0, week_rule1]), staff_group_list)
+    }
+
+    fn create_document() -> ScheduleDocument {
+        let (week_rule_table, staff_group_list) = create_test_data();
+        let params = GenerationParams { week_delta: 25, week_gen_range: 5, ..GenerationParams::default() };
+        let overrides = [ShiftOverride {
+            week: 26, day: 0, slot: Slot::Morning, hole: 1, group_id: 1, staff_id: 3,
+        }];
+        let mut shift = gen_shift(&week_rule_table, &staff_group_list, params.week_delta, params.week_gen_range);
+        apply_overrides(&mut shift, &staff_group_list, params.week_delta, &overrides);
+
+        ScheduleDocument::new(
+            Metadata { title: "test".to_string(), ..Default::default() },
+            &staff_group_list,
+            &week_rule_table,
+            params,
+            &shift,
+            &overrides,
+        )
+    }
+
+    #[test]
+    fn round_trip() {
+        let doc = create_document();
+        assert_eq!(doc.assignments[1].days[0].morning[1].name, "nameB3");
+
+        let json = doc.to_json();
+        let loaded = ScheduleDocument::from_json(&json).unwrap();
+        assert_eq!(loaded, doc);
+        assert!(loaded.is_reproducible());
+    }
+
+    #[test]
+    fn schema_version() {
+        let json = create_document().to_json();
+
+        let version = format!("\"schema_version\": \"{}.{}\"", SCHEMA_MAJOR, SCHEMA_MINOR);
+
+        // newer minor version with unknown fields is accepted
+        let newer = json.replacen(
+            &version,
+            &format!("\"schema_version\": \"{}.99\", \"future_field\": [1, 2]", SCHEMA_MAJOR),
+            1,
+        );
+        assert!(ScheduleDocument::from_json(&newer).is_ok());
+
+        let major = json.replacen(&version, &format!("\"schema_version\": \"{}.0\"", SCHEMA_MAJOR + 1), 1);
+        assert!(matches!(
+            ScheduleDocument::from_json(&major),
+            Err(DocumentErr::UnsupportedSchemaVersion(_))
+        ));
+    }
+
+    #[test]
+    fn rules_generation_cannot_run_on() {
+        let doc = create_document();
+        let from_json = |doc: &ScheduleDocument| ScheduleDocument::from_json(&doc.to_json());
+
+        let mut empty = doc.clone();
+        empty.rule_table.clear();
+        assert!(matches!(from_json(&empty), Err(DocumentErr::EmptyRuleTable)));
+
+        let groups = doc.roster.len();
+        let mut out_of_range = doc.clone();
+        out_of_range.rule_table[0].days[0].morning[0].fallback.push(HoleDoc::new(groups, 0));
+        assert!(matches!(
+            from_json(&out_of_range),
+            Err(DocumentErr::GroupIdOutOfRange { group_id }) if group_id == groups
+        ));
+
+        let mut no_staff = doc.clone();
+        no_staff.roster[0].staff.clear();
+        assert!(matches!(from_json(&no_staff), Err(DocumentErr::EmptyGroup { group_id: 0 })));
+        assert!(matches!(
+            doc.regenerate(&no_staff.staff_group_list()),
+            Err(DocumentErr::EmptyGroup { group_id: 0 })
+        ));
+    }
+
+    #[test]
+    fn saved_schedule_regenerates_as_generated() {
+        let source = r#"anchor = 2025-01-06
+
+[[group]]
+name = "A"
+staff = ["a0", "a1", "a2"]
+
+[[group]]
+name = "B"
+staff = ["b0"]
+
+[[availability]]
+staff = "A:1"
+days = ["tue"]
+
+[[absence]]
+staff = "A:1"
+from = 2025-01-13
+
+[[week]]
+mon = { morning = ["B:0|A:0", "B:0|A:0"] }
+tue = { morning = ["A:1"] }
+"#;
+        let config = ShiftConfig::parse(source, None).unwrap();
+        let calendar = ShiftCalendar::new(config.anchor.unwrap());
+        let save = |generator: Generator| {
+            let schedule = config.generate(&generator, 0, 2).unwrap();
+            let params = GenerationParams {
+                week_delta: 0,
+                week_gen_range: 2,
+                start_date: Some(calendar.date_of(0, 0)),
+                generator,
+                absences: config.absences.clone(),
+            };
+            let doc = ScheduleDocument::new(
+                Metadata::default(),
+                &config.staff_group_list,
+                &config.week_rule_table(),
+                params,
+                &schedule.weeks,
+                &config.overrides,
+            );
+            ScheduleDocument::from_json(&doc.to_json()).unwrap()
+        };
+
+        // b0 is doubled on mon and falls back to A, a1 is off on tue and away
+        // on mon of week 1
+        let doc = save(Generator::Rotation);
+        let names = |week: usize, day: usize| -> Vec<&str> {
+            doc.assignments[week].days[day].morning.iter().map(|s| s.name.as_str()).collect()
+        };
+        assert_eq!(names(0, 0), ["b0", "a0"]);
+        assert_eq!(names(0, 1), ["a2"]);
+        assert_eq!(names(1, 0), ["b0", "a2"]);
+        assert_eq!(doc.params.generator, Generator::Rotation);
+        assert!(doc.is_reproducible());
+
+        assert!(save(config.qualified_rotation()).is_reproducible());
+        let solved = save(Generator::Solver(config.solver_config()));
+        assert!(matches!(solved.params.generator, Generator::Solver(_)));
+        assert!(solved.is_reproducible());
+
+        // without its absences the document no longer matches its rules
+        let mut forgotten = doc.clone();
+        forgotten.params.absences.clear();
+        assert!(!forgotten.is_reproducible());
+    }
+}
@@ -0,0 +1,139 @@
+#[cfg(test)]
+mod editor_test {
+    use shift_calendar::config::*;
+    use shift_calendar::document::*;
+    use shift_calendar::editor::*;
+
+    const CONFIG: &str = r#"anchor = 2025-01-06
+
+[[group]]
+name = "A"
+staff = ["田中", "鈴木"]
+
+# rules
+[[week]]
+mon = { morning = ["A:0"], afternoon = ["A:1"] }
+
+[checks.labour]
+max_consecutive_days = 6
+"#;
+
+    #[test]
+    fn edit_and_write_back() {
+        let mut editor = RuleEditor::new(ShiftConfig::parse(CONFIG, None).unwrap(), None);
+        assert!(editor.diagnostics().is_empty());
+
+        // copy 田中 to monday morning, then move the copy to tuesday afternoon
+        editor.add_hole();
+        editor.move_hole(Direction::Down);
+        editor.move_hole(Direction::Right);
+        assert_eq!(editor.cursor.day, 1);
+        assert_eq!(editor.rule_table()[0].days[1].afternoon, [HoleDoc::new(0, 0)]);
+        assert!(editor.is_dirty());
+
+        // an index out of the group shows up at the hole
+        editor.change_id(5);
+        let diagnostics = editor.diagnostics_at(editor.cursor.position());
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].check, "basic");
+
+        editor.delete_hole();
+        editor.add_week_rule();
+        assert_eq!(
+            editor.source(),
+            r#"anchor = 2025-01-06
+
+[[group]]
+name = "A"
+staff = ["田中", "鈴木"]
+
+# rules
+[[week]]
+mon = { morning = ["A:0"], afternoon = ["A:1"] }
+
+[[week]]
+
+[checks.labour]
+max_consecutive_days = 6
+"#
+        );
+        assert_eq!(editor.config().rule_table.len(), 2);
+        assert!(editor.preview().contains("田中"));
+    }
+
+    #[test]
+    fn fallback_groups_and_parse_errors() {
+        let dir = std::env::temp_dir().join(format!("shiftcal_editor_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("roster.csv"), "group,name\nA,田中\nB,鈴木\nC,佐藤\n").unwrap();
+        let path = dir.join("shift.toml");
+        std::fs::write(&path, "roster = \"roster.csv\"\n\n[[week]]\nmon = { morning = [\"B:0|A:0\"] }\n").unwrap();
+        let mut editor = RuleEditor::open(&path).unwrap();
+
+        // A is the fallback of the hole, cycling from B goes on to C
+        editor.cycle_group(-1);
+        assert_eq!(editor.selected_hole().unwrap().group, 2);
+        editor.cycle_group(1);
+        assert_eq!(editor.selected_hole().unwrap().group, 1);
+
+        // the roster went away: the error is a diagnostic and nothing is saved
+        std::fs::remove_file(dir.join("roster.csv")).unwrap();
+        editor.change_id(1);
+        assert!(editor.parse_error().is_some());
+        assert_eq!(editor.diagnostics().len(), 1);
+        assert_eq!(editor.diagnostics()[0].check, "config");
+        assert!(editor.save().is_err());
+        assert!(std::fs::read_to_string(&path).unwrap().contains("B:0|A:0"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn sub_table_layout_is_kept() {
+        let source = r#"anchor = 2025-01-06
+
+[[group]]
+name = "A"
+staff = ["田中", "鈴木"]
+
+[[week]]
+mon = { morning = ["A:0"] } # opening
+
+[week.wed]
+morning = ["A:1"]
+afternoon = ["A:0"]
+
+[checks.labour]
+max_consecutive_days = 6
+"#;
+        let mut editor = RuleEditor::new(ShiftConfig::parse(source, None).unwrap(), None);
+        // a copy of 鈴木 on wednesday morning, and 田中 on monday afternoon
+        editor.move_cursor(Direction::Right);
+        editor.move_cursor(Direction::Right);
+        editor.add_hole();
+        editor.move_cursor(Direction::Left);
+        editor.move_cursor(Direction::Left);
+        editor.move_cursor(Direction::Down);
+        editor.add_hole();
+        assert!(editor.parse_error().is_none());
+        assert_eq!(
+            editor.source(),
+            r#"anchor = 2025-01-06
+
+[[group]]
+name = "A"
+staff = ["田中", "鈴木"]
+
+[[week]]
+mon = { morning = ["A:0"], afternoon = ["A:0"] } # opening
+
+[week.wed]
+morning = ["A:1", "A:1"]
+afternoon = ["A:0"]
+
+[checks.labour]
+max_consecutive_days = 6
+"#
+        );
+        assert_eq!(ShiftConfig::parse(&editor.source(), None).unwrap().rule_table, editor.rule_table());
+    }
+}
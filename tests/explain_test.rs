@@ -0,0 +1,63 @@
+#[cfg(test)]
+mod explain_test {
+    use chrono::NaiveDate;
+    use shift_calendar::calendar::*;
+    use shift_calendar::config::*;
+    use shift_calendar::explain::*;
+    use shift_calendar::shift_gen::*;
+
+    const CONFIG: &str = r#"anchor = 2025-01-06
+
+[[group]]
+name = "A"
+staff = ["田中", "鈴木", "佐藤"]
+
+[[week]]
+mon = { morning = ["A:0"], afternoon = ["A:1"] }
+
+[[week]]
+tue = { morning = ["A:2"] }
+
+[[override]]
+date = 2025-01-20
+slot = "afternoon"
+staff = "A:0"
+"#;
+
+    fn date(m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, m, d).unwrap()
+    }
+
+    #[test]
+    fn explain_rotation_and_override() {
+        let config = ShiftConfig::parse(CONFIG, None).unwrap();
+        assert_eq!(config.overrides, [ShiftOverride { week: 2, day: 0, slot: Slot::Afternoon, hole: 0, group_id: 0, staff_id: 0 }]);
+        let week_rule_table = config.week_rule_table();
+        let explainer = Explainer::new(&week_rule_table, &config.staff_group_list, &config.overrides, config.anchor.map(ShiftCalendar::new));
+
+        // week 3 uses rule 1 for the second time: (1 + 2) % 3 = 0
+        let derivation = explainer.explain_date(date(1, 28), Slot::Morning, 0).unwrap();
+        assert_eq!((derivation.week, derivation.week_rule_index, derivation.rotation), (3, 1, 1));
+        assert_eq!(derivation.staff_index, 0);
+        assert_eq!(derivation.staff().name, "田中");
+        assert!(derivation.replacement.is_none());
+        assert!(explainer.explain_date(date(1, 28), Slot::Morning, 1).is_none());
+
+        // week 2 generates 佐藤 in the afternoon, replaced by 田中
+        let tanaka = config.staff_group_list.get_staff(0, 0).unwrap();
+        let derivations = explainer.explain_staff(tanaka, date(1, 20));
+        assert_eq!(derivations.len(), 1);
+        assert_eq!(derivations[0].generated.name, "佐藤");
+        assert!(matches!(derivations[0].replacement, Some(Replacement::Override { index: 0, .. })));
+        let steps = derivations[0].steps(&config.staff_group_list);
+        assert!(steps.contains(&"staff index = (rotation + id) % group size = (1 + 1) % 3 = 2".to_string()));
+        assert_eq!(steps.last().unwrap(), "result      = 田中");
+        assert_eq!(config.override_location(0), Some(Location { line: 13, column: 1 }));
+    }
+
+    #[test]
+    fn override_needs_anchor() {
+        let e = ShiftConfig::parse(CONFIG.trim_start_matches("anchor = 2025-01-06\n"), None).err().unwrap();
+        assert!(matches!(e.reason, CauseOfConfigErr::MalformedOverride(_)));
+    }
+}
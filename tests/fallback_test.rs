@@ -0,0 +1,58 @@
+#[cfg(test)]
+mod fallback_test {
+    use shift_calendar::config::*;
+    use shift_calendar::document::HoleDoc;
+    use shift_calendar::explain::*;
+    use shift_calendar::shift_gen::*;
+    use shift_calendar::solver::Generator;
+    use shift_calendar::validate::*;
+
+    const CONFIG: &str = r#"[[group]]
+name = "A"
+staff = ["a0", "a1"]
+
+[[group]]
+name = "B"
+staff = ["b0"]
+
+[[availability]]
+staff = "B:0"
+days = ["tue"]
+
+[[week]]
+mon = { morning = ["B:0|A:0", "B:0|A:1"] }
+tue = { morning = ["B:0|A:0"] }
+"#;
+
+    fn names(schedule: &DecidedSchedule<'_>, day: usize) -> Vec<String> {
+        schedule.week(0).unwrap().0[day].slot(Slot::Morning).iter().map(|s| s.name.clone()).collect()
+    }
+
+    #[test]
+    fn holes_fall_back_to_other_groups() {
+        let config = ShiftConfig::parse(CONFIG, None).unwrap();
+        let hole = &config.rule_table[0].days[0].morning[1];
+        assert_eq!((hole.group, hole.id, hole.fallback.clone()), (1, 0, vec![HoleDoc::new(0, 1)]));
+        assert!(config.source_with_rule_table(&config.rule_table).contains(r#"["B:0|A:0", "B:0|A:1"]"#));
+        assert!(ShiftConfig::parse(&CONFIG.replace("B:0|A:1", "B:0|B:0"), None).is_err());
+
+        // B has one staff for two holes on mon and nobody available on tue
+        let schedule = config.schedule(0, 1).unwrap();
+        assert_eq!(names(&schedule, 0), ["b0", "a1"]);
+        assert_eq!(names(&schedule, 1), ["a0"]);
+        // A is only reached through fallbacks, which do not count as using its staff
+        let checks: Vec<&str> = validate(&config, 0, 1).iter().map(|d| d.check).collect();
+        assert_eq!(checks, ["basic"]);
+
+        let solved = config.generate(&Generator::Solver(config.solver_config()), 0, 1).unwrap();
+        assert_eq!(names(&solved, 0)[0], "b0");
+        assert_eq!(solved.week(0).unwrap().0[0].slot(Slot::Morning)[1].get_group_id(), 0);
+        assert_eq!(solved.week(0).unwrap().0[1].slot(Slot::Morning)[0].get_group_id(), 0);
+
+        let table = config.week_rule_table();
+        let explainer = Explainer::new(&table, &config.staff_group_list, &config.overrides, None);
+        let derivation = explainer.explain_hole(0, 0, Slot::Morning, 1).unwrap();
+        assert!(matches!(derivation.replacement, Some(Replacement::Reassigned { fallback: true, .. })));
+        assert_eq!(derivation.staff().name, "a1");
+    }
+}
@@ -0,0 +1,72 @@
+#[cfg(test)]
+mod history_test {
+    use chrono::NaiveDate;
+    use shift_calendar::calendar::*;
+    use shift_calendar::history::*;
+    use shift_calendar::schedule_checker::*;
+    use shift_calendar::shift_gen::*;
+    use shift_calendar::stats::*;
+
+    fn create_staff_group_list() -> StaffGroupList {
+        let mut staff_group_a = StaffGroup::new("group a");
+        staff_group_a.add_staff_with_profile(
+            "田中",
+            StaffProfile { employee_id: Some("E0001".to_string()), ..Default::default() },
+        );
+        staff_group_a.add_staff("鈴木");
+        let mut staff_group_list = StaffGroupList::new();
+        staff_group_list.add_staff_group(staff_group_a);
+        staff_group_list
+    }
+
+    fn calendar() -> ShiftCalendar {
+        ShiftCalendar::new(NaiveDate::from_ymd_opt(2024, 4, 1).unwrap())
+    }
+
+    #[test]
+    fn import_and_audit() {
+        let history = "\
+date,slot,staff
+2024-04-08,morning,E0001
+2024-04-08,morning,鈴木
+2024-04-20,午後,鈴木
+2024-04-20,pm,鈴木
+";
+        let staff_group_list = create_staff_group_list();
+        let schedule = import_history_csv(history.as_bytes(), &staff_group_list, &calendar()).unwrap();
+        assert_eq!(schedule.week_delta, 1);
+        assert_eq!(schedule.weeks.len(), 2);
+        assert_eq!(schedule.weeks[0].0[0].shift_morning.len(), 2);
+
+        let errors = audit(&schedule, &[&DoubleBookingChecker()]);
+        assert_eq!(errors.len(), 1);
+        assert_eq!((errors[0].week, errors[0].day), (2, Some(5)));
+        assert!(matches!(
+            errors[0].reason,
+            CauseOfScheduleErr::DoubleBookedErr { staff, slot: Slot::Afternoon } if staff.name == "鈴木"
+        ));
+
+        // counters continue from history
+        let mut counts = AssignmentCounts::from_schedule(&schedule);
+        assert_eq!(counts.count(0, 1, 5, Slot::Afternoon), 2);
+        counts.add(staff_group_list.get_staff(0, 1).unwrap(), 5, Slot::Afternoon);
+        assert_eq!(counts.total(0, 1), 4);
+    }
+
+    #[test]
+    fn row_level_errors() {
+        let history = "\
+date,slot,staff
+2024-03-31,morning,E0001
+2024-04-08,night,鈴木
+2024-04-09,morning,佐藤
+";
+        let staff_group_list = create_staff_group_list();
+        let errors = import_history_csv(history.as_bytes(), &staff_group_list, &calendar()).unwrap_err();
+        let lines: Vec<usize> = errors.iter().map(|e| e.line).collect();
+        assert_eq!(lines, [2, 3, 4]);
+        assert!(matches!(errors[0].reason, CauseOfHistoryErr::DateBeforeAnchor(_)));
+        assert!(matches!(errors[1].reason, CauseOfHistoryErr::InvalidSlot(_)));
+        assert!(matches!(errors[2].reason, CauseOfHistoryErr::UnknownStaff(_)));
+    }
+}
@@ -0,0 +1,50 @@
+#[cfg(test)]
+mod hours_test {
+    use shift_calendar::config::*;
+    use shift_calendar::contract::*;
+    use shift_calendar::hours::*;
+    use shift_calendar::rule_checker::CheckRule;
+    use shift_calendar::schedule_checker::*;
+
+    const CONFIG: &str = r#"[[group]]
+name = "A"
+staff = ["a0", "a1"]
+
+[slots]
+morning = { start = "08:00", end = "13:00" }
+afternoon = { start = "13:00", end = "18:30", break_minutes = 30 }
+
+[checks.overtime]
+
+[[week]]
+mon = { morning = ["A:0"], afternoon = ["A:0"] }
+tue = { morning = ["A:1"], afternoon = ["A:0"] }
+wed = { morning = ["A:0"], afternoon = ["A:1"] }
+thu = { morning = ["A:1"], afternoon = ["A:0"] }
+fri = { morning = ["A:0"], afternoon = ["A:0"] }
+sat = { morning = ["A:1"], afternoon = ["A:0"] }
+sun = { morning = ["A:0"], afternoon = ["A:0"] }
+"#;
+
+    #[test]
+    fn daily_and_weekly_overtime() {
+        let config = ShiftConfig::parse(CONFIG, None).unwrap();
+        assert_eq!(config.slot_durations, SlotDurations { morning: 300, afternoon: 300 });
+        let limits = config.checks.overtime.as_ref().unwrap().limits;
+        assert_eq!(limits, StatutoryLimits::default());
+
+        let schedule = config.schedule(0, 1).unwrap();
+        let reports = working_hours(&schedule, &config.staff_group_list, &config.slot_durations, &limits, None);
+        // a0: 10 hours on mon, fri and sun, 5 hours on the other days
+        let week = reports[0].weeks[0];
+        assert_eq!((week.minutes, week.daily_overtime, week.weekly_overtime), (3000, 360, 240));
+        assert_eq!(reports[0].days.iter().filter(|d| d.overtime > 0).count(), 3);
+        assert!(reports[0].months.is_empty());
+        assert_eq!((reports[1].minutes(), reports[1].overtime()), (1200, 0));
+
+        let checker = OvertimeChecker { staff_group_list: &config.staff_group_list, durations: config.slot_durations, limits };
+        let errors = checker.check_rule(&schedule).unwrap_err();
+        assert_eq!(errors.len(), 4);
+        assert_eq!(errors[3].to_string(), "week 0: a0 works 44h in the week besides daily overtime, statutory limit is 40h");
+    }
+}
@@ -0,0 +1,128 @@
+#[cfg(test)]
+mod leave_test {
+    use chrono::NaiveDate;
+    use shift_calendar::config::*;
+    use shift_calendar::leave::*;
+    use shift_calendar::shift_gen::Slot;
+
+    const ROSTER: &str = "\
+group,name,join_date,employment_type
+A,a0,2022-04-01,full-time
+A,a1,2024-10-01,part-time
+";
+
+    const CONFIG: &str = r#"anchor = 2025-01-06
+roster = "roster.csv"
+
+[[contract]]
+employment_type = "part-time"
+max_days_per_week = 3
+
+[[leave]]
+staff = "A:0"
+from = 2024-12-23
+to = 2024-12-27
+
+[[leave]]
+staff = "A:0"
+from = 2025-01-07
+
+[[attendance]]
+staff = "A:1"
+grant = 2025-04-01
+rate = 0.5
+
+[[week]]
+mon = { morning = ["A:0"], afternoon = ["A:1"] }
+"#;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn grants_use_and_mandatory_days() {
+        assert_eq!(statutory_grant(0, None), 10);
+        assert_eq!(statutory_grant(9, None), 20);
+        assert_eq!(statutory_grant(0, Some(3)), 5);
+        assert_eq!(statutory_grant(6, Some(1)), 3);
+
+        let dir = std::env::temp_dir().join(format!("shiftcal_leave_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("roster.csv"), ROSTER).unwrap();
+        let config = ShiftConfig::parse(CONFIG, Some(&dir)).unwrap();
+        // the leave after the anchor is an absence too
+        assert_eq!(config.absences.len(), 1);
+        assert_eq!(config.absences[0].first_day, 1);
+
+        let a0 = config.staff_group_list.get_staff(0, 0).unwrap();
+        let ledger = config.leave_ledger(a0, date("2025-05-01")).unwrap();
+        let grants: Vec<(NaiveDate, u32, u32)> = ledger.grants.iter().map(|g| (g.date, g.days, g.used)).collect();
+        assert_eq!(grants, [
+            (date("2022-10-01"), 10, 0),
+            (date("2023-10-01"), 11, 6),
+            (date("2024-10-01"), 12, 0),
+        ]);
+        assert_eq!((ledger.balance(), ledger.lost()), (17, 10));
+        assert_eq!(ledger.missed().len(), 2);
+        assert!(ledger.at_risk().is_none());
+        // before any leave of the current year is taken
+        let ledger = config.leave_ledger(a0, date("2024-12-01")).unwrap();
+        assert_eq!(ledger.at_risk().map(|m| (m.taken, m.deadline)), Some((0, date("2025-09-30"))));
+
+        // a part-timer of 3 days a week under 80% attendance gets nothing
+        let a1 = config.staff_group_list.get_staff(0, 1).unwrap();
+        assert_eq!(grant_days_per_week(a1), Some(3));
+        let ledger = config.leave_ledger(a1, date("2025-05-01")).unwrap();
+        assert!(ledger.grants[0].withheld);
+        assert_eq!(ledger.balance(), 0);
+        assert!(ledger.mandatory.is_empty());
+    }
+
+    #[test]
+    fn holidays_use_no_leave() {
+        let dir = std::env::temp_dir().join(format!("shiftcal_leave_holidays_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("roster.csv"), ROSTER).unwrap();
+        // friday to tuesday over a weekend and a closed monday
+        let config = ShiftConfig::parse(r#"anchor = 2025-01-06
+roster = "roster.csv"
+
+[holidays]
+rest_days = ["sat", "sun"]
+closed = [2025-02-17]
+
+[[leave]]
+staff = "A:0"
+from = 2025-02-14
+to = 2025-02-18
+
+[[week]]
+mon = { morning = ["A:0"], afternoon = ["A:1"] }
+"#, Some(&dir)).unwrap();
+        let a0 = config.staff_group_list.get_staff(0, 0).unwrap();
+        let ledger = config.leave_ledger(a0, date("2025-03-01")).unwrap();
+        assert_eq!(ledger.grants.iter().map(|g| g.used).sum::<u32>(), 2);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rotation_hands_on_the_leave() {
+        let config = ShiftConfig::parse(r#"anchor = 2025-01-06
+
+[[group]]
+name = "A"
+staff = ["a0", "a1", "a2"]
+
+[[leave]]
+staff = "A:1"
+from = 2025-01-13
+
+[[week]]
+mon = { morning = ["A:0"] }
+"#, None).unwrap();
+        let schedule = config.schedule(0, 3).unwrap();
+        let mondays: Vec<&str> = schedule.weeks.iter().map(|w| w.0[0].slot(Slot::Morning)[0].name.as_str()).collect();
+        assert_eq!(mondays, ["a0", "a2", "a2"]);
+    }
+}
@@ -0,0 +1,81 @@
+#[cfg(test)]
+mod offset_test {
+    use shift_calendar::config::ShiftConfig;
+    use shift_calendar::offset::*;
+    use shift_calendar::shift_gen::*;
+    use shift_calendar::stats::*;
+
+    fn day_rule<'a>(m: &[(usize, usize)], a: &[(usize, usize)]) -> DayRule<'a, Incomplete> {
+        DayRule {
+            shift_morning: m.iter().map(|&(g, i)| ShiftHoll::new(g, i)).collect(),
+            shift_afternoon: a.iter().map(|&(g, i)| ShiftHoll::new(g, i)).collect(),
+        }
+    }
+
+    fn week_rule<'a>(day: usize, m: &[(usize, usize)]) -> WeekRule<'a, Incomplete> {
+        WeekRule(std::array::from_fn(|d| if d == day { day_rule(m, &[]) } else { day_rule(&[], &[]) }))
+    }
+
+    #[test]
+    fn saturdays_go_to_the_others() {
+        let mut group = StaffGroup::new("A");
+        (0..3).for_each(|i| group.add_staff(&format!("a{}", i)));
+        let mut staff_group_list = StaffGroupList::new();
+        staff_group_list.add_staff_group(group);
+        let table = WeekRuleTable(vec![week_rule(5, &[(0, 0)])]);
+
+        // a0 did the last two saturdays
+        let mut history = AssignmentCounts::new();
+        let a0 = staff_group_list.get_staff(0, 0).unwrap();
+        history.add(a0, 5, Slot::Morning);
+        history.add(a0, 5, Slot::Morning);
+
+        let scores = rank_offsets(&table, &staff_group_list, &history, |table| {
+            Some(DecidedSchedule::generate(table, &staff_group_list, 0, 2))
+        });
+        assert_eq!(scores.len(), 1);
+        assert_eq!(scores[0].offset, StartOffset { rule_offset: 0, group_shifts: vec![1] });
+        // saturdays 2, 1, 1 and the same totals: 3 * 6 - 4² twice
+        assert_eq!(scores[0].cost(), 4);
+
+        let shifted = scores[0].offset.apply(&table, &staff_group_list);
+        let schedule = DecidedSchedule::generate(&shifted, &staff_group_list, 0, 2);
+        let saturdays: Vec<&str> = schedule.weeks.iter().map(|w| w.0[5].shift_morning[0].name.as_str()).collect();
+        assert_eq!(saturdays, ["a1", "a2"]);
+
+        let two_rules = WeekRuleTable(vec![week_rule(0, &[(0, 0)]), week_rule(1, &[(0, 2)])]);
+        let rotated = StartOffset { rule_offset: 1, group_shifts: vec![2] }.apply(&two_rules, &staff_group_list);
+        assert_eq!(rotated.0[0].0[1].shift_morning[0].id, 1);
+        assert_eq!(rotated.0[1].0[0].shift_morning[0].id, 2);
+    }
+
+    #[test]
+    fn offsets_score_the_generated_weeks() {
+        let source = r#"anchor = 2025-01-06
+
+[[group]]
+name = "A"
+staff = ["a0", "a1", "a2"]
+
+[[availability]]
+staff = "A:0"
+days = ["sat"]
+
+[[week]]
+sat = { morning = ["A:0"] }
+"#;
+        let config = ShiftConfig::parse(source, None).unwrap();
+        // a1 did the last two saturdays
+        let mut history = AssignmentCounts::new();
+        let a1 = config.staff_group_list.get_staff(0, 1).unwrap();
+        history.add(a1, 5, Slot::Morning);
+        history.add(a1, 5, Slot::Morning);
+
+        // plain rotation would start from a2 and hand the next one to a0,
+        // who is off on saturdays and passes it to a1
+        let scores = config.rank_offsets(&history, 0, 2);
+        assert_eq!(scores[0].offset, StartOffset { rule_offset: 0, group_shifts: vec![1] });
+        // saturdays and totals 0, 3, 1: 3 * 10 - 4² twice
+        assert_eq!(scores[0].cost(), 28);
+    }
+}
@@ -0,0 +1,78 @@
+#[cfg(test)]
+mod period_test {
+    use shift_calendar::config::ShiftConfig;
+    use shift_calendar::period::*;
+    use shift_calendar::shift_gen::*;
+
+    fn day_rule<'a>(m: &[(usize, usize)], a: &[(usize, usize)]) -> DayRule<'a, Incomplete> {
+        DayRule {
+            shift_morning: m.iter().map(|&(g, i)| ShiftHoll::new(g, i)).collect(),
+            shift_afternoon: a.iter().map(|&(g, i)| ShiftHoll::new(g, i)).collect(),
+        }
+    }
+
+    fn week_rule<'a>(mon: DayRule<'a, Incomplete>) -> WeekRule<'a, Incomplete> {
+        WeekRule(std::array::from_fn(|day| if day == 0 { mon.clone() } else { day_rule(&[], &[]) }))
+    }
+
+    #[test]
+    fn periods_of_groups() {
+        let mut staff_group_list = StaffGroupList::new();
+        for (name, len) in [("A", 4), ("B", 6), ("C", 2)] {
+            let mut group = StaffGroup::new(name);
+            (0..len).for_each(|i| group.add_staff(&format!("{}{}", name, i)));
+            staff_group_list.add_staff_group(group);
+        }
+        // C works monday morning as a whole, so it repeats every week
+        let table = WeekRuleTable(vec![
+            week_rule(day_rule(&[(0, 0), (1, 0), (2, 0), (2, 1)], &[(1, 3)])),
+            week_rule(day_rule(&[(0, 1), (1, 1), (2, 0), (2, 1)], &[(1, 4)])),
+        ]);
+        let generate = |weeks| Some(DecidedSchedule::generate(&table, &staff_group_list, 0, weeks));
+        let analysis = analyze_period(&table, &staff_group_list, None, generate).unwrap();
+        let periods: Vec<usize> = analysis.groups.iter().map(|g| g.period).collect();
+        assert_eq!(periods, [8, 12, 1]);
+        assert_eq!((analysis.period, analysis.weeks), (24, 24));
+
+        let a = &analysis.groups[0];
+        assert_eq!(a.holes[0], [8, 0]);
+        assert!(a.staff.iter().all(|s| s.count(0, Slot::Morning) == 2 && s.count(0, Slot::Afternoon) == 0));
+        assert!(a.never(0).is_empty());
+        assert_eq!(analysis.groups[2].staff[1].counts[0], [1, 0]);
+
+        assert!(analyze_period(&WeekRuleTable(vec![]), &staff_group_list, None, generate).is_none());
+    }
+
+    #[test]
+    fn availability_in_the_period() {
+        let source = r#"anchor = 2025-01-06
+
+[[group]]
+name = "A"
+staff = ["a0", "a1", "a2"]
+
+[[availability]]
+staff = "A:1"
+days = ["tue"]
+
+[[absence]]
+staff = "A:0"
+from = 2025-02-17
+
+[[week]]
+mon = { morning = ["A:0"] }
+tue = { morning = ["A:0"] }
+"#;
+        let config = ShiftConfig::parse(source, None).unwrap();
+        let analysis = config.analyze_period().unwrap();
+        // the absence in week 6 stretches the 3 weeks of rotation to 9, and
+        // breaks the repeat
+        assert_eq!((analysis.weeks, analysis.period), (9, 9));
+        let a = &analysis.groups[0];
+        assert_eq!(a.holes[1], [9, 0]);
+        assert_eq!(a.never(1), [(1, Slot::Morning)]);
+        assert!(a.never(0).is_empty());
+        assert_eq!(a.staff[1].count(0, Slot::Morning), 4);
+        assert_eq!(a.staff[2].count(1, Slot::Morning), 6);
+    }
+}
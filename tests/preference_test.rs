@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod preference_test {
+    use chrono::NaiveDate;
+    use shift_calendar::calendar::*;
+    use shift_calendar::config::*;
+    use shift_calendar::preference::*;
+    use shift_calendar::shift_gen::*;
+    use shift_calendar::solver::*;
+
+    const CONFIG: &str = r#"anchor = 2025-01-06
+
+[[group]]
+name = "A"
+staff = ["a0", "a1"]
+
+[[week]]
+mon = { morning = ["A:0"], afternoon = ["A:1"] }
+fri = { morning = ["A:1"] }
+
+[[preference]]
+staff = "A:0"
+slot = "afternoon"
+weight = 2
+
+[[preference]]
+staff = "A:1"
+day = "fri"
+weight = -3
+
+[[preference]]
+staff = "A:1"
+date = 2025-01-06
+slot = "morning"
+weight = 1
+"#;
+
+    #[test]
+    fn score_report_and_solver() {
+        let config = ShiftConfig::parse(CONFIG, None).unwrap();
+        let a1 = config.staff_group_list.get_staff(0, 1).unwrap();
+        assert_eq!(a1.profile.preferences.len(), 2);
+        let date = NaiveDate::from_ymd_opt(2025, 1, 6);
+        assert_eq!(preference_weight(a1, 0, Slot::Morning, date), 1);
+        // date preferences need the calendar
+        assert_eq!(preference_weight(a1, 0, Slot::Morning, None), 0);
+
+        // week 0: a0 mon am, a1 mon pm and fri am; week 1 the other way round
+        let calendar = ShiftCalendar::new(config.anchor.unwrap());
+        let schedule = config.schedule(0, 2).unwrap();
+        let report = satisfaction_report(&schedule, &config.staff_group_list, Some(&calendar));
+        let rows: Vec<(usize, usize, usize, i64)> = report.iter().map(|s| (s.assignments, s.liked, s.disliked, s.score)).collect();
+        assert_eq!(rows, [(3, 1, 0, 2), (3, 0, 1, -3)]);
+        assert_eq!(score_schedule(&schedule, Some(&calendar)), -1);
+
+        let solver = Generator::Solver(SolverConfig { fairness_weight: 0, ..config.solver_config() });
+        let solved = config.generate(&solver, 0, 2).unwrap();
+        // a0 takes both afternoons and fridays, a1 gets the morning of the 6th
+        assert_eq!(score_schedule(&solved, Some(&calendar)), 5);
+    }
+}
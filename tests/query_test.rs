@@ -0,0 +1,78 @@
+#[cfg(test)]
+mod query_test {
+    use chrono::NaiveDate;
+    use shift_calendar::calendar::*;
+    use shift_calendar::config::ShiftConfig;
+    use shift_calendar::query::*;
+    use shift_calendar::shift_gen::*;
+
+    fn day_rule<'a>(m: &[(usize, usize)], a: &[(usize, usize)]) -> DayRule<'a, Incomplete> {
+        DayRule {
+            shift_morning: m.iter().map(|&(g, i)| ShiftHoll::new(g, i)).collect(),
+            shift_afternoon: a.iter().map(|&(g, i)| ShiftHoll::new(g, i)).collect(),
+        }
+    }
+
+    fn date(m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, m, d).unwrap()
+    }
+
+    #[test]
+    fn who_and_when() {
+        let week_rule_table = WeekRuleTable(vec![WeekRule([
+            day_rule(&[(0, 0)], &[(0, 1)]),
+            day_rule(&[], &[]),
+            day_rule(&[], &[]),
+            day_rule(&[], &[]),
+            day_rule(&[], &[]),
+            day_rule(&[], &[(0, 2)]),
+            day_rule(&[], &[]),
+        ])]);
+        let mut staff_group = StaffGroup::new("group a");
+        for name in ["田中", "鈴木", "佐藤"] {
+            staff_group.add_staff(name);
+        }
+        let mut staff_group_list = StaffGroupList::new();
+        staff_group_list.add_staff_group(staff_group);
+
+        let query = ShiftQuery::new(&week_rule_table, &staff_group_list, ShiftCalendar::new(date(1, 6)));
+
+        // week 1 rotates by one: 鈴木, 佐藤 on monday and 田中 on saturday
+        let who: Vec<&str> = query.who(date(1, 18), Some(Slot::Afternoon)).iter().map(|a| a.staff.name.as_str()).collect();
+        assert_eq!(who, ["田中"]);
+        assert_eq!(query.who(date(1, 13), None).len(), 2);
+        assert!(query.who(date(1, 1), None).is_empty());
+
+        let tanaka = staff_group_list.get_staff(0, 0).unwrap();
+        let dates: Vec<NaiveDate> = query.next_assignments(tanaka, date(1, 7), 3).iter().map(|a| a.date).collect();
+        assert_eq!(dates, [date(1, 18), date(1, 20), date(1, 27)]);
+        assert_eq!(query.assignments_between(tanaka, date(1, 6), date(1, 31)).len(), 4);
+    }
+
+    #[test]
+    fn who_follows_availability() {
+        let config = ShiftConfig::parse(r#"anchor = 2025-01-06
+
+[[group]]
+name = "A"
+staff = ["a0", "a1", "a2"]
+
+[[availability]]
+staff = "A:1"
+days = ["wed"]
+
+[[week]]
+wed = { morning = ["A:1"] }
+"#, None).unwrap();
+        let week_rule_table = config.week_rule_table();
+        let query = ShiftQuery::new(&week_rule_table, &config.staff_group_list, ShiftCalendar::new(date(1, 6)));
+
+        // rotation picks a1 on the first wednesday, who hands it to a2
+        let who: Vec<&str> = query.who(date(1, 8), Some(Slot::Morning)).iter().map(|a| a.staff.name.as_str()).collect();
+        assert_eq!(who, ["a2"]);
+        let a1 = config.staff_group_list.get_staff(0, 1).unwrap();
+        assert!(query.assignments_between(a1, date(1, 6), date(1, 12)).is_empty());
+        let dates: Vec<NaiveDate> = query.next_assignments(a1, date(1, 6), 1).iter().map(|a| a.date).collect();
+        assert!(dates.is_empty());
+    }
+}
@@ -0,0 +1,63 @@
+#[cfg(test)]
+mod render_test {
+    use shift_calendar::shift_gen::*;
+    use shift_calendar::render::*;
+
+    fn day_rule<'a>(m: &[(usize, usize)], a: &[(usize, usize)]) -> DayRule<'a, Incomplete> {
+        DayRule {
+            shift_morning: m.iter().map(|&(g, i)| ShiftHoll::new(g, i)).collect(),
+            shift_afternoon: a.iter().map(|&(g, i)| ShiftHoll::new(g, i)).collect(),
+        }
+    }
+
+    fn create_staff_group_list() -> StaffGroupList {
+        let mut staff_group_a = StaffGroup::new("group a");
+        staff_group_a.add_staff("田中");
+        staff_group_a.add_staff("Suzuki");
+        let mut staff_group_b = StaffGroup::new("group b");
+        staff_group_b.add_staff("佐藤花子");
+
+        let mut staff_group_list = StaffGroupList::new();
+        staff_group_list.add_staff_group(staff_group_a);
+        staff_group_list.add_staff_group(staff_group_b);
+        staff_group_list
+    }
+
+    fn create_week_rule_table<'a>() -> WeekRuleTable<'a, Incomplete> {
+        WeekRuleTable(vec![WeekRule([
+            day_rule(&[(0, 0), (1, 0)], &[(0, 1)]),
+            day_rule(&[], &[(0, 0)]),
+            day_rule(&[], &[]),
+            day_rule(&[(0, 1)], &[]),
+            day_rule(&[], &[]),
+            day_rule(&[], &[]),
+            day_rule(&[], &[]),
+        ])])
+    }
+
+    #[test]
+    fn day_slot_grid_pads_wide_names() {
+        let staff_group_list = create_staff_group_list();
+        let shift = gen_shift(&create_week_rule_table(), &staff_group_list, 0, 1);
+        let table = render_day_slot_grid(&shift, &RenderOptions::new());
+        let lines: Vec<&str> = table.lines().collect();
+
+        assert_eq!(lines[0], "Day    | Morning        | Afternoon");
+        assert_eq!(lines[2], "W0 Mon | 田中, 佐藤花子 | Suzuki");
+        assert_eq!(lines.len(), 2 + 7);
+    }
+
+    #[test]
+    fn staff_day_matrix_marks_slots() {
+        let staff_group_list = create_staff_group_list();
+        let shift = gen_shift(&create_week_rule_table(), &staff_group_list, 0, 1);
+        let table = render_staff_day_matrix(&shift, &staff_group_list, &RenderOptions::new());
+        let lines: Vec<&str> = table.lines().collect();
+
+        assert!(lines[2].starts_with("group a | 田中     | M      | A      | –"));
+        assert!(lines[3].starts_with("group a | Suzuki   | A      | –      | –      | M"));
+
+        let colored = render_staff_day_matrix(&shift, &staff_group_list, &RenderOptions::new().with_color(true));
+        assert!(colored.contains("\x1b[33mM\x1b[0m"));
+    }
+}
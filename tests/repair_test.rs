@@ -0,0 +1,53 @@
+#[cfg(test)]
+mod repair_test {
+    use shift_calendar::config::*;
+    use shift_calendar::repair::*;
+    use shift_calendar::shift_gen::*;
+    use shift_calendar::validate::*;
+
+    const PUBLISHED: &str = r#"anchor = 2025-01-06
+
+[[group]]
+name = "A"
+staff = ["a0", "a1", "a2", "a3"]
+
+[[week]]
+mon = { morning = ["A:0"], afternoon = ["A:1"] }
+tue = { morning = ["A:2"], afternoon = ["A:3"] }
+wed = { morning = ["A:0"], afternoon = ["A:1"] }
+thu = { morning = ["A:2"], afternoon = ["A:3"] }
+fri = { morning = ["A:0"], afternoon = ["A:1"] }
+
+[[checks.quota]]
+max_per_week = 4
+"#;
+
+    fn names<'a>(schedule: &'a DecidedSchedule<'_>) -> Vec<&'a str> {
+        schedule
+            .days()
+            .flat_map(|(_, _, d)| Slot::ALL.map(|slot| d.slot(slot).first().map_or("", |s| s.name.as_str())))
+            .collect()
+    }
+
+    #[test]
+    fn absence_moves_only_its_holes() {
+        let old = ShiftConfig::parse(PUBLISHED, None).unwrap();
+        let absence = "\n[[absence]]\nstaff = \"A:0\"\nfrom = 2025-01-13\nto = 2025-01-26\n";
+        let new = ShiftConfig::parse(&format!("{}{}", PUBLISHED, absence), None).unwrap();
+        assert_eq!(new.absences.len(), 1);
+        assert_eq!((new.absences[0].first_day, new.absences[0].last_day), (7, 20));
+
+        // rotation gives a0 holes in weeks 1 and 2, they go to the others
+        let diagnostics = validate(&new, 0, 4);
+        assert_eq!(diagnostics.iter().filter(|d| d.check == "absence").count(), 0);
+
+        let published = old.schedule(0, 4).unwrap();
+        let repaired = new
+            .repair(&Published { schedule: &published, staff_group_list: &old.staff_group_list }, &new.solver_config(), 0, 4)
+            .unwrap();
+        let (before, after) = (names(&published), names(&repaired));
+        let changed: Vec<usize> = (0..before.len()).filter(|i| before[*i] != after[*i]).collect();
+        assert_eq!(changed.len(), 4);
+        assert!(changed.iter().all(|i| before[*i] == "a0" && after[*i] != "a0"));
+    }
+}
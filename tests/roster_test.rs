@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod roster_test {
+    use chrono::NaiveDate;
+    use shift_calendar::roster::*;
+    use shift_calendar::shift_gen::*;
+
+    const ROSTER: &str = "\
+group,name,employee_id,join_date,employment_type,store
+A,田中太郎,E0001,2023-04-01,full-time,shibuya
+B,鈴木花子,E0002,2024-10-01,part-time,
+A,佐藤次郎,E0003,,,shinjuku
+";
+
+    #[test]
+    fn import_roster() {
+        let staff_group_list = import_roster_csv(ROSTER.as_bytes()).unwrap();
+        assert_eq!(staff_group_list.0.len(), 2);
+        assert_eq!(staff_group_list.0[0].get_name(), "A");
+        assert_eq!(staff_group_list.0[0].len(), 2);
+
+        let staff = staff_group_list.get_staff(1, 0).unwrap();
+        assert_eq!(staff.name, "鈴木花子");
+        assert_eq!(staff.get_group_id(), 1);
+        assert_eq!(staff.profile.join_date, NaiveDate::from_ymd_opt(2024, 10, 1));
+        assert_eq!(staff.profile.employment_type, Some(EmploymentType::PartTime));
+        assert!(staff.profile.attributes.is_empty());
+
+        let staff = staff_group_list.get_staff(0, 1).unwrap();
+        assert_eq!(staff.profile.employee_id.as_deref(), Some("E0003"));
+        assert_eq!(staff.profile.attributes["store"], "shinjuku");
+    }
+
+    #[test]
+    fn row_level_errors() {
+        let roster = "\
+group,name,employee_id,join_date
+A,田中太郎,E0001,2023-04-01
+,鈴木花子,E0002,
+A,佐藤次郎,E0001,
+B,高橋,E0004,2023-13-01
+";
+        let preview = preview_roster_csv(roster.as_bytes());
+        assert_eq!(preview.rows.len(), 1);
+        let lines: Vec<usize> = preview.errors.iter().map(|e| e.line).collect();
+        assert_eq!(lines, [3, 4, 5]);
+        assert!(matches!(preview.errors[0].reason, CauseOfRosterErr::EmptyField("group")));
+        assert!(matches!(preview.errors[1].reason, CauseOfRosterErr::DuplicateEmployeeId(_)));
+        assert!(matches!(preview.errors[2].reason, CauseOfRosterErr::InvalidJoinDate(_)));
+        assert!(preview.summary().starts_with("1 staff in 1 groups\n  A: 1\n3 errors\n"));
+
+        assert!(import_roster_csv(roster.as_bytes()).is_err());
+        assert!(matches!(
+            import_roster_csv("name\nfoo\n".as_bytes()).err().unwrap()[0].reason,
+            CauseOfRosterErr::MissingColumn("group")
+        ));
+    }
+}
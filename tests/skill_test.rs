@@ -0,0 +1,79 @@
+#[cfg(test)]
+mod skill_test {
+    use shift_calendar::config::*;
+    use shift_calendar::rule_checker::CheckRule;
+    use shift_calendar::schedule_checker::*;
+    use shift_calendar::shift_gen::*;
+
+    const CONFIG: &str = r#"[[group]]
+name = "A"
+staff = ["a0", "a1", "a2"]
+
+[[skill]]
+name = "keyholder"
+staff = ["A:2"]
+
+[[week]]
+mon = { morning = ["A:0"], afternoon = ["A:1"] }
+tue = { morning = ["A:1"], afternoon = ["A:2"] }
+
+[[checks.skill]]
+skill = "keyholder"
+slots = ["morning"]
+"#;
+
+    fn names(schedule: &DecidedSchedule<'_>, week: usize, day: usize, slot: Slot) -> Vec<String> {
+        schedule.week(week).unwrap().0[day].slot(slot).iter().map(|s| s.name.clone()).collect()
+    }
+
+    #[test]
+    fn qualified_rotation_meets_requirements() {
+        let config = ShiftConfig::parse(CONFIG, None).unwrap();
+        assert!(config.staff_group_list.get_staff(0, 2).unwrap().has_skill("keyholder"));
+        assert!(!config.staff_group_list.get_staff(0, 0).unwrap().has_skill("keyholder"));
+        let checker = SkillChecker { requirement: config.checks.skill[0].requirement.clone() };
+
+        // rotation: a0/a1 in week 0, a1/a2 in week 1, a2/a0 in week 2
+        let schedule = config.schedule(0, 3).unwrap();
+        let errors = checker.check_rule(&schedule).unwrap_err();
+        let days: Vec<(usize, Option<usize>)> = errors.iter().map(|e| (e.week, e.day)).collect();
+        assert_eq!(days, [(0, Some(0)), (0, Some(1)), (1, Some(0)), (2, Some(1))]);
+
+        let qualified = config.generate(&config.qualified_rotation(), 0, 3).unwrap();
+        assert!(checker.check_rule(&qualified).is_ok());
+        assert_eq!(names(&qualified, 0, 0, Slot::Morning), ["a2"]);
+        // afternoons are not required to have a keyholder and keep their staff
+        assert_eq!(names(&qualified, 0, 1, Slot::Afternoon), ["a2"]);
+        assert_eq!(names(&qualified, 1, 1, Slot::Morning), ["a2"]);
+    }
+
+    #[test]
+    fn availability_keeps_the_skills() {
+        let source = r#"[[group]]
+name = "A"
+staff = ["a0", "a1", "a2", "a3"]
+
+[[skill]]
+name = "keyholder"
+staff = ["A:1", "A:3"]
+
+[[availability]]
+staff = "A:1"
+days = ["mon"]
+
+[[week]]
+mon = { morning = ["A:0"] }
+
+[[checks.skill]]
+skill = "keyholder"
+slots = ["morning"]
+"#;
+        let config = ShiftConfig::parse(source, None).unwrap();
+        let checker = SkillChecker { requirement: config.checks.skill[0].requirement.clone() };
+        // a1 is the next keyholder after a0 but is off on mondays, the hole
+        // goes past a2 to a3
+        let qualified = config.generate(&config.qualified_rotation(), 0, 1).unwrap();
+        assert_eq!(names(&qualified, 0, 0, Slot::Morning), ["a3"]);
+        assert!(checker.check_rule(&qualified).is_ok());
+    }
+}
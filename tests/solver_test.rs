@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod solver_test {
+    use shift_calendar::contract::Contract;
+    use shift_calendar::hours::StatutoryLimits;
+    use shift_calendar::rule_checker::*;
+    use shift_calendar::schedule_checker::*;
+    use shift_calendar::shift_gen::*;
+    use shift_calendar::solver::*;
+    use shift_calendar::stats::*;
+
+    fn day_rule<'a>(m: &[(usize, usize)], a: &[(usize, usize)]) -> DayRule<'a, Incomplete> {
+        DayRule {
+            shift_morning: m.iter().map(|&(g, i)| ShiftHoll::new(g, i)).collect(),
+            shift_afternoon: a.iter().map(|&(g, i)| ShiftHoll::new(g, i)).collect(),
+        }
+    }
+
+    fn staff_group_list(len: usize) -> StaffGroupList {
+        let mut staff_group = StaffGroup::new("group a");
+        for i in 0..len {
+            staff_group.add_staff(&format!("staff{}", i));
+        }
+        let mut staff_group_list = StaffGroupList::new();
+        staff_group_list.add_staff_group(staff_group);
+        staff_group_list
+    }
+
+    fn every_morning<'a>() -> WeekRuleTable<'a, Incomplete> {
+        WeekRuleTable(vec![WeekRule(std::array::from_fn(|_| day_rule(&[(0, 0)], &[])))])
+    }
+
+    #[test]
+    fn hard_and_soft_constraints() {
+        let staff_group_list = staff_group_list(3);
+        let config = SolverConfig {
+            max_consecutive_days: Some(3),
+            min_days_off_per_week: Some(2),
+            unavailable: vec![Unavailability { group_id: 0, staff_id: 0, week: 10, day: 0, slot: None }],
+            preferences: vec![Preference { group_id: 0, staff_id: 1, week: 10, day: 2, slot: Some(Slot::Morning), weight: 5 }],
+            ..SolverConfig::default()
+        };
+        let schedule = solve_schedule(&every_morning(), &staff_group_list, 10, 4, &config).unwrap();
+
+        let labour = LabourLawChecker { staff_group_list: &staff_group_list, max_consecutive_days: Some(3), min_days_off_per_week: Some(2) };
+        assert!(labour.check_rule(&schedule).is_ok());
+        assert!(DoubleBookingChecker().check_rule(&schedule).is_ok());
+        let coverage = CoverageChecker { days: (0..7).collect(), slots: vec![Slot::Morning], min: Some(1), max: Some(1) };
+        assert!(coverage.check_rule(&schedule).is_ok());
+
+        let first_week = schedule.week(10).unwrap();
+        assert_ne!(first_week.0[0].shift_morning[0].get_id(), 0);
+        assert_eq!(first_week.0[2].shift_morning[0].get_id(), 1);
+
+        // 28 holes shared by 3 staff
+        let counts = AssignmentCounts::from_schedule(&schedule);
+        let totals: Vec<usize> = (0..3).map(|id| counts.total(0, id)).collect();
+        assert!(totals.iter().all(|t| (9..=10).contains(t)), "{:?}", totals);
+    }
+
+    #[test]
+    fn infeasible_and_rotation() {
+        let staff_group_list = staff_group_list(1);
+        let config = SolverConfig { max_consecutive_days: Some(3), ..SolverConfig::default() };
+        let e = solve_schedule(&every_morning(), &staff_group_list, 0, 1, &config).err().unwrap();
+        assert_eq!(e.reason, CauseOfSolveErr::Infeasible { week: 0, day: 3, slot: Slot::Morning });
+
+        // without constraints the generator choice is free
+        let staff_group_list = self::staff_group_list(2);
+        let schedule = Generator::Rotation.generate(&every_morning(), &staff_group_list, 0, 1, None, &[]).unwrap();
+        assert_eq!(schedule.weeks.len(), 1);
+        let schedule = Generator::Solver(SolverConfig::default()).generate(&every_morning(), &staff_group_list, 0, 1, None, &[]).unwrap();
+        assert!(schedule.weeks[0].0.iter().all(|d| d.shift_morning.len() == 1));
+    }
+
+    #[test]
+    fn hour_limits_and_contract_maximums() {
+        let mut staff_group = StaffGroup::new("group a");
+        let contract = Contract { max_days_per_week: Some(2), ..Contract::default() };
+        staff_group.add_staff_with_profile("staff0", StaffProfile { contract: Some(contract), ..StaffProfile::default() });
+        for i in 1..4 {
+            staff_group.add_staff(&format!("staff{}", i));
+        }
+        let mut staff_group_list = StaffGroupList::new();
+        staff_group_list.add_staff_group(staff_group);
+        let every_slot = WeekRuleTable(vec![WeekRule(std::array::from_fn(|_| day_rule(&[(0, 0)], &[(0, 1)])))]);
+        // one 4 hour slot a day and 20 hours a week
+        let config = SolverConfig {
+            statutory_limits: Some(StatutoryLimits { daily_minutes: 240, weekly_minutes: 1200 }),
+            contract_maximums: true,
+            ..SolverConfig::default()
+        };
+        let schedule = solve_schedule(&every_slot, &staff_group_list, 0, 2, &config).unwrap();
+
+        for (week, week_shift) in schedule.weeks.iter().enumerate() {
+            let slots = |id: usize| {
+                week_shift.0.iter().flat_map(|d| d.shift_morning.iter().chain(&d.shift_afternoon)).filter(|s| s.get_id() == id).count()
+            };
+            assert!((0..4).all(|id| slots(id) <= 5), "week {}", week);
+            assert!(slots(0) <= 2, "week {}", week);
+            assert!(week_shift.0.iter().all(|d| d.shift_morning[0].get_id() != d.shift_afternoon[0].get_id()));
+        }
+    }
+}
@@ -0,0 +1,53 @@
+#[cfg(test)]
+mod swap_test {
+    use shift_calendar::config::*;
+    use shift_calendar::shift_gen::*;
+    use shift_calendar::swap::*;
+
+    const CONFIG: &str = r#"anchor = 2025-01-06
+
+[[group]]
+name = "A"
+staff = ["a0", "a1", "a2"]
+
+[[week]]
+mon = { morning = ["A:0"] }
+tue = { morning = ["A:1"] }
+wed = { morning = ["A:0"] }
+thu = { morning = ["A:2"] }
+
+[checks.labour]
+max_consecutive_days = 2
+"#;
+
+    fn hole(day: usize) -> HoleRef {
+        HoleRef { week: 0, day, slot: Slot::Morning, hole: 0 }
+    }
+
+    #[test]
+    fn giveaway_swap_and_candidates() {
+        let config = ShiftConfig::parse(CONFIG, None).unwrap();
+        let engine = SwapEngine::new(&config);
+        assert_eq!(engine.staff_of(hole(1)).unwrap().name, "a1");
+
+        // a0 would work mon to wed
+        let e = engine.check(&SwapProposal::Giveaway { hole: hole(1), group_id: 0, staff_id: 0 }).unwrap_err();
+        assert!(matches!(&e.reason, CauseOfSwapErr::Infeasible(v) if v.iter().all(|d| d.check == "labour")));
+        let e = engine.check(&SwapProposal::Giveaway { hole: hole(1), group_id: 0, staff_id: 1 }).unwrap_err();
+        assert_eq!(e.reason, CauseOfSwapErr::SameStaff);
+
+        let overrides = engine.check(&SwapProposal::Swap { a: hole(1), b: hole(3) }).unwrap();
+        assert_eq!(overrides, [
+            ShiftOverride { week: 0, day: 1, slot: Slot::Morning, hole: 0, group_id: 0, staff_id: 2 },
+            ShiftOverride { week: 0, day: 3, slot: Slot::Morning, hole: 0, group_id: 0, staff_id: 1 },
+        ]);
+        let applied = ShiftConfig::parse(&engine.apply(&SwapProposal::Swap { a: hole(1), b: hole(3) }).unwrap(), None).unwrap();
+        assert_eq!(applied.overrides, overrides);
+
+        let candidates = engine.candidates(hole(1)).unwrap();
+        let summary: Vec<(&str, bool, Vec<HoleRef>)> =
+            candidates.iter().map(|c| (c.staff.name.as_str(), c.can_take, c.swaps.clone())).collect();
+        // a0 can trade mon or wed for tue, not take it
+        assert_eq!(summary, [("a0", false, vec![hole(0), hole(2)]), ("a2", true, vec![hole(3)])]);
+    }
+}
@@ -0,0 +1,64 @@
+#[cfg(test)]
+mod synth_test {
+    use shift_calendar::rule_checker::*;
+    use shift_calendar::schedule_checker::*;
+    use shift_calendar::shift_gen::*;
+    use shift_calendar::synth::*;
+
+    fn staff_group_list(groups: &[(&str, usize)]) -> StaffGroupList {
+        let mut staff_group_list = StaffGroupList::new();
+        for (name, len) in groups {
+            let mut staff_group = StaffGroup::new(name);
+            for i in 0..*len {
+                staff_group.add_staff(&format!("{}{}", name, i));
+            }
+            staff_group_list.add_staff_group(staff_group);
+        }
+        staff_group_list
+    }
+
+    #[test]
+    fn synthesize_valid_table() {
+        let staff_group_list = staff_group_list(&[("A", 3), ("B", 4)]);
+        let request = SynthRequest {
+            needs: vec![
+                CoverageNeed { group_id: 0, days: vec![0, 1, 2, 3, 4], slots: vec![Slot::Morning], count: 1 },
+                CoverageNeed { group_id: 1, days: vec![5], slots: vec![Slot::Morning], count: 1 },
+            ],
+            quotas: vec![StaffQuota { group_id: Some(0), min_per_week: Some(1), max_per_week: Some(2) }],
+            cycle: None,
+        };
+        let week_rule_table = synthesize_rule_table(&staff_group_list, &request).ok().unwrap();
+        // group B needs 4 weeks to use each of its staff once
+        assert_eq!(week_rule_table.0.len(), 4);
+        assert!(week_rule_table.0.iter().all(|w| w.0[0].shift_morning.len() == 1 && w.0[5].shift_morning.len() == 1));
+
+        let data = (week_rule_table.clone(), staff_group_list.clone());
+        assert!(verify(&data, &[&BasicChecker()]).is_ok());
+
+        let schedule = DecidedSchedule::generate(&week_rule_table, &staff_group_list, 0, 12);
+        let quota = QuotaChecker { staff_group_list: &staff_group_list, group_id: Some(0), min_per_week: Some(1), max_per_week: Some(2) };
+        assert!(quota.check_rule(&schedule).is_ok());
+        assert!(DoubleBookingChecker().check_rule(&schedule).is_ok());
+    }
+
+    #[test]
+    fn report_every_reason() {
+        let staff_group_list = staff_group_list(&[("A", 2), ("B", 3), ("C", 1)]);
+        let request = SynthRequest {
+            needs: vec![
+                CoverageNeed { group_id: 0, days: vec![0], slots: vec![Slot::Morning], count: 3 },
+                CoverageNeed { group_id: 1, days: vec![0, 1, 2, 3, 4], slots: Slot::ALL.to_vec(), count: 1 },
+            ],
+            quotas: vec![StaffQuota { group_id: None, min_per_week: None, max_per_week: Some(2) }],
+            cycle: None,
+        };
+        let errors = synthesize_rule_table(&staff_group_list, &request).err().unwrap();
+        let reasons: Vec<(Option<usize>, CauseOfSynthErr)> = errors.into_iter().map(|e| (e.group_id, e.reason)).collect();
+        assert_eq!(reasons, [
+            (Some(0), CauseOfSynthErr::SlotOverCapacity { day: 0, slot: Slot::Morning, count: 3, staff: 2 }),
+            (Some(1), CauseOfSynthErr::QuotaUnreachable { holes_per_week: 10, staff: 3, min: 0, max: Some(2) }),
+            (Some(2), CauseOfSynthErr::NoHole),
+        ]);
+    }
+}
@@ -0,0 +1,126 @@
+#[cfg(test)]
+mod validate_test {
+    use shift_calendar::config::*;
+    use shift_calendar::validate::*;
+
+    const CONFIG: &str = r#"anchor = 2025-01-06
+
+[[group]]
+name = "A"
+staff = ["田中", "鈴木", "山本"]
+
+[[group]]
+name = "B"
+staff = ["佐藤", "高橋"]
+
+[[week]]
+mon = { morning = ["A:0", "B:0"], afternoon = ["B:1", "B:1"] }
+tue = { afternoon = ["A:1"] }
+
+[checks]
+basic = { severity = "warning" }
+
+[[checks.quota]]
+group = "B"
+max_per_week = 1
+
+[checks.labour]
+min_days_off_per_week = 7
+severity = "warning"
+"#;
+
+    #[test]
+    fn diagnostics() {
+        let config = ShiftConfig::parse(CONFIG, None).unwrap();
+        let diagnostics = validate(&config, 0, 1);
+        let summary: Vec<(Severity, &str, Option<usize>)> =
+            diagnostics.iter().map(|d| (d.severity, d.check, d.line)).collect();
+        assert_eq!(summary, [
+            // 山本 is never used
+            (Severity::Warning, "basic", None),
+            // 高橋 twice on monday afternoon
+            (Severity::Warning, "basic", Some(12)),
+            (Severity::Error, "quota", Some(18)),
+            (Severity::Warning, "labour", Some(22)),
+            (Severity::Warning, "labour", Some(22)),
+            (Severity::Warning, "labour", Some(22)),
+            (Severity::Warning, "labour", Some(22)),
+        ]);
+        assert_eq!(diagnostics[2].message, "高橋 has 2 assignments in the week, quota is at most 1");
+        assert_eq!(diagnostics[2].to_string(), "error[quota]: 高橋 has 2 assignments in the week, quota is at most 1 (week 0)");
+    }
+
+    #[test]
+    fn staff_table_severity_and_location() {
+        let source = r#"anchor = 2025-01-06
+
+[[group]]
+name = "A"
+staff = ["田中", "鈴木"]
+
+[[contract]]
+staff = ["A:0"]
+max_days_per_week = 0
+severity = "warning"
+
+[[availability]]
+staff = "A:0"
+days = ["mon"]
+severity = "warning"
+
+[[availability]]
+staff = "A:1"
+days = ["mon"]
+
+[[absence]]
+staff = "A:0"
+from = 2025-01-06
+
+[[week]]
+mon = { morning = ["A:0"] }
+"#;
+        let config = ShiftConfig::parse(source, None).unwrap();
+        let found: Vec<(&str, Severity, Option<usize>, Option<usize>)> = validate(&config, 0, 2)
+            .iter()
+            .filter(|d| d.check != "basic")
+            .map(|d| (d.check, d.severity, d.line, d.week))
+            .collect();
+        // the contract at its table, the rest at the monday hole
+        assert_eq!(found, [
+            ("contract", Severity::Warning, Some(7), Some(0)),
+            ("availability", Severity::Warning, Some(26), Some(0)),
+            ("availability", Severity::Error, Some(26), Some(1)),
+            ("absence", Severity::Error, Some(26), Some(0)),
+        ]);
+    }
+
+    #[test]
+    fn absence_severity() {
+        let source = r#"anchor = 2025-01-06
+
+[[group]]
+name = "A"
+staff = ["田中"]
+
+[[absence]]
+staff = "A:0"
+from = 2025-01-13
+
+[[leave]]
+staff = "A:0"
+from = 2025-01-06
+severity = "warning"
+
+[[week]]
+mon = { morning = ["A:0"] }
+"#;
+        let config = ShiftConfig::parse(source, None).unwrap();
+        // nobody can take the hole of 田中, the absence comes before the leave
+        let found: Vec<(&str, Severity, Option<usize>)> = validate(&config, 0, 2)
+            .iter()
+            .filter(|d| d.check == "absence")
+            .map(|d| (d.check, d.severity, d.week))
+            .collect();
+        assert_eq!(found, [("absence", Severity::Error, Some(1)), ("absence", Severity::Warning, Some(0))]);
+    }
+}
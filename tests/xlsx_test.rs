@@ -0,0 +1,44 @@
+#[cfg(test)]
+mod xlsx_test {
+    use chrono::NaiveDate;
+    use shift_calendar::shift_gen::*;
+    use shift_calendar::xlsx::*;
+
+    fn day_rule<'a>(m: &[(usize, usize)], a: &[(usize, usize)]) -> DayRule<'a, Incomplete> {
+        DayRule {
+            shift_morning: m.iter().map(|&(g, i)| ShiftHoll::new(g, i)).collect(),
+            shift_afternoon: a.iter().map(|&(g, i)| ShiftHoll::new(g, i)).collect(),
+        }
+    }
+
+    #[test]
+    fn sheet_per_month() {
+        let week_rule_table = WeekRuleTable(vec![WeekRule([
+            day_rule(&[(0, 0), (1, 0)], &[(0, 1)]),
+            day_rule(&[], &[(1, 1)]),
+            day_rule(&[], &[]),
+            day_rule(&[], &[]),
+            day_rule(&[], &[]),
+            day_rule(&[], &[]),
+            day_rule(&[], &[]),
+        ])]);
+        let mut staff_group_a = StaffGroup::new("group a");
+        staff_group_a.add_staff("田中");
+        staff_group_a.add_staff("鈴木");
+        let mut staff_group_b = StaffGroup::new("group b");
+        staff_group_b.add_staff("佐藤");
+        staff_group_b.add_staff("高橋");
+        let mut staff_group_list = StaffGroupList::new();
+        staff_group_list.add_staff_group(staff_group_a);
+        staff_group_list.add_staff_group(staff_group_b);
+
+        let shift = gen_shift(&week_rule_table, &staff_group_list, 0, 5);
+        let start_date = NaiveDate::from_ymd_opt(2025, 1, 20).unwrap();
+        let mut workbook = xlsx_workbook(&shift, &staff_group_list, start_date).unwrap();
+        let names: Vec<String> = workbook.worksheets().iter().map(|w| w.name()).collect();
+        assert_eq!(names, ["2025-01", "2025-02", "Summary"]);
+
+        let buffer = xlsx_buffer(&shift, &staff_group_list, start_date).unwrap();
+        assert_eq!(&buffer[..2], b"PK");
+    }
+}